@@ -1,11 +1,20 @@
-//! API to read and use bloom filters
+//! API to read, write and use bloom filters
 mod hash;
+mod probe;
 mod read;
 mod split_block;
 
 pub use hash::{hash_byte, hash_native};
+pub use probe::may_contain;
+pub(crate) use probe::may_contain_scalars;
 pub use read::read;
-pub use split_block::{insert, is_in_set};
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use read::read_async;
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub use read::read_with_decryption;
+pub use split_block::{insert, is_in_set, optimal_num_bytes};
 
 #[cfg(test)]
 mod tests {
@@ -68,4 +77,93 @@ mod tests {
         ];
         assert_eq!(bitset, expected);
     }
+
+    #[test]
+    fn num_bytes() {
+        assert_eq!(optimal_num_bytes(0, 0.01), 32);
+        assert_eq!(optimal_num_bytes(10, 0.01), 32);
+        assert_eq!(optimal_num_bytes(1000, 0.01), 2048);
+        assert_eq!(optimal_num_bytes(1_000_000, 0.01), 2 * 1024 * 1024);
+        assert_eq!(optimal_num_bytes(usize::MAX, 0.01), 128 * 1024 * 1024);
+
+        // a filter sized from the ndv and fpp respects the fpp
+        let ndv = 1000;
+        let mut bitset = vec![0; optimal_num_bytes(ndv, 0.01)];
+        (0..ndv as i64).for_each(|x| insert(&mut bitset, hash_native(x)));
+        let false_positives = (ndv as i64..2 * ndv as i64)
+            .filter(|x| is_in_set(&bitset, hash_native(*x)))
+            .count();
+        assert!(false_positives < ndv / 100 * 2);
+    }
+
+    #[test]
+    fn probe() {
+        use crate::scalar::Scalar;
+        use crate::schema::types::{
+            IntegerType, PhysicalType, PrimitiveLogicalType, PrimitiveType,
+        };
+
+        let with_logical = |physical_type, logical_type| {
+            let mut primitive_type = PrimitiveType::from_physical("c".to_string(), physical_type);
+            primitive_type.logical_type = logical_type;
+            primitive_type
+        };
+        let filter = |hashes: &[u64]| {
+            let mut bitset = vec![0; 32];
+            hashes.iter().for_each(|hash| insert(&mut bitset, *hash));
+            bitset
+        };
+
+        // unsigned integers are hashed as their bits
+        let uint32 = with_logical(
+            PhysicalType::Int32,
+            Some(PrimitiveLogicalType::Integer(IntegerType::UInt32)),
+        );
+        let bitset = filter(&[hash_native(-1i32)]);
+        let values = [Scalar::Integer(u32::MAX as i128)];
+        assert!(may_contain_scalars(&bitset, &uint32, &values));
+        let values = [Scalar::Integer(-1)];
+        assert!(!may_contain_scalars(&bitset, &uint32, &values));
+
+        // decimals are sign-extended to the size of the column
+        let decimal = with_logical(
+            PhysicalType::FixedLenByteArray(4),
+            Some(PrimitiveLogicalType::Decimal(9, 2)),
+        );
+        let bitset = filter(&[hash_byte([0xff, 0xff, 0xff, 0xfe])]);
+        assert!(may_contain_scalars(
+            &bitset,
+            &decimal,
+            &[Scalar::Integer(-2)]
+        ));
+        assert!(!may_contain_scalars(
+            &bitset,
+            &decimal,
+            &[Scalar::Integer(2)]
+        ));
+
+        // both zeros are equal
+        let double = with_logical(PhysicalType::Double, None);
+        let bitset = filter(&[hash_native(-0.0f64)]);
+        assert!(may_contain_scalars(&bitset, &double, &[Scalar::Float(0.0)]));
+        assert!(!may_contain_scalars(
+            &bitset,
+            &double,
+            &[Scalar::Float(f64::NAN)]
+        ));
+
+        // values that cannot be represented are not contained
+        let float = with_logical(PhysicalType::Float, None);
+        let bitset = filter(&[hash_native(0.1f32)]);
+        assert!(may_contain_scalars(
+            &bitset,
+            &float,
+            &[Scalar::Float(0.1f32 as f64)]
+        ));
+        assert!(!may_contain_scalars(&bitset, &float, &[Scalar::Float(0.1)]));
+
+        // INT96 cannot be probed
+        let int96 = with_logical(PhysicalType::Int96, None);
+        assert!(may_contain_scalars(&bitset, &int96, &[Scalar::Integer(1)]));
+    }
 }
@@ -0,0 +1,116 @@
+use crate::error::Result;
+use crate::metadata::{ColumnChunkMetaData, SortOrder};
+use crate::predicate::Literal;
+use crate::scalar::{is_float16, sort_order, Scalar};
+use crate::schema::types::{PhysicalType, PrimitiveType};
+use crate::types::{f32_to_float16, float16_to_f32};
+use crate::write::decimal_to_bytes;
+
+use super::{hash_byte, hash_native, is_in_set};
+
+/// The hashes of `value` and, when it is zero, of its negative, since `0.0 == -0.0` but their
+/// representations differ. `hash` returns `None` when the column cannot represent a value.
+fn float_hashes<F: Fn(f64) -> Option<u64>>(value: f64, hash: F) -> Vec<u64> {
+    let values = if value == 0.0 {
+        vec![0.0, -0.0]
+    } else {
+        vec![value]
+    };
+    values.into_iter().filter_map(hash).collect()
+}
+
+/// The hashes of the plain representations of `value` in a column of `primitive_type`.
+/// It is empty when no value of the column equals `value` and `None` when the column cannot be
+/// probed for `value`.
+fn hashes(value: &Scalar, primitive_type: &PrimitiveType) -> Option<Vec<u64>> {
+    let hashes = match (value, primitive_type.physical_type) {
+        (Scalar::Integer(value), PhysicalType::Int32) => match sort_order(primitive_type) {
+            SortOrder::Signed => i32::try_from(*value).ok().map(hash_native),
+            SortOrder::Unsigned => u32::try_from(*value)
+                .ok()
+                .map(|value| hash_native(value as i32)),
+            SortOrder::Undefined => return None,
+        },
+        (Scalar::Integer(value), PhysicalType::Int64) => match sort_order(primitive_type) {
+            SortOrder::Signed => i64::try_from(*value).ok().map(hash_native),
+            SortOrder::Unsigned => u64::try_from(*value)
+                .ok()
+                .map(|value| hash_native(value as i64)),
+            SortOrder::Undefined => return None,
+        },
+        // decimals of variable length can be stored with any number of (sign-extended) bytes
+        (Scalar::Integer(_), PhysicalType::ByteArray) => return None,
+        (Scalar::Integer(value), PhysicalType::FixedLenByteArray(size)) => {
+            decimal_to_bytes(*value, Some(size)).map(hash_byte)
+        }
+        (Scalar::Float(value), PhysicalType::Float) => {
+            return Some(float_hashes(*value, |value| {
+                (value as f32 as f64 == value).then(|| hash_native(value as f32))
+            }))
+        }
+        (Scalar::Float(value), PhysicalType::Double) => {
+            return Some(float_hashes(*value, |value| {
+                (!value.is_nan()).then(|| hash_native(value))
+            }))
+        }
+        (Scalar::Float(value), PhysicalType::FixedLenByteArray(_))
+            if is_float16(primitive_type) =>
+        {
+            return Some(float_hashes(*value, |value| {
+                let half = f32_to_float16(value as f32);
+                (float16_to_f32(half) as f64 == value).then(|| hash_byte(half.to_le_bytes()))
+            }))
+        }
+        (Scalar::Bytes(value), PhysicalType::ByteArray) => Some(hash_byte(value)),
+        (Scalar::Bytes(value), PhysicalType::FixedLenByteArray(size)) => {
+            (value.len() == size).then(|| hash_byte(value))
+        }
+        // booleans and INT96 have no bloom filters
+        _ => return None,
+    };
+    Some(hashes.into_iter().collect())
+}
+
+/// Returns whether a column of `primitive_type` whose bloom filter is `bitset` may contain
+/// one of `values`
+pub(crate) fn may_contain_scalars(
+    bitset: &[u8],
+    primitive_type: &PrimitiveType,
+    values: &[Scalar],
+) -> bool {
+    values.iter().any(|value| {
+        bitset.is_empty()
+            || hashes(value, primitive_type)
+                .map(|hashes| hashes.into_iter().any(|hash| is_in_set(bitset, hash)))
+                .unwrap_or(true)
+    })
+}
+
+/// Returns whether `column` may contain a value equal to one of `values` according to its
+/// bloom filter, `bitset`. `false` guarantees that none of `values` is in the column chunk.
+///
+/// `bitset` is read by [`read`](super::read) (or `read_async`), which reuses its allocation
+/// across column chunks. It is empty when the column chunk has no bloom filter, in which
+/// case all values may be contained.
+///
+/// The literals are hashed in the plain representation of the column, e.g. `UINT_32` as the
+/// bits of an `INT32`, strings as their UTF-8 bytes and `DECIMAL` as its unscaled integer
+/// or big-endian bytes. Literals that cannot be represented in the column (e.g. out of range)
+/// are never contained.
+/// # Errors
+/// Errors iff a literal cannot be compared with the values of `column`.
+pub fn may_contain(
+    column: &ColumnChunkMetaData,
+    bitset: &[u8],
+    values: &[Literal],
+) -> Result<bool> {
+    let values = values
+        .iter()
+        .map(|value| Scalar::try_from_literal(value, column.descriptor()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(may_contain_scalars(
+        bitset,
+        &column.descriptor().descriptor.primitive_type,
+        &values,
+    ))
+}
@@ -1,21 +1,36 @@
 use std::io::{Read, Seek, SeekFrom};
 
+#[cfg(feature = "async")]
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+#[cfg(feature = "async")]
+use parquet_format_safe::thrift::protocol::TCompactInputStreamProtocol;
 use parquet_format_safe::{
     thrift::protocol::TCompactInputProtocol, BloomFilterAlgorithm, BloomFilterCompression,
     BloomFilterHeader, SplitBlockAlgorithm, Uncompressed,
 };
 
+#[cfg(feature = "encryption")]
+use crate::encryption::ColumnDecryptor;
+use crate::read::check_not_encrypted;
 use crate::{error::Error, metadata::ColumnChunkMetaData};
 
+/// Whether the bitset of a bloom filter with `header` can be used
+fn is_supported(header: &BloomFilterHeader) -> bool {
+    header.algorithm == BloomFilterAlgorithm::BLOCK(SplitBlockAlgorithm {})
+        && header.compression == BloomFilterCompression::UNCOMPRESSED(Uncompressed {})
+}
+
 /// Reads the bloom filter associated to [`ColumnChunkMetaData`] into `bitset`.
 /// Results in an empty `bitset` if there is no associated bloom filter or the algorithm is not supported.
 /// # Error
-/// Errors if the column contains no metadata or the filter can't be read or deserialized.
+/// Errors if the column contains no metadata, is encrypted (see [`read_with_decryption`]) or
+/// the filter can't be read or deserialized.
 pub fn read<R: Read + Seek>(
     column_metadata: &ColumnChunkMetaData,
     mut reader: &mut R,
     bitset: &mut Vec<u8>,
 ) -> Result<(), Error> {
+    check_not_encrypted(column_metadata, "Reading bloom filters")?;
     let offset = column_metadata.metadata().bloom_filter_offset;
 
     let offset = if let Some(offset) = offset {
@@ -30,20 +45,89 @@ pub fn read<R: Read + Seek>(
     let mut prot = TCompactInputProtocol::new(&mut reader, usize::MAX); // max is ok since `BloomFilterHeader` never allocates
     let header = BloomFilterHeader::read_from_in_protocol(&mut prot)?;
 
-    if header.algorithm != BloomFilterAlgorithm::BLOCK(SplitBlockAlgorithm {}) {
-        bitset.clear();
+    bitset.clear();
+    if !is_supported(&header) {
         return Ok(());
     }
-    if header.compression != BloomFilterCompression::UNCOMPRESSED(Uncompressed {}) {
+
+    let length: usize = header.num_bytes.try_into()?;
+
+    bitset.try_reserve(length)?;
+    reader.by_ref().take(length as u64).read_to_end(bitset)?;
+
+    Ok(())
+}
+
+/// Reads the bloom filter associated to the encrypted [`ColumnChunkMetaData`] into `bitset`,
+/// decrypting it with the column chunk's `decryptor`.
+/// Results in an empty `bitset` if there is no associated bloom filter or the algorithm is not supported.
+/// # Error
+/// Errors if the column contains no metadata or the filter can't be read, decrypted or deserialized.
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub fn read_with_decryption<R: Read + Seek>(
+    column_metadata: &ColumnChunkMetaData,
+    reader: &mut R,
+    bitset: &mut Vec<u8>,
+    decryptor: &ColumnDecryptor,
+) -> Result<(), Error> {
+    let offset = column_metadata.metadata().bloom_filter_offset;
+
+    let offset = if let Some(offset) = offset {
+        offset as u64
+    } else {
         bitset.clear();
         return Ok(());
+    };
+    reader.seek(SeekFrom::Start(offset))?;
+
+    let header = decryptor.read_bloom_filter_header(reader)?;
+
+    bitset.clear();
+    if !is_supported(&header) {
+        return Ok(());
     }
 
     let length: usize = header.num_bytes.try_into()?;
+    decryptor.read_bloom_filter_bitset(reader, length, bitset)
+}
+
+/// Asynchronously reads the bloom filter associated to [`ColumnChunkMetaData`] into `bitset`.
+/// Results in an empty `bitset` if there is no associated bloom filter or the algorithm is not supported.
+/// # Error
+/// Errors if the column contains no metadata, is encrypted or the filter can't be read or
+/// deserialized.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn read_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+    column_metadata: &ColumnChunkMetaData,
+    reader: &mut R,
+    bitset: &mut Vec<u8>,
+) -> Result<(), Error> {
+    check_not_encrypted(column_metadata, "Reading bloom filters")?;
+    let offset = column_metadata.metadata().bloom_filter_offset;
+
+    let offset = if let Some(offset) = offset {
+        offset as u64
+    } else {
+        bitset.clear();
+        return Ok(());
+    };
+    reader.seek(SeekFrom::Start(offset)).await?;
+
+    // deserialize header
+    let mut prot = TCompactInputStreamProtocol::new(&mut *reader, usize::MAX); // max is ok since `BloomFilterHeader` never allocates
+    let header = BloomFilterHeader::stream_from_in_protocol(&mut prot).await?;
 
     bitset.clear();
+    if !is_supported(&header) {
+        return Ok(());
+    }
+
+    let length: usize = header.num_bytes.try_into()?;
+
     bitset.try_reserve(length)?;
-    reader.by_ref().take(length as u64).read_to_end(bitset)?;
+    reader.take(length as u64).read_to_end(bitset).await?;
 
     Ok(())
 }
@@ -80,3 +80,23 @@ pub fn insert(bitset: &mut [u8], hash: u64) {
         unload_block(block_mask, mut_slice)
     }
 }
+
+/// The minimum size of a bitset in bytes, corresponding to a single block
+const MIN_NUM_BYTES: usize = 32;
+/// The maximum size of a bitset in bytes
+const MAX_NUM_BYTES: usize = 128 * 1024 * 1024;
+
+/// Returns the number of bytes of a bitset whose false-positive rate is at most `fpp` when
+/// `ndv` distinct values are inserted in it.
+///
+/// The result is a power of two between 32 bytes (a single block) and 128MiB, so that it can be
+/// used to initialize a bitset, e.g. `vec![0; optimal_num_bytes(ndv, fpp)]`.
+pub fn optimal_num_bytes(ndv: usize, fpp: f64) -> usize {
+    // see https://github.com/apache/parquet-format/blob/master/BloomFilter.md#sizing-an-sbbf
+    let num_bits = -8.0 * ndv as f64 / (1.0 - fpp.powf(1.0 / 8.0)).ln();
+    // float to int casts saturate, so this is valid for any `ndv` and `fpp`
+    let num_bytes = (num_bits / 8.0).ceil() as usize;
+    num_bytes
+        .clamp(MIN_NUM_BYTES, MAX_NUM_BYTES)
+        .next_power_of_two()
+}
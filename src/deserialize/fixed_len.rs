@@ -1,5 +1,5 @@
 use crate::{
-    encoding::hybrid_rle,
+    encoding::{byte_stream_split, hybrid_rle},
     error::Error,
     page::{split_buffer, DataPage},
     parquet_bridge::{Encoding, Repetition},
@@ -59,6 +59,11 @@ pub enum FixedLenBinaryPageState<'a, P> {
     Required(FixexBinaryIter<'a>),
     RequiredDictionary(Dictionary<'a, P>),
     OptionalDictionary(utils::DefLevelsDecoder<'a>, Dictionary<'a, P>),
+    OptionalByteStreamSplit(
+        utils::DefLevelsDecoder<'a>,
+        byte_stream_split::FixedLenDecoder<'a>,
+    ),
+    RequiredByteStreamSplit(byte_stream_split::FixedLenDecoder<'a>),
 }
 
 impl<'a, P> FixedLenBinaryPageState<'a, P> {
@@ -101,6 +106,20 @@ impl<'a, P> FixedLenBinaryPageState<'a, P> {
 
                 Ok(Self::Required(values))
             }
+            (Encoding::ByteStreamSplit, _, true) => {
+                let (_, _, values) = split_buffer(page)?;
+
+                let validity = utils::DefLevelsDecoder::try_new(page)?;
+                let values = byte_stream_split::FixedLenDecoder::try_new(values, size)?;
+
+                Ok(Self::OptionalByteStreamSplit(validity, values))
+            }
+            (Encoding::ByteStreamSplit, _, false) => {
+                let (_, _, values) = split_buffer(page)?;
+                let values = byte_stream_split::FixedLenDecoder::try_new(values, size)?;
+
+                Ok(Self::RequiredByteStreamSplit(values))
+            }
             _ => Err(Error::FeatureNotSupported(format!(
                 "Viewing page for encoding {:?} for binary type",
                 page.encoding(),
@@ -108,3 +127,78 @@ impl<'a, P> FixedLenBinaryPageState<'a, P> {
         }
     }
 }
+
+fn decode_float16(value: &[u8]) -> u16 {
+    u16::from_le_bytes([value[0], value[1]])
+}
+
+/// An iterator over the non-null values of a page of a `FLOAT16` column, IEEE 754
+/// half-precision floats stored as `FIXED_LEN_BYTE_ARRAY(2)`, yielding their bits.
+#[derive(Debug)]
+pub enum Float16Values<'a, P> {
+    /// PLAIN-encoded values
+    Plain(FixexBinaryIter<'a>),
+    /// Dictionary-encoded values, whose dictionary is the PLAIN-encoded values of the
+    /// dictionary page
+    Dictionary(Dictionary<'a, P>),
+    /// BYTE_STREAM_SPLIT-encoded values
+    ByteStreamSplit(byte_stream_split::FixedLenDecoder<'a>),
+}
+
+impl<'a, P> Float16Values<'a, P> {
+    /// Tries to create [`Float16Values`] from `page` and its dictionary `dict`, the buffer of
+    /// the dictionary page, returning the definition levels of optional pages alongside.
+    /// # Error
+    /// Errors iff the page is not a page of `FIXED_LEN_BYTE_ARRAY(2)` or its encoding is not supported
+    pub fn try_new(
+        page: &'a DataPage,
+        dict: Option<P>,
+    ) -> Result<(Option<utils::DefLevelsDecoder<'a>>, Self), Error> {
+        if page.descriptor.primitive_type.physical_type != PhysicalType::FixedLenByteArray(2) {
+            return Err(Error::InvalidParameter(
+                "Float16Values must be initialized by pages of FixedLenByteArray(2)".to_string(),
+            ));
+        }
+
+        Ok(match FixedLenBinaryPageState::try_new(page, dict)? {
+            FixedLenBinaryPageState::Optional(validity, values) => {
+                (Some(validity), Self::Plain(values))
+            }
+            FixedLenBinaryPageState::Required(values) => (None, Self::Plain(values)),
+            FixedLenBinaryPageState::RequiredDictionary(dict) => (None, Self::Dictionary(dict)),
+            FixedLenBinaryPageState::OptionalDictionary(validity, dict) => {
+                (Some(validity), Self::Dictionary(dict))
+            }
+            FixedLenBinaryPageState::OptionalByteStreamSplit(validity, values) => {
+                (Some(validity), Self::ByteStreamSplit(values))
+            }
+            FixedLenBinaryPageState::RequiredByteStreamSplit(values) => {
+                (None, Self::ByteStreamSplit(values))
+            }
+        })
+    }
+}
+
+impl<'a, P: AsRef<[u8]>> Iterator for Float16Values<'a, P> {
+    type Item = Result<u16, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Plain(values) => values.next().map(|value| Ok(decode_float16(value))),
+            Self::Dictionary(dict) => dict.indexes.next().map(|index| {
+                let start = index? as usize * 2;
+                dict.dict
+                    .as_ref()
+                    .get(start..start + 2)
+                    .map(decode_float16)
+                    .ok_or_else(|| Error::oos("The dictionary index of a value is out of bounds"))
+            }),
+            Self::ByteStreamSplit(values) => {
+                let mut value = [0; 2];
+                values
+                    .decode_next(&mut value)
+                    .then(|| Ok(u16::from_le_bytes(value)))
+            }
+        }
+    }
+}
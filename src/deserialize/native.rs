@@ -1,5 +1,5 @@
 use crate::{
-    encoding::hybrid_rle,
+    encoding::{byte_stream_split, hybrid_rle},
     error::Error,
     page::{split_buffer, DataPage},
     parquet_bridge::{Encoding, Repetition},
@@ -63,6 +63,13 @@ where
     RequiredDictionary(Dictionary<'a, P>),
     /// A page of optional, dictionary-encoded values
     OptionalDictionary(utils::DefLevelsDecoder<'a>, Dictionary<'a, P>),
+    /// A page of optional, byte-stream-split-encoded values
+    OptionalByteStreamSplit(
+        utils::DefLevelsDecoder<'a>,
+        byte_stream_split::Decoder<'a, T>,
+    ),
+    /// A page of required, byte-stream-split-encoded values
+    RequiredByteStreamSplit(byte_stream_split::Decoder<'a, T>),
 }
 
 impl<'a, T: NativeType, P> NativePageState<'a, T, P> {
@@ -90,6 +97,17 @@ impl<'a, T: NativeType, P> NativePageState<'a, T, P> {
                 Ok(Self::Optional(validity, values))
             }
             (Encoding::Plain, _, false) => native_cast(page).map(Self::Required),
+            (Encoding::ByteStreamSplit, _, true) => {
+                let validity = utils::DefLevelsDecoder::try_new(page)?;
+                let (_, _, values) = split_buffer(page)?;
+                let values = byte_stream_split::Decoder::try_new(values)?;
+
+                Ok(Self::OptionalByteStreamSplit(validity, values))
+            }
+            (Encoding::ByteStreamSplit, _, false) => {
+                let (_, _, values) = split_buffer(page)?;
+                byte_stream_split::Decoder::try_new(values).map(Self::RequiredByteStreamSplit)
+            }
             _ => Err(Error::FeatureNotSupported(format!(
                 "Viewing page for encoding {:?} for native type {}",
                 page.encoding(),
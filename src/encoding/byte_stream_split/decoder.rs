@@ -0,0 +1,148 @@
+use std::marker::PhantomData;
+
+use crate::error::{Error, Result};
+use crate::types::NativeType;
+
+fn check_length(values: &[u8], size: usize) -> Result<usize> {
+    if size == 0 || values.len() % size != 0 {
+        return Err(Error::oos(
+            "A BYTE_STREAM_SPLIT page's len must be a multiple of the type's size",
+        ));
+    }
+    Ok(values.len() / size)
+}
+
+/// Decodes [BYTE_STREAM_SPLIT](https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9)
+/// values of [`NativeType`].
+/// # Implementation
+/// This struct does not allocate on the heap.
+/// # Example
+/// ```
+/// use parquet2::encoding::byte_stream_split::Decoder;
+///
+/// // 1.0f32 and 2.0f32, whose little-endian bytes are [0, 0, 128, 63] and [0, 0, 0, 64]
+/// let data = &[0, 0, 0, 0, 128, 0, 63, 64];
+///
+/// let decoder = Decoder::<f32>::try_new(data).unwrap();
+/// assert_eq!(decoder.collect::<Vec<_>>(), vec![1.0, 2.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Decoder<'a, T: NativeType> {
+    values: &'a [u8],
+    num_values: usize,
+    index: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: NativeType> Decoder<'a, T> {
+    /// Returns a new [`Decoder`].
+    /// # Error
+    /// Errors iff `values.len()` is not a multiple of the size of `T`.
+    pub fn try_new(values: &'a [u8]) -> Result<Self> {
+        let num_values = check_length(values, std::mem::size_of::<T>())?;
+        Ok(Self {
+            values,
+            num_values,
+            index: 0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a, T: NativeType> Iterator for Decoder<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.num_values {
+            return None;
+        }
+        let size = std::mem::size_of::<T>();
+        // all native types are at most 12 bytes long
+        let mut bytes = [0u8; 16];
+        bytes
+            .iter_mut()
+            .take(size)
+            .enumerate()
+            .for_each(|(k, byte)| *byte = self.values[k * self.num_values + self.index]);
+        self.index += 1;
+
+        let bytes: T::Bytes = match bytes[..size].try_into() {
+            Ok(v) => v,
+            Err(_) => unreachable!(),
+        };
+        Some(T::from_le_bytes(bytes))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.num_values - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: NativeType> ExactSizeIterator for Decoder<'a, T> {}
+
+/// Decodes BYTE_STREAM_SPLIT values of `size` bytes each (e.g. FIXED_LEN_BYTE_ARRAY).
+/// # Implementation
+/// This struct does not allocate on the heap: values are decoded into buffers of the caller.
+#[derive(Debug, Clone)]
+pub struct FixedLenDecoder<'a> {
+    values: &'a [u8],
+    size: usize,
+    num_values: usize,
+    index: usize,
+}
+
+impl<'a> FixedLenDecoder<'a> {
+    /// Returns a new [`FixedLenDecoder`].
+    /// # Error
+    /// Errors iff `size` is zero or `values.len()` is not a multiple of `size`.
+    pub fn try_new(values: &'a [u8], size: usize) -> Result<Self> {
+        let num_values = check_length(values, size)?;
+        Ok(Self {
+            values,
+            size,
+            num_values,
+            index: 0,
+        })
+    }
+
+    /// The number of values that remain to be decoded
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.num_values - self.index
+    }
+
+    /// Whether all values were decoded
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Decodes the next value into the first `size` bytes of `value`.
+    /// Returns `false`, leaving `value` untouched, iff all values were decoded.
+    /// # Panics
+    /// Panics iff `value` has less than `size` bytes.
+    #[inline]
+    pub fn decode_next(&mut self, value: &mut [u8]) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        value[..self.size]
+            .iter_mut()
+            .enumerate()
+            .for_each(|(k, byte)| *byte = self.values[k * self.num_values + self.index]);
+        self.index += 1;
+        true
+    }
+
+    /// Decodes all remaining values into `buffer` as plain-encoded values, back to back.
+    /// This consumes the decoder.
+    pub fn decode(self, buffer: &mut Vec<u8>) {
+        buffer.reserve(self.len() * self.size);
+        (self.index..self.num_values).for_each(|i| {
+            (0..self.size).for_each(|k| buffer.push(self.values[k * self.num_values + i]))
+        });
+    }
+}
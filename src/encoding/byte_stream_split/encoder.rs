@@ -0,0 +1,52 @@
+use crate::error::{Error, Result};
+use crate::types::NativeType;
+
+/// Encodes `values`, a sequence of plain-encoded (little-endian) items of `size` bytes each,
+/// according to [BYTE_STREAM_SPLIT](https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9),
+/// extending `buffer` with the result.
+/// # Error
+/// Errors iff `size` is zero or `values.len()` is not a multiple of `size`.
+pub fn encode(values: &[u8], size: usize, buffer: &mut Vec<u8>) -> Result<()> {
+    if size == 0 || values.len() % size != 0 {
+        return Err(Error::InvalidParameter(format!(
+            "BYTE_STREAM_SPLIT requires the number of bytes ({}) to be a multiple of the type's size ({})",
+            values.len(),
+            size
+        )));
+    }
+    let num_values = values.len() / size;
+
+    let start = buffer.len();
+    buffer.resize(start + values.len(), 0);
+    let streams = &mut buffer[start..];
+
+    // the k-th byte of the i-th value goes to position `k * num_values + i`
+    values
+        .chunks_exact(size)
+        .enumerate()
+        .for_each(|(i, value)| {
+            value
+                .iter()
+                .enumerate()
+                .for_each(|(k, byte)| streams[k * num_values + i] = *byte)
+        });
+    Ok(())
+}
+
+/// Encodes a slice of [`NativeType`] according to BYTE_STREAM_SPLIT, extending `buffer`.
+pub fn encode_native<T: NativeType>(values: &[T], buffer: &mut Vec<u8>) {
+    let num_values = values.len();
+
+    let start = buffer.len();
+    buffer.resize(start + std::mem::size_of_val(values), 0);
+    let streams = &mut buffer[start..];
+
+    values.iter().enumerate().for_each(|(i, value)| {
+        value
+            .to_le_bytes()
+            .as_ref()
+            .iter()
+            .enumerate()
+            .for_each(|(k, byte)| streams[k * num_values + i] = *byte)
+    });
+}
@@ -0,0 +1,74 @@
+// See https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9
+mod decoder;
+mod encoder;
+
+pub use decoder::{Decoder, FixedLenDecoder};
+pub use encoder::{encode, encode_native};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn basic() -> Result<(), Error> {
+        let data = vec![1.0f32, 2.0, -3.5, f32::MAX];
+
+        let mut buffer = vec![];
+        encode_native(&data, &mut buffer);
+
+        // the first stream contains the first byte of every value
+        let expected_first = data.iter().map(|x| x.to_le_bytes()[0]).collect::<Vec<_>>();
+        assert_eq!(&buffer[..4], expected_first.as_slice());
+
+        let result = Decoder::<f32>::try_new(&buffer)?.collect::<Vec<_>>();
+        assert_eq!(result, data);
+        Ok(())
+    }
+
+    #[test]
+    fn native() -> Result<(), Error> {
+        let data = (0..1000).map(|x| x * 1_000_003).collect::<Vec<i64>>();
+
+        let mut buffer = vec![];
+        encode_native(&data, &mut buffer);
+
+        let result = Decoder::<i64>::try_new(&buffer)?.collect::<Vec<_>>();
+        assert_eq!(result, data);
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_len() -> Result<(), Error> {
+        let data: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+        let mut buffer = vec![];
+        encode(data, 3, &mut buffer)?;
+        assert_eq!(buffer, vec![1, 4, 7, 2, 5, 8, 3, 6, 9]);
+
+        let mut decoder = FixedLenDecoder::try_new(&buffer, 3)?;
+        assert_eq!(decoder.len(), 3);
+        let mut value = [0u8; 3];
+        assert!(decoder.decode_next(&mut value));
+        assert_eq!(value, [1, 2, 3]);
+        assert_eq!(decoder.len(), 2);
+
+        let mut plain = vec![];
+        decoder.clone().decode(&mut plain);
+        assert_eq!(plain, &data[3..]);
+
+        assert!(decoder.decode_next(&mut value));
+        assert!(decoder.decode_next(&mut value));
+        assert_eq!(value, [7, 8, 9]);
+        assert!(!decoder.decode_next(&mut value));
+        assert!(decoder.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert!(encode(&[1, 2, 3], 2, &mut vec![]).is_err());
+        assert!(Decoder::<i32>::try_new(&[1, 2, 3]).is_err());
+        assert!(FixedLenDecoder::try_new(&[1, 2, 3], 2).is_err());
+    }
+}
@@ -1,10 +1,12 @@
 use std::convert::TryInto;
 
 pub mod bitpacked;
+pub mod byte_stream_split;
 pub mod delta_bitpacked;
 pub mod delta_byte_array;
 pub mod delta_length_byte_array;
 pub mod hybrid_rle;
+pub mod plain;
 pub mod plain_byte_array;
 pub mod uleb128;
 pub mod zigzag_leb128;
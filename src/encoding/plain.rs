@@ -0,0 +1,134 @@
+//! Encoders according to [Plain](https://github.com/apache/parquet-format/blob/master/Encodings.md#plain-plain--0).
+//! All encoders extend the `buffer` passed to them, so that it can be re-used across pages.
+use crate::error::{Error, Result};
+use crate::types::NativeType;
+
+/// Encodes booleans as a bitmap whose bits are ordered from the least significant bit.
+pub fn encode_bool<I: Iterator<Item = bool>>(values: I, buffer: &mut Vec<u8>) {
+    let mut byte = 0u8;
+    let mut i = 0;
+    for value in values {
+        byte |= (value as u8) << i;
+        i += 1;
+        if i == 8 {
+            buffer.push(byte);
+            byte = 0;
+            i = 0;
+        }
+    }
+    if i != 0 {
+        buffer.push(byte);
+    }
+}
+
+/// Encodes values of a native type (Int32, Int64, Int96, Float and Double) as their
+/// little-endian representation.
+pub fn encode_native<T: NativeType>(values: &[T], buffer: &mut Vec<u8>) {
+    buffer.reserve(std::mem::size_of_val(values));
+    values
+        .iter()
+        .for_each(|value| buffer.extend_from_slice(value.to_le_bytes().as_ref()));
+}
+
+/// Encodes a single ByteArray value, prefixed by its length.
+#[inline]
+pub fn encode_binary_value(value: &[u8], buffer: &mut Vec<u8>) {
+    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(value);
+}
+
+/// Encodes ByteArray values, each prefixed by its length.
+pub fn encode_binary<V: AsRef<[u8]>, I: Iterator<Item = V>>(values: I, buffer: &mut Vec<u8>) {
+    values.for_each(|value| encode_binary_value(value.as_ref(), buffer))
+}
+
+/// Encodes FixedLenByteArray values of `size` bytes, written back to back.
+/// # Error
+/// Errors iff a value does not have `size` bytes, in which case `buffer` is left unchanged.
+pub fn encode_fixed_len<V: AsRef<[u8]>, I: Iterator<Item = V>>(
+    values: I,
+    size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<()> {
+    let start = buffer.len();
+    for value in values {
+        let value = value.as_ref();
+        if value.len() != size {
+            buffer.truncate(start);
+            return Err(Error::InvalidParameter(format!(
+                "A FixedLenByteArray({}) value must have {} bytes, but it has {}",
+                size,
+                size,
+                value.len()
+            )));
+        }
+        buffer.extend_from_slice(value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::FixexBinaryIter;
+    use crate::encoding::hybrid_rle::BitmapIter;
+    use crate::encoding::plain_byte_array::BinaryIter;
+    use crate::types::decode;
+
+    #[test]
+    fn boolean() {
+        let values = vec![
+            true, false, true, true, false, false, true, false, true, true,
+        ];
+        let mut buffer = vec![];
+        encode_bool(values.iter().copied(), &mut buffer);
+        assert_eq!(buffer, vec![0b01001101, 0b00000011]);
+
+        let result = BitmapIter::new(&buffer, 0, values.len()).collect::<Vec<_>>();
+        assert_eq!(result, values);
+    }
+
+    fn roundtrip_native<T: NativeType + PartialEq>(values: &[T]) {
+        let mut buffer = vec![];
+        encode_native(values, &mut buffer);
+        let result = buffer
+            .chunks_exact(std::mem::size_of::<T>())
+            .map(decode::<T>)
+            .collect::<Vec<_>>();
+        assert_eq!(result, values);
+    }
+
+    #[test]
+    fn native() {
+        roundtrip_native(&[1i32, -2, i32::MAX]);
+        roundtrip_native(&[1i64, -2, i64::MIN]);
+        roundtrip_native(&[[1u32, 2, 3], [4, 5, 6]]);
+        roundtrip_native(&[1.0f32, -0.5]);
+        roundtrip_native(&[1.0f64, f64::MAX]);
+    }
+
+    #[test]
+    fn binary() -> Result<()> {
+        let values = vec![b"aa".as_ref(), b"", b"bcd"];
+        let mut buffer = vec![];
+        encode_binary(values.iter(), &mut buffer);
+
+        let result = BinaryIter::new(&buffer, Some(values.len())).collect::<Result<Vec<_>>>()?;
+        assert_eq!(result, values);
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_len() -> Result<()> {
+        let values = [b"ab", b"cd"];
+        let mut buffer = vec![];
+        encode_fixed_len(values.iter(), 2, &mut buffer)?;
+
+        let result = FixexBinaryIter::new(&buffer, 2).collect::<Vec<_>>();
+        assert_eq!(result, vec![b"ab", b"cd"]);
+
+        assert!(encode_fixed_len([b"abc"].iter(), 2, &mut buffer).is_err());
+        assert_eq!(buffer.len(), 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! Additional authenticated data (AAD) of each module, as declared in the
+//! [specification](https://github.com/apache/parquet-format/blob/master/Encryption.md#442-aad-suffix).
+use crate::error::{Error, Result};
+
+/// The length of the random part of the file AAD written by this crate.
+pub(super) const AAD_FILE_UNIQUE_LEN: usize = 8;
+
+/// The type of a module, declared in its AAD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub(super) enum ModuleType {
+    Footer = 0,
+    ColumnMetaData = 1,
+    DataPage = 2,
+    DictionaryPage = 3,
+    DataPageHeader = 4,
+    DictionaryPageHeader = 5,
+    ColumnIndex = 6,
+    OffsetIndex = 7,
+    BloomFilterHeader = 8,
+    BloomFilterBitset = 9,
+}
+
+/// Converts a row group, column or page ordinal to its AAD representation.
+pub(super) fn ordinal(value: usize) -> Result<i16> {
+    // SPEC: encrypted files can contain at most 32767 row groups, columns and pages per column chunk
+    value.try_into().map_err(|_| {
+        Error::InvalidParameter(format!(
+            "Encrypted files can only contain up to {} row groups, columns and pages per column chunk",
+            i16::MAX
+        ))
+    })
+}
+
+/// Returns the AAD of the file, shared by all its modules.
+pub(super) fn file_aad(aad_prefix: Option<&[u8]>, aad_file_unique: &[u8]) -> Vec<u8> {
+    let mut aad = aad_prefix.unwrap_or_default().to_vec();
+    aad.extend_from_slice(aad_file_unique);
+    aad
+}
+
+/// Returns the AAD of the footer.
+pub(super) fn footer_aad(file_aad: &[u8]) -> Vec<u8> {
+    let mut aad = file_aad.to_vec();
+    aad.push(ModuleType::Footer as u8);
+    aad
+}
+
+/// Returns the AAD of a module of a column chunk. `page` is only used by data pages and their headers.
+pub(super) fn module_aad(
+    file_aad: &[u8],
+    module: ModuleType,
+    row_group: i16,
+    column: i16,
+    page: i16,
+) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(file_aad.len() + 7);
+    aad.extend_from_slice(file_aad);
+    aad.push(module as u8);
+    aad.extend_from_slice(&row_group.to_le_bytes());
+    aad.extend_from_slice(&column.to_le_bytes());
+    if matches!(module, ModuleType::DataPage | ModuleType::DataPageHeader) {
+        aad.extend_from_slice(&page.to_le_bytes());
+    }
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aad() {
+        let file_aad = file_aad(Some(b"p"), &[1, 2]);
+        assert_eq!(file_aad, vec![b'p', 1, 2]);
+
+        assert_eq!(footer_aad(&file_aad), vec![b'p', 1, 2, 0]);
+        assert_eq!(
+            module_aad(&file_aad, ModuleType::ColumnMetaData, 1, 258, 3),
+            vec![b'p', 1, 2, 1, 1, 0, 2, 1]
+        );
+        assert_eq!(
+            module_aad(&file_aad, ModuleType::DictionaryPage, 0, 1, 3),
+            vec![b'p', 1, 2, 3, 0, 0, 1, 0]
+        );
+        assert_eq!(
+            module_aad(&file_aad, ModuleType::DataPageHeader, 0, 1, 3),
+            vec![b'p', 1, 2, 4, 0, 0, 1, 0, 3, 0]
+        );
+        assert_eq!(
+            module_aad(&file_aad, ModuleType::BloomFilterBitset, 2, 1, 3),
+            vec![b'p', 1, 2, 9, 2, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn ordinal_overflow() {
+        assert_eq!(ordinal(32767).unwrap(), i16::MAX);
+        assert!(ordinal(32768).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! AES-GCM and AES-CTR encryption of modules.
+//!
+//! SPEC: a module is serialized as `length (4 bytes, little endian) | nonce (12 bytes) | ciphertext`,
+//! SPEC: followed by a 16 bytes tag in GCM. The length covers everything but itself.
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{AeadCore, AeadInPlace, KeyInit, OsRng};
+use aes_gcm::aes::cipher::{KeyIvInit, StreamCipher};
+use aes_gcm::{AesGcm, Nonce, Tag};
+
+use crate::error::{Error, Result};
+
+pub(super) const NONCE_LEN: usize = 12;
+pub(super) const TAG_LEN: usize = 16;
+pub(super) const SIZE_LEN: usize = 4;
+
+type Ctr<B> = ctr::Ctr128BE<B>;
+
+/// Dispatches `$f` to the AES variant corresponding to the length of `$key`.
+macro_rules! with_aes {
+    ($key:expr, $f:ident $(, $arg:expr)*) => {
+        match $key.len() {
+            16 => $f::<Aes128>($key $(, $arg)*),
+            24 => $f::<Aes192>($key $(, $arg)*),
+            32 => $f::<Aes256>($key $(, $arg)*),
+            other => Err(Error::InvalidParameter(format!(
+                "AES keys must have 16, 24 or 32 bytes, but this key has {} bytes",
+                other
+            ))),
+        }
+    };
+}
+
+/// Returns `length` random bytes.
+pub(super) fn random_bytes(length: usize) -> Vec<u8> {
+    let mut bytes = vec![0; length];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn write_length(length: usize, buffer: &mut Vec<u8>) -> Result<()> {
+    let length: u32 = length
+        .try_into()
+        .map_err(|_| Error::oos("An encrypted module can only contain u32::MAX bytes"))?;
+    buffer.extend_from_slice(&length.to_le_bytes());
+    Ok(())
+}
+
+/// Returns the module of `data`, a module prefixed by its length.
+pub(super) fn split_length(data: &[u8]) -> Result<&[u8]> {
+    if data.len() < SIZE_LEN {
+        return Err(Error::oos("An encrypted module must start with its length"));
+    }
+    let (length, module) = data.split_at(SIZE_LEN);
+    let length = u32::from_le_bytes(length.try_into().unwrap()) as usize;
+    if length != module.len() {
+        return Err(Error::oos(format!(
+            "The encrypted module declares {} bytes but contains {}",
+            length,
+            module.len()
+        )));
+    }
+    Ok(module)
+}
+
+fn gcm_encrypt_<A>(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>>
+where
+    A: aes::cipher::BlockCipher
+        + aes::cipher::BlockSizeUser<BlockSize = aes::cipher::consts::U16>
+        + aes::cipher::BlockEncrypt
+        + KeyInit,
+{
+    let cipher = AesGcm::<A, U12>::new_from_slice(key)
+        .map_err(|_| Error::InvalidParameter("Invalid AES key".to_string()))?;
+
+    let mut buffer = Vec::with_capacity(SIZE_LEN + NONCE_LEN + plaintext.len() + TAG_LEN);
+    write_length(NONCE_LEN + plaintext.len() + TAG_LEN, &mut buffer)?;
+    buffer.extend_from_slice(nonce);
+    buffer.extend_from_slice(plaintext);
+
+    let tag = cipher
+        .encrypt_in_place_detached(
+            Nonce::from_slice(nonce),
+            aad,
+            &mut buffer[SIZE_LEN + NONCE_LEN..],
+        )
+        .map_err(|_| Error::InvalidParameter("Failed to encrypt module".to_string()))?;
+    buffer.extend_from_slice(&tag);
+    Ok(buffer)
+}
+
+fn gcm_decrypt_<A>(key: &[u8], aad: &[u8], module: &mut Vec<u8>) -> Result<()>
+where
+    A: aes::cipher::BlockCipher
+        + aes::cipher::BlockSizeUser<BlockSize = aes::cipher::consts::U16>
+        + aes::cipher::BlockEncrypt
+        + KeyInit,
+{
+    let cipher = AesGcm::<A, U12>::new_from_slice(key)
+        .map_err(|_| Error::InvalidParameter("Invalid AES key".to_string()))?;
+
+    if module.len() < NONCE_LEN + TAG_LEN {
+        return Err(Error::oos(
+            "An AES-GCM module must contain a nonce and a tag",
+        ));
+    }
+    let (nonce, ciphertext) = module.split_at_mut(NONCE_LEN);
+    let (ciphertext, tag) = ciphertext.split_at_mut(ciphertext.len() - TAG_LEN);
+
+    cipher
+        .decrypt_in_place_detached(
+            Nonce::from_slice(nonce),
+            aad,
+            ciphertext,
+            Tag::from_slice(tag),
+        )
+        .map_err(|_| {
+            Error::oos(
+                "Failed to decrypt an AES-GCM module: the key is wrong or the data is corrupted",
+            )
+        })?;
+    module.truncate(module.len() - TAG_LEN);
+    module.drain(..NONCE_LEN);
+    Ok(())
+}
+
+fn ctr_apply_<A>(key: &[u8], nonce: &[u8], buffer: &mut [u8]) -> Result<()>
+where
+    A: aes::cipher::BlockCipher
+        + aes::cipher::BlockSizeUser<BlockSize = aes::cipher::consts::U16>
+        + aes::cipher::BlockEncrypt
+        + KeyInit,
+{
+    // SPEC: the IV is the 12 bytes nonce followed by a 4 bytes counter whose initial value is 1
+    let mut iv = [0u8; 16];
+    iv[..NONCE_LEN].copy_from_slice(nonce);
+    iv[15] = 1;
+
+    let mut cipher = Ctr::<A>::new_from_slices(key, &iv)
+        .map_err(|_| Error::InvalidParameter("Invalid AES key".to_string()))?;
+    cipher.apply_keystream(buffer);
+    Ok(())
+}
+
+fn ctr_encrypt_<A>(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>>
+where
+    A: aes::cipher::BlockCipher
+        + aes::cipher::BlockSizeUser<BlockSize = aes::cipher::consts::U16>
+        + aes::cipher::BlockEncrypt
+        + KeyInit,
+{
+    let nonce = random_bytes(NONCE_LEN);
+
+    let mut buffer = Vec::with_capacity(SIZE_LEN + NONCE_LEN + plaintext.len());
+    write_length(NONCE_LEN + plaintext.len(), &mut buffer)?;
+    buffer.extend_from_slice(&nonce);
+    buffer.extend_from_slice(plaintext);
+    ctr_apply_::<A>(key, &nonce, &mut buffer[SIZE_LEN + NONCE_LEN..])?;
+    Ok(buffer)
+}
+
+fn ctr_decrypt_<A>(key: &[u8], module: &mut Vec<u8>) -> Result<()>
+where
+    A: aes::cipher::BlockCipher
+        + aes::cipher::BlockSizeUser<BlockSize = aes::cipher::consts::U16>
+        + aes::cipher::BlockEncrypt
+        + KeyInit,
+{
+    if module.len() < NONCE_LEN {
+        return Err(Error::oos("An AES-CTR module must contain a nonce"));
+    }
+    let (nonce, ciphertext) = module.split_at_mut(NONCE_LEN);
+    ctr_apply_::<A>(key, nonce, ciphertext)?;
+    module.drain(..NONCE_LEN);
+    Ok(())
+}
+
+/// Encrypts `plaintext` into an AES-GCM module (including its length) with a random nonce.
+pub(super) fn gcm_encrypt(key: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = AesGcm::<Aes128, U12>::generate_nonce(&mut OsRng);
+    gcm_encrypt_with_nonce(key, &nonce, aad, plaintext)
+}
+
+/// Encrypts `plaintext` into an AES-GCM module (including its length) with a given nonce.
+pub(super) fn gcm_encrypt_with_nonce(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    with_aes!(key, gcm_encrypt_, nonce, aad, plaintext)
+}
+
+/// Decrypts an AES-GCM `module` (excluding its length).
+pub(super) fn gcm_decrypt(key: &[u8], aad: &[u8], module: &[u8]) -> Result<Vec<u8>> {
+    let mut buffer = module.to_vec();
+    gcm_decrypt_in_place(key, aad, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Decrypts an AES-GCM `module` (excluding its length) in place, leaving its plaintext.
+pub(super) fn gcm_decrypt_in_place(key: &[u8], aad: &[u8], module: &mut Vec<u8>) -> Result<()> {
+    with_aes!(key, gcm_decrypt_, aad, module)
+}
+
+/// Encrypts `plaintext` into an AES-CTR module (including its length) with a random nonce.
+pub(super) fn ctr_encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    with_aes!(key, ctr_encrypt_, plaintext)
+}
+
+/// Decrypts an AES-CTR `module` (excluding its length) in place, leaving its plaintext.
+pub(super) fn ctr_decrypt_in_place(key: &[u8], module: &mut Vec<u8>) -> Result<()> {
+    with_aes!(key, ctr_decrypt_, module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcm() -> Result<()> {
+        for key in [[1u8; 16].as_ref(), [2u8; 24].as_ref(), [3u8; 32].as_ref()] {
+            let module = gcm_encrypt(key, b"aad", b"hello")?;
+            assert_eq!(module.len(), SIZE_LEN + NONCE_LEN + 5 + TAG_LEN);
+            assert_eq!(gcm_decrypt(key, b"aad", split_length(&module)?)?, b"hello");
+
+            // the aad is authenticated
+            assert!(gcm_decrypt(key, b"add", &module[SIZE_LEN..]).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn gcm_test_vector() -> Result<()> {
+        // Test case 3 of "The Galois/Counter Mode of Operation (GCM)", McGrew & Viega
+        let key = [
+            0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30,
+            0x83, 0x08,
+        ];
+        let nonce = [
+            0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, 0xde, 0xca, 0xf8, 0x88,
+        ];
+        let plaintext = [
+            0xd9, 0x31, 0x32, 0x25, 0xf8, 0x84, 0x06, 0xe5, 0xa5, 0x59, 0x09, 0xc5, 0xaf, 0xf5,
+            0x26, 0x9a, 0x86, 0xa7, 0xa9, 0x53, 0x15, 0x34, 0xf7, 0xda, 0x2e, 0x4c, 0x30, 0x3d,
+            0x8a, 0x31, 0x8a, 0x72, 0x1c, 0x3c, 0x0c, 0x95, 0x95, 0x68, 0x09, 0x53, 0x2f, 0xcf,
+            0x0e, 0x24, 0x49, 0xa6, 0xb5, 0x25, 0xb1, 0x6a, 0xed, 0xf5, 0xaa, 0x0d, 0xe6, 0x57,
+            0xba, 0x63, 0x7b, 0x39, 0x1a, 0xaf, 0xd2, 0x55,
+        ];
+        let expected_tag = [
+            0x4d, 0x5c, 0x2a, 0xf3, 0x27, 0xcd, 0x64, 0xa6, 0x2c, 0xf3, 0x5a, 0xbd, 0x2b, 0xa6,
+            0xfa, 0xb4,
+        ];
+        let module = gcm_encrypt_with_nonce(&key, &nonce, &[], &plaintext)?;
+        assert_eq!(&module[SIZE_LEN..SIZE_LEN + NONCE_LEN], nonce);
+        assert_eq!(&module[module.len() - TAG_LEN..], expected_tag);
+        Ok(())
+    }
+
+    #[test]
+    fn ctr() -> Result<()> {
+        let key = [1u8; 16];
+        let module = ctr_encrypt(&key, b"hello")?;
+        assert_eq!(module.len(), SIZE_LEN + NONCE_LEN + 5);
+        assert_ne!(&module[SIZE_LEN + NONCE_LEN..], b"hello");
+        let mut plaintext = split_length(&module)?.to_vec();
+        ctr_decrypt_in_place(&key, &mut plaintext)?;
+        assert_eq!(plaintext, b"hello");
+        Ok(())
+    }
+
+    #[test]
+    fn ctr_test_vector() -> Result<()> {
+        // AES-128-CTR with the IV `nonce || 00000001`, computed with Python's `cryptography`
+        let mut module = vec![
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 199, 41, 195, 148, 0, 194, 218, 60, 142, 47, 60,
+            207, 124, 204, 160, 154, 124, 47, 161, 223, 4, 195, 73, 96, 29, 238, 19, 65, 203, 54,
+            23, 194, 128, 11,
+        ];
+        ctr_decrypt_in_place(&[1; 16], &mut module)?;
+        assert_eq!(module, b"hello parquet modular encryption!!");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert!(split_length(&[1, 0]).is_err());
+        assert!(split_length(&[2, 0, 0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert!(gcm_encrypt(&[0; 10], &[], b"a").is_err());
+        assert!(ctr_encrypt(&[0; 10], b"a").is_err());
+    }
+}
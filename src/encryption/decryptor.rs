@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use parquet_format_safe::thrift::protocol::TCompactInputProtocol;
+use parquet_format_safe::{
+    BloomFilterHeader, ColumnCryptoMetaData, ColumnMetaData, EncryptionAlgorithm,
+    FileCryptoMetaData, FileMetaData as TFileMetaData,
+};
+
+use crate::error::{Error, Result};
+use crate::metadata::{ColumnChunkMetaData, FileMetaData};
+use crate::page::ParquetPageHeader;
+
+use super::aad::{file_aad, footer_aad, module_aad, ordinal, ModuleType};
+use super::ciphers::{
+    ctr_decrypt_in_place, gcm_decrypt, gcm_decrypt_in_place, gcm_encrypt_with_nonce, split_length,
+    NONCE_LEN, SIZE_LEN, TAG_LEN,
+};
+use super::{Algorithm, FileDecryptionProperties};
+
+/// Decrypts the modules of an encrypted file.
+/// Returned by [`crate::read::read_metadata_with_decryption`].
+pub struct FileDecryptor {
+    algorithm: Algorithm,
+    file_aad: Vec<u8>,
+    footer_key: Option<Vec<u8>>,
+    // the keys of the columns encrypted with their own key, by path
+    column_keys: HashMap<String, Vec<u8>>,
+}
+
+impl FileDecryptor {
+    /// The [`Algorithm`] used to encrypt the file
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Returns the [`ColumnDecryptor`] of the column chunk `column_chunk`, located in the
+    /// `column`th column of the `row_group`th row group of the file, or `None` if the column
+    /// chunk is not encrypted.
+    /// # Errors
+    /// Errors iff the key of the column is not available
+    pub fn column_decryptor(
+        &self,
+        row_group: usize,
+        column: usize,
+        column_chunk: &ColumnChunkMetaData,
+    ) -> Result<Option<ColumnDecryptor>> {
+        let key = match &column_chunk.column_chunk().crypto_metadata {
+            None => return Ok(None),
+            Some(ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_)) => self.footer_key.as_ref(),
+            Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(crypto)) => {
+                self.column_keys.get(&crypto.path_in_schema.join("."))
+            }
+        };
+        let key = key.ok_or_else(|| missing_key(&column_chunk.descriptor().path_in_schema))?;
+
+        Ok(Some(ColumnDecryptor {
+            algorithm: self.algorithm,
+            key: key.clone(),
+            file_aad: self.file_aad.clone(),
+            row_group: ordinal(row_group)?,
+            column: ordinal(column)?,
+            page: 0,
+            dictionary: column_chunk.dictionary_page_offset().is_some(),
+        }))
+    }
+}
+
+/// Decrypts the page headers and pages of an encrypted column chunk, in the order they
+/// are written in the file.
+pub struct ColumnDecryptor {
+    algorithm: Algorithm,
+    key: Vec<u8>,
+    file_aad: Vec<u8>,
+    row_group: i16,
+    column: i16,
+    // the ordinal of the next data page
+    page: usize,
+    // whether the next page is a dictionary page
+    dictionary: bool,
+}
+
+impl ColumnDecryptor {
+    fn aad(&self, module: ModuleType) -> Result<Vec<u8>> {
+        Ok(module_aad(
+            &self.file_aad,
+            module,
+            self.row_group,
+            self.column,
+            ordinal(self.page)?,
+        ))
+    }
+
+    /// Sets the ordinal of the next page, a data page, for readers that skip pages.
+    pub(crate) fn set_data_page(&mut self, page: usize) {
+        self.page = page;
+        self.dictionary = false;
+    }
+
+    /// Reads and decrypts the header of the next page from `reader`.
+    pub(crate) fn read_page_header<R: Read>(
+        &mut self,
+        reader: &mut R,
+        max_size: usize,
+    ) -> Result<ParquetPageHeader> {
+        let module = read_module(reader, max_size)?;
+
+        let module_type = if self.dictionary {
+            ModuleType::DictionaryPageHeader
+        } else {
+            ModuleType::DataPageHeader
+        };
+        let header = gcm_decrypt(&self.key, &self.aad(module_type)?, &module)?;
+
+        let mut prot = TCompactInputProtocol::new(header.as_slice(), max_size);
+        Ok(ParquetPageHeader::read_from_in_protocol(&mut prot)?)
+    }
+
+    /// Decrypts the next page, `buffer`, as read from the file, replacing it by its plaintext.
+    pub(crate) fn decrypt_page(&mut self, buffer: &mut Vec<u8>) -> Result<()> {
+        let module_type = if self.dictionary {
+            ModuleType::DictionaryPage
+        } else {
+            ModuleType::DataPage
+        };
+        split_length(buffer)?;
+        buffer.drain(..SIZE_LEN);
+        match self.algorithm {
+            Algorithm::AesGcmV1 => {
+                gcm_decrypt_in_place(&self.key, &self.aad(module_type)?, buffer)?
+            }
+            Algorithm::AesGcmCtrV1 => ctr_decrypt_in_place(&self.key, buffer)?,
+        };
+
+        if self.dictionary {
+            self.dictionary = false;
+        } else {
+            self.page += 1;
+        }
+        Ok(())
+    }
+
+    /// Decrypts the column index module of the column chunk, `module`, as read from the file.
+    pub(crate) fn decrypt_column_index(&self, module: &[u8]) -> Result<Vec<u8>> {
+        gcm_decrypt(
+            &self.key,
+            &self.aad(ModuleType::ColumnIndex)?,
+            split_length(module)?,
+        )
+    }
+
+    /// Decrypts the offset index module of the column chunk, `module`, as read from the file.
+    pub(crate) fn decrypt_offset_index(&self, module: &[u8]) -> Result<Vec<u8>> {
+        gcm_decrypt(
+            &self.key,
+            &self.aad(ModuleType::OffsetIndex)?,
+            split_length(module)?,
+        )
+    }
+
+    /// Reads and decrypts the header of the bloom filter of the column chunk from `reader`.
+    pub(crate) fn read_bloom_filter_header<R: Read>(
+        &self,
+        reader: &mut R,
+    ) -> Result<BloomFilterHeader> {
+        let module = read_module(reader, MAX_BLOOM_FILTER_HEADER_SIZE)?;
+        let header = gcm_decrypt(
+            &self.key,
+            &self.aad(ModuleType::BloomFilterHeader)?,
+            &module,
+        )?;
+        let mut prot = TCompactInputProtocol::new(header.as_slice(), usize::MAX); // max is ok since `BloomFilterHeader` never allocates
+        Ok(BloomFilterHeader::read_from_in_protocol(&mut prot)?)
+    }
+
+    /// Reads and decrypts the bitset of `length` bytes of the bloom filter of the column chunk
+    /// from `reader` into `bitset`.
+    pub(crate) fn read_bloom_filter_bitset<R: Read>(
+        &self,
+        reader: &mut R,
+        length: usize,
+        bitset: &mut Vec<u8>,
+    ) -> Result<()> {
+        let module = read_module(reader, length + NONCE_LEN + TAG_LEN)?;
+        *bitset = gcm_decrypt(
+            &self.key,
+            &self.aad(ModuleType::BloomFilterBitset)?,
+            &module,
+        )?;
+        Ok(())
+    }
+}
+
+/// The maximum size of the encrypted header of a bloom filter, whose fields are all small.
+const MAX_BLOOM_FILTER_HEADER_SIZE: usize = 1024;
+
+/// Reads a module from `reader`, returning it without its length.
+fn read_module<R: Read>(reader: &mut R, max_size: usize) -> Result<Vec<u8>> {
+    let mut length = [0u8; SIZE_LEN];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length) as usize;
+    if length > max_size {
+        return Err(Error::WouldOverAllocate);
+    }
+
+    let mut module = vec![];
+    module.try_reserve(length)?;
+    reader
+        .by_ref()
+        .take(length as u64)
+        .read_to_end(&mut module)?;
+    if module.len() != length {
+        return Err(Error::oos("The encrypted module is smaller than declared"));
+    }
+    Ok(module)
+}
+
+fn missing_key(path: &[String]) -> Error {
+    Error::InvalidParameter(format!(
+        "The column \"{}\" is encrypted but its key is not available",
+        path.join(".")
+    ))
+}
+
+/// Returns the key declared in `key` or retrieved from `key_metadata`.
+fn retrieve_key(
+    properties: &FileDecryptionProperties,
+    key: Option<&Vec<u8>>,
+    key_metadata: Option<&[u8]>,
+) -> Result<Option<Vec<u8>>> {
+    if let Some(key) = key {
+        return Ok(Some(key.clone()));
+    }
+    match (&properties.key_retriever, key_metadata) {
+        (Some(retriever), Some(key_metadata)) => retriever.retrieve_key(key_metadata).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Returns the [`Algorithm`] and the file AAD declared in `algorithm`.
+fn parse_algorithm(
+    algorithm: &EncryptionAlgorithm,
+    properties: &FileDecryptionProperties,
+) -> Result<(Algorithm, Vec<u8>)> {
+    let (algorithm, aad_prefix, aad_file_unique, supply_aad_prefix) = match algorithm {
+        EncryptionAlgorithm::AESGCMV1(a) => (
+            Algorithm::AesGcmV1,
+            &a.aad_prefix,
+            &a.aad_file_unique,
+            a.supply_aad_prefix,
+        ),
+        EncryptionAlgorithm::AESGCMCTRV1(a) => (
+            Algorithm::AesGcmCtrV1,
+            &a.aad_prefix,
+            &a.aad_file_unique,
+            a.supply_aad_prefix,
+        ),
+    };
+
+    let aad_prefix = match (aad_prefix, &properties.aad_prefix) {
+        (Some(stored), Some(supplied)) if stored != supplied => {
+            return Err(Error::InvalidParameter(
+                "The AAD prefix supplied does not match the one stored in the file".to_string(),
+            ))
+        }
+        (Some(stored), _) => Some(stored),
+        (None, Some(supplied)) => Some(supplied),
+        (None, None) if supply_aad_prefix.unwrap_or(false) => {
+            return Err(Error::InvalidParameter(
+                "The file does not store its AAD prefix, which must be supplied".to_string(),
+            ))
+        }
+        (None, None) => None,
+    };
+
+    let file_aad = file_aad(
+        aad_prefix.map(|x| x.as_slice()),
+        aad_file_unique.as_deref().unwrap_or_default(),
+    );
+    Ok((algorithm, file_aad))
+}
+
+/// Verifies the signature of a plaintext footer, composed by the nonce and tag of the
+/// footer encrypted with the footer key.
+fn verify_signature(key: &[u8], file_aad: &[u8], footer: &[u8], signature: &[u8]) -> Result<()> {
+    if signature.len() != NONCE_LEN + TAG_LEN {
+        return Err(Error::oos(
+            "The signature of a plaintext footer must contain a nonce and a tag",
+        ));
+    }
+    let (nonce, tag) = signature.split_at(NONCE_LEN);
+    let module = gcm_encrypt_with_nonce(key, nonce, &footer_aad(file_aad), footer)?;
+    if &module[module.len() - TAG_LEN..] != tag {
+        return Err(Error::oos(
+            "The signature of the footer does not match its contents: the footer key is wrong or the footer was tampered",
+        ));
+    }
+    Ok(())
+}
+
+/// Deserializes the `footer` of a file, i.e. its last bytes excluding the length and magic,
+/// decrypting the footer (if `encrypted_footer`) and the metadata of the encrypted columns.
+/// Returns `None` as decryptor when the file is not encrypted.
+pub(crate) fn decrypt_metadata(
+    footer: &[u8],
+    encrypted_footer: bool,
+    properties: &FileDecryptionProperties,
+) -> Result<(FileMetaData, Option<FileDecryptor>)> {
+    // a highly nested but sparse struct could result in many allocations
+    let max_size = footer.len() * 2 + 1024;
+
+    let mut reader = footer;
+    let (mut metadata, algorithm, file_aad, footer_key) = if encrypted_footer {
+        let mut prot = TCompactInputProtocol::new(&mut reader, max_size);
+        let crypto = FileCryptoMetaData::read_from_in_protocol(&mut prot)?;
+
+        let (algorithm, file_aad) = parse_algorithm(&crypto.encryption_algorithm, properties)?;
+        let footer_key = retrieve_key(
+            properties,
+            properties.footer_key.as_ref(),
+            crypto.key_metadata.as_deref(),
+        )?
+        .ok_or_else(|| {
+            Error::InvalidParameter(
+                "The footer of the file is encrypted and requires a footer key".to_string(),
+            )
+        })?;
+
+        let plaintext = gcm_decrypt(&footer_key, &footer_aad(&file_aad), split_length(reader)?)?;
+        let mut prot = TCompactInputProtocol::new(plaintext.as_slice(), max_size);
+        let metadata = TFileMetaData::read_from_in_protocol(&mut prot)?;
+        (metadata, algorithm, file_aad, Some(footer_key))
+    } else {
+        let mut prot = TCompactInputProtocol::new(&mut reader, max_size);
+        let metadata = TFileMetaData::read_from_in_protocol(&mut prot)?;
+        let (algorithm, file_aad) = match &metadata.encryption_algorithm {
+            Some(algorithm) => parse_algorithm(algorithm, properties)?,
+            None => return Ok((FileMetaData::try_from_thrift(metadata)?, None)),
+        };
+
+        let footer_key = retrieve_key(
+            properties,
+            properties.footer_key.as_ref(),
+            metadata.footer_signing_key_metadata.as_deref(),
+        )?;
+        // without the footer key, only the plaintext columns can be read
+        if let Some(footer_key) = &footer_key {
+            let signed = &footer[..footer.len() - reader.len()];
+            verify_signature(footer_key, &file_aad, signed, reader)?;
+        }
+        (metadata, algorithm, file_aad, footer_key)
+    };
+
+    let mut column_keys = HashMap::<String, Vec<u8>>::new();
+    for (row_group, group) in metadata.row_groups.iter_mut().enumerate() {
+        for (column, chunk) in group.columns.iter_mut().enumerate() {
+            let key = match &chunk.crypto_metadata {
+                None => continue,
+                Some(ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_)) => footer_key.clone(),
+                Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(crypto)) => {
+                    let path = crypto.path_in_schema.join(".");
+                    match column_keys.get(&path) {
+                        Some(key) => Some(key.clone()),
+                        None => {
+                            let key = retrieve_key(
+                                properties,
+                                properties.column_keys.get(&path),
+                                crypto.key_metadata.as_deref(),
+                            )?;
+                            if let Some(key) = &key {
+                                column_keys.insert(path, key.clone());
+                            }
+                            key
+                        }
+                    }
+                }
+            };
+
+            match (key, &chunk.encrypted_column_metadata) {
+                (Some(key), Some(encrypted)) => {
+                    let aad = module_aad(
+                        &file_aad,
+                        ModuleType::ColumnMetaData,
+                        ordinal(row_group)?,
+                        ordinal(column)?,
+                        0,
+                    );
+                    let plaintext = gcm_decrypt(&key, &aad, encrypted)?;
+                    let mut prot = TCompactInputProtocol::new(plaintext.as_slice(), max_size);
+                    chunk.meta_data = Some(ColumnMetaData::read_from_in_protocol(&mut prot)?);
+                }
+                // the column metadata is either in the (encrypted) footer or stripped in the plaintext footer
+                _ if chunk.meta_data.is_some() => {}
+                _ => {
+                    let path = match &chunk.crypto_metadata {
+                        Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(crypto)) => {
+                            crypto.path_in_schema.clone()
+                        }
+                        _ => vec![],
+                    };
+                    return Err(missing_key(&path));
+                }
+            }
+        }
+    }
+
+    let metadata = FileMetaData::try_from_thrift(metadata)?;
+    Ok((
+        metadata,
+        Some(FileDecryptor {
+            algorithm,
+            file_aad,
+            footer_key,
+            column_keys,
+        }),
+    ))
+}
@@ -0,0 +1,304 @@
+use std::io::Write;
+
+use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
+use parquet_format_safe::{
+    ColumnCryptoMetaData, EncryptionWithColumnKey, EncryptionWithFooterKey, FileCryptoMetaData,
+    RowGroup,
+};
+
+use crate::error::{Error, Result};
+use crate::metadata::{ColumnDescriptor, SchemaDescriptor, ThriftFileMetaData};
+use crate::{ENCRYPTED_MAGIC, FOOTER_SIZE, PARQUET_MAGIC};
+
+use super::aad::{file_aad, footer_aad, module_aad, ordinal, ModuleType, AAD_FILE_UNIQUE_LEN};
+use super::ciphers::{ctr_encrypt, gcm_encrypt, random_bytes, NONCE_LEN, SIZE_LEN, TAG_LEN};
+use super::{Algorithm, FileEncryptionProperties};
+
+fn check_key(key: &[u8]) -> Result<()> {
+    if ![16, 24, 32].contains(&key.len()) {
+        return Err(Error::InvalidParameter(format!(
+            "AES keys must have 16, 24 or 32 bytes, but this key has {} bytes",
+            key.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Encrypts the modules of a file.
+pub(crate) struct FileEncryptor {
+    properties: FileEncryptionProperties,
+    aad_file_unique: Vec<u8>,
+    file_aad: Vec<u8>,
+}
+
+impl FileEncryptor {
+    /// Returns a new [`FileEncryptor`] of a file with `schema`.
+    /// # Errors
+    /// Errors iff a key is invalid or a column key does not correspond to a column of `schema`
+    pub(crate) fn try_new(
+        properties: FileEncryptionProperties,
+        schema: &SchemaDescriptor,
+    ) -> Result<Self> {
+        check_key(&properties.footer_key)?;
+        for (path, key) in &properties.column_keys {
+            check_key(&key.key)?;
+            if !schema
+                .columns()
+                .iter()
+                .any(|column| &column.path_in_schema.join(".") == path)
+            {
+                return Err(Error::InvalidParameter(format!(
+                    "The encrypted column \"{}\" is not part of the schema",
+                    path
+                )));
+            }
+        }
+
+        let aad_file_unique = random_bytes(AAD_FILE_UNIQUE_LEN);
+        let file_aad = file_aad(properties.aad_prefix.as_deref(), &aad_file_unique);
+        Ok(Self {
+            properties,
+            aad_file_unique,
+            file_aad,
+        })
+    }
+
+    fn key(&self, crypto_metadata: &ColumnCryptoMetaData) -> &[u8] {
+        match crypto_metadata {
+            ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_) => &self.properties.footer_key,
+            ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(crypto) => {
+                // the column was encrypted via `column_encryptor`
+                &self.properties.column_keys[&crypto.path_in_schema.join(".")].key
+            }
+        }
+    }
+
+    /// Returns the [`ColumnEncryptor`] of the `column`th column of the `row_group`th row group,
+    /// or `None` if the column is not encrypted.
+    pub(crate) fn column_encryptor(
+        &self,
+        row_group: usize,
+        column: usize,
+        descriptor: &ColumnDescriptor,
+    ) -> Result<Option<ColumnEncryptor>> {
+        let crypto_metadata = if self.properties.column_keys.is_empty() {
+            ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(EncryptionWithFooterKey {})
+        } else {
+            match self
+                .properties
+                .column_keys
+                .get(&descriptor.path_in_schema.join("."))
+            {
+                Some(key) => {
+                    ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(EncryptionWithColumnKey {
+                        path_in_schema: descriptor.path_in_schema.clone(),
+                        key_metadata: key.key_metadata.clone(),
+                    })
+                }
+                None => return Ok(None),
+            }
+        };
+
+        Ok(Some(ColumnEncryptor {
+            algorithm: self.properties.algorithm,
+            key: self.key(&crypto_metadata).to_vec(),
+            file_aad: self.file_aad.clone(),
+            row_group: ordinal(row_group)?,
+            column: ordinal(column)?,
+            page: 0,
+            crypto_metadata,
+        }))
+    }
+
+    /// Encrypts the metadata of the encrypted columns of `row_groups`.
+    pub(crate) fn encrypt_column_metadata(&self, row_groups: &mut [RowGroup]) -> Result<()> {
+        for (row_group, group) in row_groups.iter_mut().enumerate() {
+            for (column, chunk) in group.columns.iter_mut().enumerate() {
+                let crypto_metadata = match &chunk.crypto_metadata {
+                    Some(crypto_metadata) => crypto_metadata,
+                    None => continue,
+                };
+                // SPEC: the metadata of columns encrypted with the footer key is encrypted as part of the footer
+                if !self.properties.plaintext_footer
+                    && matches!(
+                        crypto_metadata,
+                        ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_)
+                    )
+                {
+                    continue;
+                }
+                let metadata = chunk
+                    .meta_data
+                    .as_mut()
+                    .ok_or_else(|| Error::oos("Column chunks must contain metadata"))?;
+
+                let mut plaintext = vec![];
+                let mut protocol = TCompactOutputProtocol::new(&mut plaintext);
+                metadata.write_to_out_protocol(&mut protocol)?;
+
+                let aad = module_aad(
+                    &self.file_aad,
+                    ModuleType::ColumnMetaData,
+                    ordinal(row_group)?,
+                    ordinal(column)?,
+                    0,
+                );
+                let module = gcm_encrypt(self.key(crypto_metadata), &aad, &plaintext)?;
+                // SPEC: the encrypted column metadata is not prefixed by its length
+                chunk.encrypted_column_metadata = Some(module[SIZE_LEN..].to_vec());
+
+                if self.properties.plaintext_footer {
+                    // SPEC: plaintext footers contain the column metadata without statistics
+                    metadata.statistics = None;
+                    metadata.encoding_stats = None;
+                } else {
+                    chunk.meta_data = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes `metadata` as the footer of the file, returning the number of bytes written.
+    pub(crate) fn write_footer<W: Write>(
+        &self,
+        writer: &mut W,
+        metadata: &mut ThriftFileMetaData,
+    ) -> Result<u64> {
+        let properties = &self.properties;
+        let supply_aad_prefix = properties.aad_prefix.is_some() && !properties.store_aad_prefix;
+        let aad_prefix = properties
+            .aad_prefix
+            .clone()
+            .filter(|_| properties.store_aad_prefix);
+        let algorithm = properties.algorithm.into_thrift(
+            aad_prefix,
+            self.aad_file_unique.clone(),
+            supply_aad_prefix,
+        );
+        let aad = footer_aad(&self.file_aad);
+
+        let mut footer = vec![];
+        let magic = if properties.plaintext_footer {
+            metadata.encryption_algorithm = Some(algorithm);
+            metadata.footer_signing_key_metadata = properties.footer_key_metadata.clone();
+
+            let mut protocol = TCompactOutputProtocol::new(&mut footer);
+            metadata.write_to_out_protocol(&mut protocol)?;
+
+            // SPEC: plaintext footers are signed by the nonce and tag of the footer encrypted with the footer key
+            let module = gcm_encrypt(&properties.footer_key, &aad, &footer)?;
+            footer.extend_from_slice(&module[SIZE_LEN..SIZE_LEN + NONCE_LEN]);
+            footer.extend_from_slice(&module[module.len() - TAG_LEN..]);
+            PARQUET_MAGIC
+        } else {
+            let crypto_metadata = FileCryptoMetaData {
+                encryption_algorithm: algorithm,
+                key_metadata: properties.footer_key_metadata.clone(),
+            };
+            let mut protocol = TCompactOutputProtocol::new(&mut footer);
+            crypto_metadata.write_to_out_protocol(&mut protocol)?;
+
+            let mut plaintext = vec![];
+            let mut protocol = TCompactOutputProtocol::new(&mut plaintext);
+            metadata.write_to_out_protocol(&mut protocol)?;
+
+            footer.extend(gcm_encrypt(&properties.footer_key, &aad, &plaintext)?);
+            ENCRYPTED_MAGIC
+        };
+
+        let footer_len: i32 = footer.len().try_into()?;
+        writer.write_all(&footer)?;
+        writer.write_all(&footer_len.to_le_bytes())?;
+        writer.write_all(&magic)?;
+        writer.flush()?;
+        Ok(footer.len() as u64 + FOOTER_SIZE)
+    }
+}
+
+/// Encrypts the page headers and pages of a column chunk, in the order they are written.
+pub(crate) struct ColumnEncryptor {
+    algorithm: Algorithm,
+    key: Vec<u8>,
+    file_aad: Vec<u8>,
+    row_group: i16,
+    column: i16,
+    // the ordinal of the next data page
+    page: usize,
+    crypto_metadata: ColumnCryptoMetaData,
+}
+
+impl ColumnEncryptor {
+    /// The [`ColumnCryptoMetaData`] of the column chunk
+    pub(crate) fn crypto_metadata(&self) -> ColumnCryptoMetaData {
+        self.crypto_metadata.clone()
+    }
+
+    fn aad(&self, module: ModuleType) -> Result<Vec<u8>> {
+        Ok(module_aad(
+            &self.file_aad,
+            module,
+            self.row_group,
+            self.column,
+            ordinal(self.page)?,
+        ))
+    }
+
+    /// Encrypts the (compressed) `page`, returning the module to write.
+    pub(crate) fn encrypt_page(&self, is_dictionary: bool, page: &[u8]) -> Result<Vec<u8>> {
+        match self.algorithm {
+            Algorithm::AesGcmV1 => {
+                let module = if is_dictionary {
+                    ModuleType::DictionaryPage
+                } else {
+                    ModuleType::DataPage
+                };
+                gcm_encrypt(&self.key, &self.aad(module)?, page)
+            }
+            Algorithm::AesGcmCtrV1 => ctr_encrypt(&self.key, page),
+        }
+    }
+
+    /// Encrypts the serialized `header` of a page, returning the module to write.
+    /// Must be called after [`Self::encrypt_page`] of the same page.
+    pub(crate) fn encrypt_page_header(
+        &mut self,
+        is_dictionary: bool,
+        header: &[u8],
+    ) -> Result<Vec<u8>> {
+        let module = if is_dictionary {
+            ModuleType::DictionaryPageHeader
+        } else {
+            ModuleType::DataPageHeader
+        };
+        let module = gcm_encrypt(&self.key, &self.aad(module)?, header)?;
+        if !is_dictionary {
+            self.page += 1;
+        }
+        Ok(module)
+    }
+
+    /// Encrypts the serialized column index of the column chunk, returning the module to write.
+    pub(crate) fn encrypt_column_index(&self, index: &[u8]) -> Result<Vec<u8>> {
+        gcm_encrypt(&self.key, &self.aad(ModuleType::ColumnIndex)?, index)
+    }
+
+    /// Encrypts the serialized offset index of the column chunk, returning the module to write.
+    pub(crate) fn encrypt_offset_index(&self, index: &[u8]) -> Result<Vec<u8>> {
+        gcm_encrypt(&self.key, &self.aad(ModuleType::OffsetIndex)?, index)
+    }
+
+    /// Encrypts the serialized `header` and the `bitset` of the bloom filter of the column
+    /// chunk, returning both modules to write.
+    pub(crate) fn encrypt_bloom_filter(&self, header: &[u8], bitset: &[u8]) -> Result<Vec<u8>> {
+        // SPEC: the header and the bitset are encrypted separately, both with AES-GCM
+        let mut modules =
+            gcm_encrypt(&self.key, &self.aad(ModuleType::BloomFilterHeader)?, header)?;
+        modules.extend(gcm_encrypt(
+            &self.key,
+            &self.aad(ModuleType::BloomFilterBitset)?,
+            bitset,
+        )?);
+        Ok(modules)
+    }
+}
@@ -0,0 +1,130 @@
+//! API to read and write files encrypted with
+//! [Parquet modular encryption](https://github.com/apache/parquet-format/blob/master/Encryption.md).
+//!
+//! Files are written encrypted via [`crate::write::FileWriter::with_encryption`] and their
+//! metadata is read via [`crate::read::read_metadata_with_decryption`], whose [`FileDecryptor`]
+//! provides the [`ColumnDecryptor`] of each column chunk to [`crate::read::PageReader::with_decryptor`].
+mod aad;
+mod ciphers;
+mod decryptor;
+mod encryptor;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parquet_format_safe::{AesGcmCtrV1, AesGcmV1, EncryptionAlgorithm};
+
+use crate::error::Result;
+
+pub(crate) use decryptor::decrypt_metadata;
+pub use decryptor::{ColumnDecryptor, FileDecryptor};
+pub(crate) use encryptor::{ColumnEncryptor, FileEncryptor};
+
+/// The encryption algorithm of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// AES-GCM for every module
+    AesGcmV1,
+    /// AES-CTR for pages and AES-GCM for every other module (page headers, metadata and footer)
+    AesGcmCtrV1,
+}
+
+impl Algorithm {
+    fn into_thrift(
+        self,
+        aad_prefix: Option<Vec<u8>>,
+        aad_file_unique: Vec<u8>,
+        supply_aad_prefix: bool,
+    ) -> EncryptionAlgorithm {
+        let supply_aad_prefix = supply_aad_prefix.then_some(true);
+        match self {
+            Algorithm::AesGcmV1 => EncryptionAlgorithm::AESGCMV1(AesGcmV1 {
+                aad_prefix,
+                aad_file_unique: Some(aad_file_unique),
+                supply_aad_prefix,
+            }),
+            Algorithm::AesGcmCtrV1 => EncryptionAlgorithm::AESGCMCTRV1(AesGcmCtrV1 {
+                aad_prefix,
+                aad_file_unique: Some(aad_file_unique),
+                supply_aad_prefix,
+            }),
+        }
+    }
+}
+
+/// Retrieves the keys of a file from the key metadata stored in it, e.g. from a key management service.
+pub trait KeyRetriever: Send + Sync {
+    /// Returns the key identified by `key_metadata`.
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// The key of a column, together with the metadata stored in the file to retrieve it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnKey {
+    /// The AES key, with 16, 24 or 32 bytes
+    pub key: Vec<u8>,
+    /// The metadata used by readers to retrieve `key` via [`KeyRetriever`]
+    pub key_metadata: Option<Vec<u8>>,
+}
+
+/// Properties to write an encrypted file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEncryptionProperties {
+    /// The encryption algorithm
+    pub algorithm: Algorithm,
+    /// The AES key, with 16, 24 or 32 bytes, used to encrypt (or sign) the footer
+    pub footer_key: Vec<u8>,
+    /// The metadata used by readers to retrieve `footer_key` via [`KeyRetriever`]
+    pub footer_key_metadata: Option<Vec<u8>>,
+    /// Whether the footer is written in plaintext (and signed), so that readers without keys
+    /// can read the plaintext columns.
+    pub plaintext_footer: bool,
+    /// The keys of the encrypted columns, by their path in the schema joined by `.`.
+    /// When empty, every column is encrypted with `footer_key`; otherwise, the columns without
+    /// key are written in plaintext.
+    pub column_keys: HashMap<String, ColumnKey>,
+    /// A prefix of the AAD of every module, used to bind the file to e.g. its name
+    pub aad_prefix: Option<Vec<u8>>,
+    /// Whether `aad_prefix` is stored in the file. When not, readers must supply it.
+    pub store_aad_prefix: bool,
+}
+
+impl FileEncryptionProperties {
+    /// Returns [`FileEncryptionProperties`] encrypting the footer and every column with
+    /// `footer_key` via [`Algorithm::AesGcmV1`].
+    pub fn new(footer_key: Vec<u8>) -> Self {
+        Self {
+            algorithm: Algorithm::AesGcmV1,
+            footer_key,
+            footer_key_metadata: None,
+            plaintext_footer: false,
+            column_keys: HashMap::new(),
+            aad_prefix: None,
+            store_aad_prefix: true,
+        }
+    }
+}
+
+/// Properties to read an encrypted file.
+/// Keys not declared here are requested to `key_retriever`, if any.
+#[derive(Clone, Default)]
+pub struct FileDecryptionProperties {
+    /// The key of the footer
+    pub footer_key: Option<Vec<u8>>,
+    /// The keys of columns encrypted with their own key, by their path in the schema joined by `.`
+    pub column_keys: HashMap<String, Vec<u8>>,
+    /// Retrieves the keys not declared above from their metadata
+    pub key_retriever: Option<Arc<dyn KeyRetriever>>,
+    /// The AAD prefix of files that do not store it
+    pub aad_prefix: Option<Vec<u8>>,
+}
+
+impl FileDecryptionProperties {
+    /// Returns [`FileDecryptionProperties`] whose footer and columns are decrypted with `footer_key`.
+    pub fn new(footer_key: Vec<u8>) -> Self {
+        Self {
+            footer_key: Some(footer_key),
+            ..Default::default()
+        }
+    }
+}
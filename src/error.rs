@@ -15,6 +15,8 @@ pub enum Feature {
     Lz4,
     /// Zstd compression and decompression
     Zstd,
+    /// Parquet modular encryption
+    Encryption,
 }
 
 /// Errors generated by this crate
@@ -31,6 +33,13 @@ pub enum Error {
     InvalidParameter(String),
     /// When decoding or decompressing, the page would allocate more memory than allowed
     WouldOverAllocate,
+    /// When reading, the CRC32 checksum of a page does not match the one declared in its header
+    InvalidChecksum {
+        /// The checksum declared in the page header
+        expected: u32,
+        /// The checksum computed from the page data
+        computed: u32,
+    },
 }
 
 impl Error {
@@ -63,6 +72,13 @@ impl std::fmt::Display for Error {
             Error::WouldOverAllocate => {
                 write!(fmt, "Operation would exceed memory use threshold")
             }
+            Error::InvalidChecksum { expected, computed } => {
+                write!(
+                    fmt,
+                    "Page checksum mismatch: the header declares {:#010x} but the data has {:#010x}",
+                    expected, computed
+                )
+            }
         }
     }
 }
@@ -83,6 +83,63 @@ pub fn compute_rows(
         .collect())
 }
 
+/// Appends `interval` to the sorted and non-overlapping `intervals`, merging it with the last
+/// interval when they overlap or are contiguous. Empty intervals are ignored.
+pub(crate) fn push_interval(intervals: &mut Vec<Interval>, interval: Interval) {
+    if interval.length == 0 {
+        return;
+    }
+    if let Some(last) = intervals.last_mut() {
+        let end = last.start + last.length;
+        if interval.start <= end {
+            last.length = end.max(interval.start + interval.length) - last.start;
+            return;
+        }
+    }
+    intervals.push(interval)
+}
+
+/// Returns the intersection of two sets of sorted and non-overlapping [`Interval`]s, e.g. the
+/// rows selected by two columns of the same row group.
+pub fn intersect_intervals(lhs: &[Interval], rhs: &[Interval]) -> Vec<Interval> {
+    let mut result = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() && j < rhs.len() {
+        let lhs_end = lhs[i].start + lhs[i].length;
+        let rhs_end = rhs[j].start + rhs[j].length;
+        let start = lhs[i].start.max(rhs[j].start);
+        let end = lhs_end.min(rhs_end);
+        if start < end {
+            push_interval(&mut result, Interval::new(start, end - start));
+        }
+        if lhs_end <= rhs_end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
+
+/// Returns the union of two sets of sorted and non-overlapping [`Interval`]s, e.g. the
+/// rows selected by either of two columns of the same row group.
+/// The result is sorted and its contiguous intervals are merged.
+pub fn union_intervals(lhs: &[Interval], rhs: &[Interval]) -> Vec<Interval> {
+    let mut result = Vec::with_capacity(lhs.len() + rhs.len());
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() || j < rhs.len() {
+        let next = if j == rhs.len() || (i < lhs.len() && lhs[i].start <= rhs[j].start) {
+            i += 1;
+            lhs[i - 1]
+        } else {
+            j += 1;
+            rhs[j - 1]
+        };
+        push_interval(&mut result, next);
+    }
+    result
+}
+
 /// An enum describing a page that was either selected in a filter pushdown or skipped
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde_types", derive(Deserialize, Serialize))]
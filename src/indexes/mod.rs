@@ -5,7 +5,10 @@ pub use crate::parquet_bridge::BoundaryOrder;
 pub use crate::thrift_format::PageLocation;
 
 pub use self::index::{BooleanIndex, ByteIndex, FixedLenByteIndex, Index, NativeIndex, PageIndex};
-pub use intervals::{compute_rows, select_pages, FilteredPage, Interval};
+pub(crate) use intervals::{compute_page_row_intervals, push_interval};
+pub use intervals::{
+    compute_rows, intersect_intervals, select_pages, union_intervals, FilteredPage, Interval,
+};
 
 #[cfg(test)]
 mod tests {
@@ -232,4 +235,18 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_intersect_union() {
+        let lhs = &[Interval::new(0, 10), Interval::new(20, 10)];
+        let rhs = &[Interval::new(5, 20), Interval::new(30, 5)];
+
+        assert_eq!(
+            intersect_intervals(lhs, rhs),
+            vec![Interval::new(5, 5), Interval::new(20, 5)]
+        );
+        assert_eq!(union_intervals(lhs, rhs), vec![Interval::new(0, 35)]);
+        assert_eq!(union_intervals(lhs, &[]), lhs.to_vec());
+        assert_eq!(intersect_intervals(lhs, &[]), vec![]);
+    }
 }
@@ -1,3 +1,5 @@
+use parquet_format_safe::{ColumnOrder as TColumnOrder, TypeDefinedOrder};
+
 use super::sort::SortOrder;
 #[cfg(feature = "serde_types")]
 use serde::{Deserialize, Serialize};
@@ -26,4 +28,13 @@ impl ColumnOrder {
             ColumnOrder::Undefined => SortOrder::Signed,
         }
     }
+
+    /// Serializes itself to thrift's [`parquet_format_safe::ColumnOrder`], or `None` for the
+    /// undefined (legacy) order, which has no thrift representation.
+    pub fn into_thrift(self) -> Option<TColumnOrder> {
+        match self {
+            ColumnOrder::TypeDefinedOrder(_) => Some(TColumnOrder::TYPEORDER(TypeDefinedOrder {})),
+            ColumnOrder::Undefined => None,
+        }
+    }
 }
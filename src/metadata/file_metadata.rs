@@ -3,7 +3,7 @@ use crate::{error::Error, metadata::get_sort_order};
 use super::{column_order::ColumnOrder, schema_descriptor::SchemaDescriptor, RowGroupMetaData};
 use parquet_format_safe::ColumnOrder as TColumnOrder;
 
-pub use crate::thrift_format::KeyValue;
+pub use crate::thrift_format::{EncryptionAlgorithm, KeyValue};
 
 /// Metadata for a Parquet file.
 // This is almost equal to [`parquet_format_safe::FileMetaData`] but contains the descriptors,
@@ -37,6 +37,10 @@ pub struct FileMetaData {
     /// When `None` is returned, there are no column orders available, and each column
     /// should be assumed to have undefined (legacy) column order.
     pub column_orders: Option<Vec<ColumnOrder>>,
+    /// The encryption algorithm of encrypted files with a plaintext footer.
+    pub encryption_algorithm: Option<EncryptionAlgorithm>,
+    /// The metadata of the key used to sign the plaintext footer of encrypted files.
+    pub footer_signing_key_metadata: Option<Vec<u8>>,
 }
 
 impl FileMetaData {
@@ -81,6 +85,8 @@ impl FileMetaData {
             key_value_metadata: metadata.key_value_metadata,
             schema_descr,
             column_orders,
+            encryption_algorithm: metadata.encryption_algorithm,
+            footer_signing_key_metadata: metadata.footer_signing_key_metadata,
         })
     }
 
@@ -97,9 +103,12 @@ impl FileMetaData {
                 .collect(),
             key_value_metadata: self.key_value_metadata,
             created_by: self.created_by,
-            column_orders: None, // todo
-            encryption_algorithm: None,
-            footer_signing_key_metadata: None,
+            // the orders are either all defined or absent from the file
+            column_orders: self
+                .column_orders
+                .and_then(|orders| orders.into_iter().map(ColumnOrder::into_thrift).collect()),
+            encryption_algorithm: self.encryption_algorithm,
+            footer_signing_key_metadata: self.footer_signing_key_metadata,
         }
     }
 }
@@ -5,13 +5,15 @@ mod file_metadata;
 mod row_metadata;
 mod schema_descriptor;
 mod sort;
+mod sorting_column;
 
 pub use column_chunk_metadata::ColumnChunkMetaData;
 pub use column_descriptor::{ColumnDescriptor, Descriptor};
 pub use column_order::ColumnOrder;
-pub use file_metadata::{FileMetaData, KeyValue};
+pub use file_metadata::{EncryptionAlgorithm, FileMetaData, KeyValue};
 pub use row_metadata::RowGroupMetaData;
 pub use schema_descriptor::SchemaDescriptor;
 pub use sort::*;
+pub use sorting_column::SortingColumn;
 
 pub use crate::thrift_format::FileMetaData as ThriftFileMetaData;
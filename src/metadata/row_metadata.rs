@@ -1,6 +1,9 @@
 use parquet_format_safe::RowGroup;
 
-use super::{column_chunk_metadata::ColumnChunkMetaData, schema_descriptor::SchemaDescriptor};
+use super::{
+    column_chunk_metadata::ColumnChunkMetaData, schema_descriptor::SchemaDescriptor,
+    sorting_column::SortingColumn,
+};
 use crate::{
     error::{Error, Result},
     write::ColumnOffsetsMetadata,
@@ -15,6 +18,7 @@ pub struct RowGroupMetaData {
     columns: Vec<ColumnChunkMetaData>,
     num_rows: usize,
     total_byte_size: usize,
+    sorting_columns: Option<Vec<SortingColumn>>,
 }
 
 impl RowGroupMetaData {
@@ -28,9 +32,16 @@ impl RowGroupMetaData {
             columns,
             num_rows,
             total_byte_size,
+            sorting_columns: None,
         }
     }
 
+    /// Sets the columns by which the rows of this row group are sorted
+    pub fn with_sorting_columns(mut self, sorting_columns: Option<Vec<SortingColumn>>) -> Self {
+        self.sorting_columns = sorting_columns;
+        self
+    }
+
     /// Returns slice of column chunk metadata.
     pub fn columns(&self) -> &[ColumnChunkMetaData] {
         &self.columns
@@ -46,6 +57,11 @@ impl RowGroupMetaData {
         self.total_byte_size
     }
 
+    /// The columns by which the rows of this row group are sorted, if declared by the writer.
+    pub fn sorting_columns(&self) -> Option<&[SortingColumn]> {
+        self.sorting_columns.as_deref()
+    }
+
     /// Total size of all compressed column data in this row group.
     pub fn compressed_size(&self) -> usize {
         self.columns
@@ -72,11 +88,19 @@ impl RowGroupMetaData {
                 ColumnChunkMetaData::try_from_thrift(descriptor.clone(), column_chunk)
             })
             .collect::<Result<Vec<_>>>()?;
+        // invalid sorting columns are ignored: they only hint how the rows are ordered
+        let sorting_columns = rg.sorting_columns.and_then(|sorting_columns| {
+            sorting_columns
+                .iter()
+                .map(|column| SortingColumn::from_thrift(column, columns.len()))
+                .collect::<Option<Vec<_>>>()
+        });
 
         Ok(RowGroupMetaData {
             columns,
             num_rows,
             total_byte_size,
+            sorting_columns,
         })
     }
 
@@ -95,10 +119,41 @@ impl RowGroupMetaData {
             columns: self.columns.into_iter().map(|v| v.into_thrift()).collect(),
             total_byte_size: self.total_byte_size as i64,
             num_rows: self.num_rows as i64,
-            sorting_columns: None,
+            sorting_columns: self.sorting_columns.map(|sorting_columns| {
+                sorting_columns
+                    .into_iter()
+                    .map(SortingColumn::into_thrift)
+                    .collect()
+            }),
             file_offset,
             total_compressed_size,
             ordinal: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use parquet_format_safe::SortingColumn as TSortingColumn;
+
+    use super::*;
+
+    #[test]
+    fn invalid_sorting_columns() -> Result<()> {
+        let schema = SchemaDescriptor::new("schema".to_string(), vec![]);
+        let row_group = |column_idx| RowGroup {
+            columns: vec![],
+            total_byte_size: 0,
+            num_rows: 0,
+            sorting_columns: Some(vec![TSortingColumn::new(column_idx, false, false)]),
+            file_offset: None,
+            total_compressed_size: None,
+            ordinal: None,
+        };
+        for column_idx in [0, -1] {
+            let metadata = RowGroupMetaData::try_from_thrift(&schema, row_group(column_idx))?;
+            assert_eq!(metadata.sorting_columns(), None);
+        }
+        Ok(())
+    }
+}
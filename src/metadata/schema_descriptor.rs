@@ -2,7 +2,11 @@ use parquet_format_safe::SchemaElement;
 
 use crate::{
     error::Error,
-    schema::{io_message::from_message, types::ParquetType, Repetition},
+    schema::{
+        io_message::{from_message, to_message},
+        types::ParquetType,
+        Repetition,
+    },
 };
 use crate::{error::Result, schema::types::FieldInfo};
 
@@ -92,6 +96,14 @@ impl SchemaDescriptor {
         let schema = from_message(message)?;
         Self::try_from_type(schema)
     }
+
+    /// Renders this schema in the message type format, the inverse of [`Self::try_from_message`].
+    pub fn to_message(&self) -> String {
+        to_message(&ParquetType::new_root(
+            self.name.clone(),
+            self.fields.clone(),
+        ))
+    }
 }
 
 fn build_tree<'a>(
@@ -56,6 +56,8 @@ fn get_logical_sort_order(logical_type: &PrimitiveLogicalType) -> SortOrder {
         Timestamp { .. } => SortOrder::Signed,
         Unknown => SortOrder::Undefined,
         Uuid => SortOrder::Unsigned,
+        // Signed comparison of the represented float (see `crate::types::ord_float16`)
+        Float16 => SortOrder::Signed,
     }
 }
 
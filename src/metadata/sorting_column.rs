@@ -0,0 +1,46 @@
+use parquet_format_safe::SortingColumn as TSortingColumn;
+#[cfg(feature = "serde_types")]
+use serde::{Deserialize, Serialize};
+
+/// A column by which the rows of a row group are sorted.
+///
+/// The rows are sorted by the first sorting column of a row group, then by the second one
+/// for rows with equal values in the first, and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_types", derive(Deserialize, Serialize))]
+pub struct SortingColumn {
+    /// The index of the column in the row group, i.e. of the leaf in the schema
+    pub column_idx: usize,
+    /// Whether the values are sorted in descending order
+    pub descending: bool,
+    /// Whether nulls come before the values, or after them otherwise
+    pub nulls_first: bool,
+}
+
+impl SortingColumn {
+    /// Returns a new [`SortingColumn`]
+    pub fn new(column_idx: usize, descending: bool, nulls_first: bool) -> Self {
+        Self {
+            column_idx,
+            descending,
+            nulls_first,
+        }
+    }
+
+    /// Deserializes [`parquet_format_safe::SortingColumn`] of a row group with `num_columns`
+    /// columns into this struct, or `None` if it is not one of the columns
+    pub(crate) fn from_thrift(column: &TSortingColumn, num_columns: usize) -> Option<Self> {
+        let column_idx = usize::try_from(column.column_idx).ok()?;
+        (column_idx < num_columns)
+            .then(|| Self::new(column_idx, column.descending, column.nulls_first))
+    }
+
+    /// Serializes itself to thrift's [`parquet_format_safe::SortingColumn`].
+    pub(crate) fn into_thrift(self) -> TSortingColumn {
+        TSortingColumn {
+            column_idx: self.column_idx as i32,
+            descending: self.descending,
+            nulls_first: self.nulls_first,
+        }
+    }
+}
@@ -0,0 +1,528 @@
+use crate::encoding::hybrid_rle::HybridRleDecoder;
+use crate::error::{Error, Result};
+use crate::metadata::ColumnChunkMetaData;
+use crate::page::{split_buffer, CompressedPage, DataPage, Page};
+use crate::read::levels::get_bit_width;
+use crate::read::{decompress, MutStreamingIterator, State};
+use crate::schema::types::ParquetType;
+use crate::schema::Repetition;
+
+use super::{list_nodes, num_leaves, push, push_validity, skeleton, ListNodes, Nested};
+
+/// The repetition and definition levels of a leaf column.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Levels {
+    /// The repetition levels, one per entry of the column
+    pub rep_levels: Vec<u32>,
+    /// The definition levels, one per entry of the column
+    pub def_levels: Vec<u32>,
+}
+
+impl Levels {
+    /// Appends the levels of `other` to these levels
+    pub fn extend(&mut self, other: Levels) {
+        self.rep_levels.extend(other.rep_levels);
+        self.def_levels.extend(other.def_levels);
+    }
+}
+
+fn decode(levels: &[u8], max_level: i16, length: usize) -> Result<Vec<u32>> {
+    if max_level == 0 {
+        Ok(vec![0; length])
+    } else {
+        HybridRleDecoder::try_new(levels, get_bit_width(max_level), length)?.collect()
+    }
+}
+
+/// Decodes the repetition and definition levels of `page`.
+pub fn decode_levels(page: &DataPage) -> Result<Levels> {
+    let (rep_levels, def_levels, _) = split_buffer(page)?;
+    let length = page.num_values();
+    Ok(Levels {
+        rep_levels: decode(rep_levels, page.descriptor.max_rep_level, length)?,
+        def_levels: decode(def_levels, page.descriptor.max_def_level, length)?,
+    })
+}
+
+/// Reads the leaf columns of `field`, e.g. from [`crate::read::get_column_iterator`], and
+/// assembles their levels via [`assemble`].
+///
+/// Returns the [`Nested`] structure of `field` and the decompressed pages of each of its leaves,
+/// from which the (non-null) values of the leaves are deserialized.
+/// # Errors
+/// Errors iff the pages cannot be read or decompressed, or their levels are inconsistent.
+pub fn read_nested<I, P>(field: &ParquetType, mut columns: I) -> Result<(Nested, Vec<Vec<Page>>)>
+where
+    I: MutStreamingIterator<Item = (P, ColumnChunkMetaData), Error = Error>,
+    P: Iterator<Item = Result<CompressedPage>>,
+{
+    let mut levels = vec![];
+    let mut leaves = vec![];
+    let mut buffer = vec![];
+    loop {
+        columns = match columns.advance()? {
+            State::Some(mut new_columns) => {
+                if let Some((pages, _)) = new_columns.get() {
+                    let mut leaf_levels = Levels::default();
+                    let mut leaf = vec![];
+                    for page in pages {
+                        let page = decompress(page?, &mut buffer)?;
+                        if let Page::Data(page) = &page {
+                            leaf_levels.extend(decode_levels(page)?);
+                        }
+                        leaf.push(page);
+                    }
+                    levels.push(leaf_levels);
+                    leaves.push(leaf);
+                }
+                new_columns
+            }
+            State::Finished(_) => break,
+        }
+    }
+    Ok((assemble(field, &levels)?, leaves))
+}
+
+/// Assembles the [`Nested`] structure of `field` from the levels of each of its leaf columns,
+/// in depth-first order (the order of [`crate::metadata::SchemaDescriptor::columns`]).
+///
+/// This is the inverse of [`super::shred`]: the values of each leaf column (i.e. the entries
+/// whose definition level is the maximum) correspond to the valid slots of its
+/// [`Nested::Primitive`], in order.
+/// # Errors
+/// Errors iff the number of leaves or their levels are inconsistent with `field`.
+pub fn assemble(field: &ParquetType, leaves: &[Levels]) -> Result<Nested> {
+    if leaves.len() != num_leaves(field) {
+        return Err(Error::oos(format!(
+            "The field \"{}\" has {} leaf columns but {} were provided",
+            field.name(),
+            num_leaves(field),
+            leaves.len()
+        )));
+    }
+    for leaf in leaves {
+        if leaf.rep_levels.len() != leaf.def_levels.len() {
+            return Err(Error::oos(
+                "The number of repetition and definition levels of a column must be equal",
+            ));
+        }
+    }
+
+    let mut nested = skeleton(field, false);
+    let mut cursors = leaves
+        .iter()
+        .map(|levels| Cursor {
+            levels,
+            position: 0,
+        })
+        .collect::<Vec<_>>();
+    while let Some((rep, _)) = cursors.first().and_then(Cursor::peek) {
+        if rep != 0 {
+            return Err(Error::oos("The first repetition level of a row must be 0"));
+        }
+        assemble_field(field, &mut nested, false, 0, 0, &mut cursors)?;
+    }
+    if cursors.iter().any(|cursor| cursor.peek().is_some()) {
+        return Err(Error::oos(
+            "The leaf columns of a field must have the same number of rows",
+        ));
+    }
+    Ok(nested)
+}
+
+/// The position of the assembly in the levels of a leaf
+struct Cursor<'a> {
+    levels: &'a Levels,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// The (repetition, definition) levels of the next entry
+    fn peek(&self) -> Option<(u32, u32)> {
+        self.levels
+            .rep_levels
+            .get(self.position)
+            .zip(self.levels.def_levels.get(self.position))
+            .map(|(rep, def)| (*rep, *def))
+    }
+
+    fn next_def(&self) -> Result<u32> {
+        self.peek().map(|(_, def)| def).ok_or_else(|| {
+            Error::oos("The leaf columns of a field must have the same number of rows")
+        })
+    }
+
+    fn advance(&mut self) {
+        self.position += 1;
+    }
+}
+
+/// Assembles a slot of `field` into `nested`.
+/// * `required`: whether the repetition of `field` was already accounted for by its parent
+/// * `def`: the definition level of the parent of `field`
+/// * `rep_depth`: the maximum repetition level of `field`
+fn assemble_field(
+    field: &ParquetType,
+    nested: &mut Nested,
+    required: bool,
+    mut def: u32,
+    rep_depth: u32,
+    leaves: &mut [Cursor],
+) -> Result<()> {
+    let repetition = if required {
+        Repetition::Required
+    } else {
+        field.get_field_info().repetition
+    };
+
+    match repetition {
+        Repetition::Repeated => {
+            let nodes = ListNodes {
+                element: field,
+                element_is_repeated: true,
+            };
+            return assemble_list(nodes, nested, def, rep_depth, leaves);
+        }
+        Repetition::Optional => {
+            if leaves[0].next_def()? <= def {
+                // a null slot has a single entry in each leaf
+                push(nested, false);
+                leaves.iter_mut().for_each(Cursor::advance);
+                return Ok(());
+            }
+            def += 1;
+        }
+        Repetition::Required => {}
+    }
+
+    match field {
+        ParquetType::PrimitiveType(_) => {
+            if leaves[0].next_def()? != def {
+                return Err(Error::oos(format!(
+                    "The definition levels of the column \"{}\" are larger than its maximum",
+                    field.name()
+                )));
+            }
+            push(nested, true);
+            leaves[0].advance();
+            Ok(())
+        }
+        ParquetType::GroupType { fields, .. } => match (list_nodes(field), nested) {
+            (Some(nodes), nested) => assemble_list(nodes, nested, def, rep_depth, leaves),
+            (
+                None,
+                Nested::Struct {
+                    validity,
+                    length,
+                    children,
+                },
+            ) => {
+                push_validity(validity, *length, true);
+                *length += 1;
+                let mut leaves = leaves;
+                for (field, child) in fields.iter().zip(children.iter_mut()) {
+                    let (field_leaves, remaining) =
+                        std::mem::take(&mut leaves).split_at_mut(num_leaves(field));
+                    assemble_field(field, child, false, def, rep_depth, field_leaves)?;
+                    leaves = remaining;
+                }
+                Ok(())
+            }
+            // `nested` is the skeleton of `field`
+            _ => unreachable!(),
+        },
+    }
+}
+
+/// Assembles a list whose items are `nodes.element` into `nested`.
+fn assemble_list(
+    nodes: ListNodes,
+    nested: &mut Nested,
+    def: u32,
+    rep_depth: u32,
+    leaves: &mut [Cursor],
+) -> Result<()> {
+    let (validity, offsets, child) = match nested {
+        Nested::List {
+            validity,
+            offsets,
+            child,
+        } => (validity, offsets, child.as_mut()),
+        // `nested` is the skeleton of a list
+        _ => unreachable!(),
+    };
+    let length = offsets.len() - 1;
+    push_validity(validity, length, true);
+
+    if leaves[0].next_def()? <= def {
+        // an empty list has a single entry in each leaf
+        offsets.push(offsets[length]);
+        leaves.iter_mut().for_each(Cursor::advance);
+        return Ok(());
+    }
+
+    let rep_depth = rep_depth + 1;
+    let mut items = 0;
+    loop {
+        assemble_field(
+            nodes.element,
+            child,
+            nodes.element_is_repeated,
+            def + 1,
+            rep_depth,
+            leaves,
+        )?;
+        items += 1;
+        // the list continues while its items are repeated
+        match leaves[0].peek() {
+            Some((rep, _)) if rep == rep_depth => continue,
+            _ => break,
+        }
+    }
+    offsets.push(offsets[length] + items);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nested::shred;
+    use crate::schema::io_message::from_message;
+
+    fn field(message: &str) -> ParquetType {
+        match from_message(message).unwrap() {
+            ParquetType::GroupType { mut fields, .. } => fields.pop().unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn levels(rep_levels: Vec<u32>, def_levels: Vec<u32>) -> Levels {
+        Levels {
+            rep_levels,
+            def_levels,
+        }
+    }
+
+    fn primitive(validity: Option<Vec<bool>>, length: usize) -> Nested {
+        Nested::Primitive { validity, length }
+    }
+
+    fn list(validity: Option<Vec<bool>>, offsets: Vec<usize>, child: Nested) -> Nested {
+        Nested::List {
+            validity,
+            offsets,
+            child: Box::new(child),
+        }
+    }
+
+    // asserts that `levels` assemble into `expected` and that `expected` shreds into `levels`
+    fn round_trip(field: &ParquetType, levels: &[Levels], expected: &Nested) -> Result<()> {
+        assert_eq!(&assemble(field, levels)?, expected);
+        let shredded = shred(field, expected)?
+            .into_iter()
+            .map(|leaf| Levels {
+                rep_levels: leaf.rep_levels,
+                def_levels: leaf.def_levels,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(shredded, levels);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_and_null_lists() -> Result<()> {
+        let field = field(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL INT32 element;
+                    }
+                }
+            }",
+        );
+        // [[1, null], null, [], [2]]
+        let expected = list(
+            Some(vec![true, false, true, true]),
+            vec![0, 2, 2, 2, 3],
+            primitive(Some(vec![true, false, true]), 3),
+        );
+        round_trip(
+            &field,
+            &[levels(vec![0, 1, 0, 0, 0], vec![3, 2, 0, 1, 3])],
+            &expected,
+        )
+    }
+
+    #[test]
+    fn nested_lists() -> Result<()> {
+        let field = field(
+            "message schema {
+                REQUIRED group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL group element (LIST) {
+                            REPEATED group list {
+                                REQUIRED INT32 element;
+                            }
+                        }
+                    }
+                }
+            }",
+        );
+        // [[[1, 2], [3]], [null, []], []]
+        let expected = list(
+            None,
+            vec![0, 2, 4, 4],
+            list(
+                Some(vec![true, true, false, true]),
+                vec![0, 2, 3, 3, 3],
+                primitive(None, 3),
+            ),
+        );
+        round_trip(
+            &field,
+            &[levels(vec![0, 2, 1, 0, 1, 0], vec![3, 3, 3, 1, 2, 0])],
+            &expected,
+        )
+    }
+
+    #[test]
+    fn structs() -> Result<()> {
+        let field = field(
+            "message schema {
+                OPTIONAL group a {
+                    REQUIRED INT32 b;
+                    REPEATED group c {
+                        OPTIONAL BYTE_ARRAY d;
+                        REQUIRED INT64 e;
+                    }
+                }
+            }",
+        );
+        // [{b: 1, c: [{d: "x", e: 1}, {d: null, e: 2}]}, null, {b: 2, c: []}]
+        let expected = Nested::Struct {
+            validity: Some(vec![true, false, true]),
+            length: 3,
+            children: vec![
+                primitive(Some(vec![true, false, true]), 3),
+                list(
+                    Some(vec![true, false, true]),
+                    vec![0, 2, 2, 2],
+                    Nested::Struct {
+                        validity: None,
+                        length: 2,
+                        children: vec![primitive(Some(vec![true, false]), 2), primitive(None, 2)],
+                    },
+                ),
+            ],
+        };
+        round_trip(
+            &field,
+            &[
+                levels(vec![0, 0, 0], vec![1, 0, 1]),
+                levels(vec![0, 1, 0, 0], vec![3, 2, 0, 1]),
+                levels(vec![0, 1, 0, 0], vec![2, 2, 0, 1]),
+            ],
+            &expected,
+        )
+    }
+
+    #[test]
+    fn map() -> Result<()> {
+        let field = field(
+            "message schema {
+                OPTIONAL group a (MAP) {
+                    REPEATED group key_value {
+                        REQUIRED BYTE_ARRAY key (UTF8);
+                        OPTIONAL INT32 value;
+                    }
+                }
+            }",
+        );
+        // [{"a": 1, "b": null}, {}, null]
+        let expected = list(
+            Some(vec![true, true, false]),
+            vec![0, 2, 2, 2],
+            Nested::Struct {
+                validity: None,
+                length: 2,
+                children: vec![primitive(None, 2), primitive(Some(vec![true, false]), 2)],
+            },
+        );
+        round_trip(
+            &field,
+            &[
+                levels(vec![0, 1, 0, 0], vec![2, 2, 1, 0]),
+                levels(vec![0, 1, 0, 0], vec![3, 2, 1, 0]),
+            ],
+            &expected,
+        )
+    }
+
+    #[test]
+    fn legacy_lists() -> Result<()> {
+        // [[1, 2], []]
+        let expected = list(None, vec![0, 2, 2], primitive(None, 2));
+        let two_level = field(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED INT32 element;
+                }
+            }",
+        );
+        round_trip(
+            &two_level,
+            &[levels(vec![0, 1, 0], vec![2, 2, 1])],
+            &expected,
+        )?;
+
+        let array = field(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group array {
+                        REQUIRED INT32 b;
+                    }
+                }
+            }",
+        );
+        let expected = list(
+            None,
+            vec![0, 2, 2],
+            Nested::Struct {
+                validity: None,
+                length: 2,
+                children: vec![primitive(None, 2)],
+            },
+        );
+        round_trip(&array, &[levels(vec![0, 1, 0], vec![2, 2, 1])], &expected)?;
+
+        let repeated = field(
+            "message schema {
+                REPEATED INT32 a;
+            }",
+        );
+        let expected = list(None, vec![0, 2, 2], primitive(None, 2));
+        round_trip(
+            &repeated,
+            &[levels(vec![0, 1, 0], vec![1, 1, 0])],
+            &expected,
+        )
+    }
+
+    #[test]
+    fn inconsistent_levels() {
+        let field = field(
+            "message schema {
+                OPTIONAL group a {
+                    OPTIONAL INT32 b;
+                    OPTIONAL INT32 c;
+                }
+            }",
+        );
+        // the second leaf has fewer rows
+        let leaves = [levels(vec![0, 0], vec![2, 2]), levels(vec![0], vec![2])];
+        assert!(assemble(&field, &leaves).is_err());
+        // a definition level larger than the maximum
+        let leaves = [levels(vec![0], vec![3]), levels(vec![0], vec![2])];
+        assert!(assemble(&field, &leaves).is_err());
+    }
+}
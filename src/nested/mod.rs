@@ -0,0 +1,324 @@
+//! Conversion between nested values and the repetition and definition levels of their leaf
+//! columns (a.k.a. Dremel encoding).
+//!
+//! Nested values are represented by a [`Nested`] tree that mirrors the [`ParquetType`] they
+//! belong to: each node declares the validity of its slots and, for lists, the offsets of each
+//! list in its child. [`shred`] computes the levels of the leaves of a field from its
+//! [`Nested`] values and [`assemble`] reverses it.
+mod assemble;
+mod shred;
+
+pub use assemble::{assemble, decode_levels, read_nested, Levels};
+pub use shred::{shred, ShreddedLeaf};
+
+use crate::schema::types::{GroupConvertedType, GroupLogicalType, ParquetType};
+use crate::schema::Repetition;
+
+/// The structure of nested values, without the values of its leaves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nested {
+    /// A leaf with `length` slots
+    Primitive {
+        /// The validity of each slot; `None` when all slots are valid
+        validity: Option<Vec<bool>>,
+        /// The number of slots
+        length: usize,
+    },
+    /// A list whose `i`th slot contains the slots `offsets[i]..offsets[i + 1]` of `child`.
+    /// An empty list (`offsets[i] == offsets[i + 1]`) is valid and distinct from a null list.
+    List {
+        /// The validity of each slot; `None` when all slots are valid
+        validity: Option<Vec<bool>>,
+        /// The offsets of each list in `child`, with one more entry than the number of slots
+        offsets: Vec<usize>,
+        /// The items of the lists
+        child: Box<Nested>,
+    },
+    /// A group whose `i`th slot is composed by the `i`th slot of each of its `children`
+    Struct {
+        /// The validity of each slot; `None` when all slots are valid
+        validity: Option<Vec<bool>>,
+        /// The number of slots
+        length: usize,
+        /// The fields of the group, in the order of the group's fields
+        children: Vec<Nested>,
+    },
+}
+
+impl Nested {
+    /// The number of slots of this node
+    pub fn len(&self) -> usize {
+        match self {
+            Nested::Primitive { length, .. } => *length,
+            Nested::List { offsets, .. } => offsets.len().saturating_sub(1),
+            Nested::Struct { length, .. } => *length,
+        }
+    }
+
+    /// Whether this node has no slots
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The validity of the slots of this node; `None` when all slots are valid
+    pub fn validity(&self) -> Option<&[bool]> {
+        match self {
+            Nested::Primitive { validity, .. }
+            | Nested::List { validity, .. }
+            | Nested::Struct { validity, .. } => validity.as_deref(),
+        }
+    }
+
+    /// Whether the `index`th slot of this node is valid
+    pub fn is_valid(&self, index: usize) -> bool {
+        self.validity()
+            .map(|validity| validity[index])
+            .unwrap_or(true)
+    }
+}
+
+/// The nodes of a list field.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ListNodes<'a> {
+    /// The node of the items of the list
+    pub element: &'a ParquetType,
+    /// Whether `element` is the repeated node itself, in which case its repetition is that of
+    /// the list (i.e. it must be treated as required)
+    pub element_is_repeated: bool,
+}
+
+fn is_list_annotated(field: &ParquetType) -> bool {
+    matches!(
+        field,
+        ParquetType::GroupType {
+            logical_type: Some(GroupLogicalType::List),
+            ..
+        } | ParquetType::GroupType {
+            logical_type: None,
+            converted_type: Some(GroupConvertedType::List),
+            ..
+        }
+    )
+}
+
+pub(crate) fn is_map_annotated(field: &ParquetType) -> bool {
+    matches!(
+        field,
+        ParquetType::GroupType {
+            logical_type: Some(GroupLogicalType::Map),
+            ..
+        } | ParquetType::GroupType {
+            logical_type: None,
+            converted_type: Some(GroupConvertedType::Map | GroupConvertedType::MapKeyValue),
+            ..
+        }
+    )
+}
+
+/// Returns the [`ListNodes`] of `field` if it is a `LIST`- or `MAP`-annotated group.
+/// Maps are lists of their key-value group.
+///
+/// This follows the backward-compatibility rules of the specification: the repeated node of a
+/// `LIST` is the element itself when it is a primitive, has more than one field, is named
+/// `array` or is named `<name>_tuple`.
+pub(crate) fn list_nodes(field: &ParquetType) -> Option<ListNodes<'_>> {
+    let is_list = is_list_annotated(field);
+    if !is_list && !is_map_annotated(field) {
+        return None;
+    }
+    let repeated = match field {
+        ParquetType::GroupType { fields, .. } if fields.len() == 1 => &fields[0],
+        _ => return None,
+    };
+    if repeated.get_field_info().repetition != Repetition::Repeated {
+        return None;
+    }
+    if !is_list {
+        return Some(ListNodes {
+            element: repeated,
+            element_is_repeated: true,
+        });
+    }
+
+    match repeated {
+        ParquetType::GroupType { fields, .. }
+            if fields.len() == 1
+                && repeated.name() != "array"
+                && repeated.name() != format!("{}_tuple", field.name()) =>
+        {
+            Some(ListNodes {
+                element: &fields[0],
+                element_is_repeated: false,
+            })
+        }
+        _ => Some(ListNodes {
+            element: repeated,
+            element_is_repeated: true,
+        }),
+    }
+}
+
+/// The number of leaf columns of `field`
+pub(crate) fn num_leaves(field: &ParquetType) -> usize {
+    match field {
+        ParquetType::PrimitiveType(_) => 1,
+        ParquetType::GroupType { fields, .. } => fields.iter().map(num_leaves).sum(),
+    }
+}
+
+/// Returns an empty [`Nested`] of `field`.
+/// * `required`: whether the repetition of `field` was already accounted for by its parent
+pub(crate) fn skeleton(field: &ParquetType, required: bool) -> Nested {
+    if !required && field.get_field_info().repetition == Repetition::Repeated {
+        return Nested::List {
+            validity: None,
+            offsets: vec![0],
+            child: Box::new(skeleton(field, true)),
+        };
+    }
+    match field {
+        ParquetType::PrimitiveType(_) => Nested::Primitive {
+            validity: None,
+            length: 0,
+        },
+        ParquetType::GroupType { fields, .. } => match list_nodes(field) {
+            Some(nodes) => Nested::List {
+                validity: None,
+                offsets: vec![0],
+                child: Box::new(skeleton(nodes.element, nodes.element_is_repeated)),
+            },
+            None => Nested::Struct {
+                validity: None,
+                length: 0,
+                children: fields.iter().map(|field| skeleton(field, false)).collect(),
+            },
+        },
+    }
+}
+
+/// Appends the validity of a slot to the `validity` of a node with `length` slots, which is
+/// only materialized once a slot is null.
+pub(crate) fn push_validity(validity: &mut Option<Vec<bool>>, length: usize, is_valid: bool) {
+    match validity {
+        Some(validity) => validity.push(is_valid),
+        None if !is_valid => {
+            let mut new = vec![true; length];
+            new.push(false);
+            *validity = Some(new);
+        }
+        None => {}
+    }
+}
+
+/// Appends a slot to `nested`. Lists are appended empty, and the children of a null group
+/// are appended null.
+pub(crate) fn push(nested: &mut Nested, is_valid: bool) {
+    let length = nested.len();
+    match nested {
+        Nested::Primitive {
+            validity,
+            length: len,
+        } => {
+            push_validity(validity, length, is_valid);
+            *len += 1;
+        }
+        Nested::List {
+            validity, offsets, ..
+        } => {
+            push_validity(validity, length, is_valid);
+            // a null list has no items
+            offsets.push(*offsets.last().unwrap());
+        }
+        Nested::Struct {
+            validity,
+            length: len,
+            children,
+        } => {
+            push_validity(validity, length, is_valid);
+            *len += 1;
+            // the slots of a group are the slots of its children
+            if !is_valid {
+                children.iter_mut().for_each(|child| push(child, false));
+            }
+        }
+    }
+}
+
+fn extend_validity(
+    validity: &mut Option<Vec<bool>>,
+    length: usize,
+    other: Option<Vec<bool>>,
+    other_length: usize,
+) {
+    match (validity.as_mut(), other) {
+        (Some(validity), Some(other)) => validity.extend(other),
+        (Some(validity), None) => validity.resize(validity.len() + other_length, true),
+        (None, Some(other)) => {
+            let mut new = vec![true; length];
+            new.extend(other);
+            *validity = Some(new);
+        }
+        (None, None) => {}
+    }
+}
+
+/// Appends the slots of `other` to `nested`. Both must be built from the same field.
+pub(crate) fn extend(nested: &mut Nested, other: Nested) {
+    let length = nested.len();
+    let other_length = other.len();
+    match (nested, other) {
+        (
+            Nested::Primitive {
+                validity,
+                length: len,
+            },
+            Nested::Primitive {
+                validity: other, ..
+            },
+        ) => {
+            extend_validity(validity, length, other, other_length);
+            *len += other_length;
+        }
+        (
+            Nested::List {
+                validity,
+                offsets,
+                child,
+            },
+            Nested::List {
+                validity: other,
+                offsets: other_offsets,
+                child: other_child,
+            },
+        ) => {
+            extend_validity(validity, length, other, other_length);
+            let last = *offsets.last().unwrap();
+            offsets.extend(
+                other_offsets[1..]
+                    .iter()
+                    .map(|offset| last + offset - other_offsets[0]),
+            );
+            extend(child, *other_child);
+        }
+        (
+            Nested::Struct {
+                validity,
+                length: len,
+                children,
+            },
+            Nested::Struct {
+                validity: other,
+                children: other_children,
+                ..
+            },
+        ) => {
+            extend_validity(validity, length, other, other_length);
+            *len += other_length;
+            children
+                .iter_mut()
+                .zip(other_children)
+                .for_each(|(child, other)| extend(child, other));
+        }
+        _ => unreachable!("nested values built from the same field have the same shape"),
+    }
+}
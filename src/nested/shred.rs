@@ -0,0 +1,441 @@
+use crate::error::{Error, Result};
+use crate::schema::types::ParquetType;
+use crate::schema::Repetition;
+
+use super::{list_nodes, num_leaves, ListNodes, Nested};
+
+/// The repetition and definition levels of a leaf column, and the slots of its values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShreddedLeaf {
+    /// The repetition levels, one per entry of the column
+    pub rep_levels: Vec<u32>,
+    /// The definition levels, one per entry of the column
+    pub def_levels: Vec<u32>,
+    /// The slots of the leaf's [`Nested::Primitive`] that are non-null values, in the order
+    /// they are written (i.e. the entries whose definition level is the maximum)
+    pub indices: Vec<usize>,
+}
+
+impl ShreddedLeaf {
+    fn push(&mut self, rep: u32, def: u32) {
+        self.rep_levels.push(rep);
+        self.def_levels.push(def);
+    }
+}
+
+/// Computes the repetition and definition levels of each leaf column of `field` from the
+/// `nested` values of `field`, one row per slot of `nested`.
+///
+/// The leaves are returned in depth-first order, i.e. the order of the field's columns in
+/// [`crate::metadata::SchemaDescriptor::columns`], and their maximum levels are those of the
+/// corresponding [`crate::metadata::ColumnDescriptor`].
+/// # Errors
+/// Errors iff the shape of `nested` does not correspond to `field`.
+pub fn shred(field: &ParquetType, nested: &Nested) -> Result<Vec<ShreddedLeaf>> {
+    let mut leaves = vec![ShreddedLeaf::default(); num_leaves(field)];
+    for index in 0..nested.len() {
+        shred_field(field, nested, index, false, 0, 0, 0, &mut leaves)?;
+    }
+    Ok(leaves)
+}
+
+/// Appends an undefined entry to all `leaves`
+fn push_null(leaves: &mut [ShreddedLeaf], rep: u32, def: u32) {
+    leaves.iter_mut().for_each(|leaf| leaf.push(rep, def));
+}
+
+fn mismatch(field: &ParquetType, nested: &Nested) -> Error {
+    let expected = match nested {
+        Nested::Primitive { .. } => "a primitive field",
+        Nested::List { .. } => "a list",
+        Nested::Struct { .. } => "a group",
+    };
+    Error::InvalidParameter(format!(
+        "The nested values of the field \"{}\" correspond to {} but the field is not one",
+        field.name(),
+        expected
+    ))
+}
+
+/// Shreds the `index`th slot of `nested`.
+/// * `required`: whether the repetition of `field` was already accounted for by its parent
+/// * `rep_depth`: the maximum repetition level of `field`
+#[allow(clippy::too_many_arguments)]
+fn shred_field(
+    field: &ParquetType,
+    nested: &Nested,
+    index: usize,
+    required: bool,
+    mut def: u32,
+    rep: u32,
+    rep_depth: u32,
+    leaves: &mut [ShreddedLeaf],
+) -> Result<()> {
+    if index >= nested.len() {
+        return Err(Error::InvalidParameter(format!(
+            "The nested values of the field \"{}\" have {} slots but slot {} was required",
+            field.name(),
+            nested.len(),
+            index
+        )));
+    }
+    let repetition = if required {
+        Repetition::Required
+    } else {
+        field.get_field_info().repetition
+    };
+
+    match repetition {
+        Repetition::Repeated => {
+            // the field is the repeated node of a list of itself
+            if !nested.is_valid(index) {
+                return Err(Error::InvalidParameter(format!(
+                    "The repeated field \"{}\" cannot be null",
+                    field.name()
+                )));
+            }
+            let nodes = ListNodes {
+                element: field,
+                element_is_repeated: true,
+            };
+            return shred_list(nodes, nested, index, def, rep, rep_depth, leaves);
+        }
+        Repetition::Optional => {
+            if !nested.is_valid(index) {
+                push_null(leaves, rep, def);
+                return Ok(());
+            }
+            def += 1;
+        }
+        Repetition::Required => {}
+    }
+
+    match (field, nested) {
+        (ParquetType::PrimitiveType(_), Nested::Primitive { .. }) => {
+            let leaf = &mut leaves[0];
+            leaf.push(rep, def);
+            leaf.indices.push(index);
+            Ok(())
+        }
+        (ParquetType::GroupType { .. }, Nested::List { .. }) => {
+            let nodes = list_nodes(field).ok_or_else(|| mismatch(field, nested))?;
+            shred_list(nodes, nested, index, def, rep, rep_depth, leaves)
+        }
+        (ParquetType::GroupType { fields, .. }, Nested::Struct { children, .. })
+            if list_nodes(field).is_none() =>
+        {
+            if fields.len() != children.len() {
+                return Err(Error::InvalidParameter(format!(
+                    "The group \"{}\" has {} fields but its nested values have {} children",
+                    field.name(),
+                    fields.len(),
+                    children.len()
+                )));
+            }
+            let mut leaves = leaves;
+            for (field, child) in fields.iter().zip(children) {
+                let (field_leaves, remaining) = leaves.split_at_mut(num_leaves(field));
+                shred_field(
+                    field,
+                    child,
+                    index,
+                    false,
+                    def,
+                    rep,
+                    rep_depth,
+                    field_leaves,
+                )?;
+                leaves = remaining;
+            }
+            Ok(())
+        }
+        _ => Err(mismatch(field, nested)),
+    }
+}
+
+/// Shreds the `index`th list of `nested`, whose items are `nodes.element`.
+fn shred_list(
+    nodes: ListNodes,
+    nested: &Nested,
+    index: usize,
+    def: u32,
+    rep: u32,
+    rep_depth: u32,
+    leaves: &mut [ShreddedLeaf],
+) -> Result<()> {
+    let (offsets, child) = match nested {
+        Nested::List { offsets, child, .. } => (offsets, child.as_ref()),
+        _ => return Err(mismatch(nodes.element, nested)),
+    };
+    if index + 1 >= offsets.len() {
+        return Err(Error::InvalidParameter(
+            "The offsets of a list must have one more entry than its number of slots".to_string(),
+        ));
+    }
+    let (start, end) = (offsets[index], offsets[index + 1]);
+    if end < start {
+        return Err(Error::InvalidParameter(
+            "The offsets of a list must be monotonically increasing".to_string(),
+        ));
+    }
+    if start == end {
+        // an empty list is defined up to the list itself
+        push_null(leaves, rep, def);
+        return Ok(());
+    }
+
+    // the items of the list are defined one level further and repeated at a new level
+    let rep_depth = rep_depth + 1;
+    for (i, item) in (start..end).enumerate() {
+        // the first item starts the list, the others repeat it
+        let rep = if i == 0 { rep } else { rep_depth };
+        shred_field(
+            nodes.element,
+            child,
+            item,
+            nodes.element_is_repeated,
+            def + 1,
+            rep,
+            rep_depth,
+            leaves,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::SchemaDescriptor;
+    use crate::schema::io_message::from_message;
+
+    fn field(message: &str) -> ParquetType {
+        match from_message(message).unwrap() {
+            ParquetType::GroupType { mut fields, .. } => fields.pop().unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn leaf(rep_levels: Vec<u32>, def_levels: Vec<u32>, indices: Vec<usize>) -> ShreddedLeaf {
+        ShreddedLeaf {
+            rep_levels,
+            def_levels,
+            indices,
+        }
+    }
+
+    fn primitive(validity: Option<Vec<bool>>, length: usize) -> Nested {
+        Nested::Primitive { validity, length }
+    }
+
+    fn list(validity: Option<Vec<bool>>, offsets: Vec<usize>, child: Nested) -> Nested {
+        Nested::List {
+            validity,
+            offsets,
+            child: Box::new(child),
+        }
+    }
+
+    #[test]
+    fn empty_and_null_lists() -> Result<()> {
+        let field = field(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL INT32 element;
+                    }
+                }
+            }",
+        );
+        // [[1, null], null, [], [2]]
+        let nested = list(
+            Some(vec![true, false, true, true]),
+            vec![0, 2, 2, 2, 3],
+            primitive(Some(vec![true, false, true]), 3),
+        );
+
+        let leaves = shred(&field, &nested)?;
+        assert_eq!(
+            leaves,
+            vec![leaf(vec![0, 1, 0, 0, 0], vec![3, 2, 0, 1, 3], vec![0, 2])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nested_lists() -> Result<()> {
+        let field = field(
+            "message schema {
+                REQUIRED group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL group element (LIST) {
+                            REPEATED group list {
+                                REQUIRED INT32 element;
+                            }
+                        }
+                    }
+                }
+            }",
+        );
+        // [[[1, 2], [3]], [null, []], []]
+        let nested = list(
+            None,
+            vec![0, 2, 4, 4],
+            list(
+                Some(vec![true, true, false, true]),
+                vec![0, 2, 3, 3, 3],
+                primitive(None, 3),
+            ),
+        );
+
+        let leaves = shred(&field, &nested)?;
+        assert_eq!(
+            leaves,
+            vec![leaf(
+                vec![0, 2, 1, 0, 1, 0],
+                vec![3, 3, 3, 1, 2, 0],
+                vec![0, 1, 2]
+            )]
+        );
+
+        let descriptor = SchemaDescriptor::new("schema".to_string(), vec![field]);
+        let max_def_level = descriptor.columns()[0].descriptor.max_def_level as u32;
+        assert_eq!(leaves[0].def_levels.iter().max(), Some(&max_def_level));
+        Ok(())
+    }
+
+    #[test]
+    fn structs() -> Result<()> {
+        let field = field(
+            "message schema {
+                OPTIONAL group a {
+                    REQUIRED INT32 b;
+                    REPEATED group c {
+                        OPTIONAL BYTE_ARRAY d;
+                        REQUIRED INT64 e;
+                    }
+                }
+            }",
+        );
+        // [{b: 1, c: [{d: "x", e: 1}, {d: null, e: 2}]}, null, {b: 2, c: []}]
+        let nested = Nested::Struct {
+            validity: Some(vec![true, false, true]),
+            length: 3,
+            children: vec![
+                primitive(None, 3),
+                list(
+                    None,
+                    vec![0, 2, 2, 2],
+                    Nested::Struct {
+                        validity: None,
+                        length: 2,
+                        children: vec![primitive(Some(vec![true, false]), 2), primitive(None, 2)],
+                    },
+                ),
+            ],
+        };
+
+        let leaves = shred(&field, &nested)?;
+        assert_eq!(
+            leaves,
+            vec![
+                leaf(vec![0, 0, 0], vec![1, 0, 1], vec![0, 2]),
+                leaf(vec![0, 1, 0, 0], vec![3, 2, 0, 1], vec![0]),
+                leaf(vec![0, 1, 0, 0], vec![2, 2, 0, 1], vec![0, 1]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn map() -> Result<()> {
+        let field = field(
+            "message schema {
+                OPTIONAL group a (MAP) {
+                    REPEATED group key_value {
+                        REQUIRED BYTE_ARRAY key (UTF8);
+                        OPTIONAL INT32 value;
+                    }
+                }
+            }",
+        );
+        // [{"a": 1, "b": null}, {}, null]
+        let nested = list(
+            Some(vec![true, true, false]),
+            vec![0, 2, 2, 2],
+            Nested::Struct {
+                validity: None,
+                length: 2,
+                children: vec![primitive(None, 2), primitive(Some(vec![true, false]), 2)],
+            },
+        );
+
+        let leaves = shred(&field, &nested)?;
+        assert_eq!(
+            leaves,
+            vec![
+                leaf(vec![0, 1, 0, 0], vec![2, 2, 1, 0], vec![0, 1]),
+                leaf(vec![0, 1, 0, 0], vec![3, 2, 1, 0], vec![0]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_lists() -> Result<()> {
+        // [[1, 2], []]
+        let nested = list(None, vec![0, 2, 2], primitive(None, 2));
+        let expected = vec![leaf(vec![0, 1, 0], vec![2, 2, 1], vec![0, 1])];
+
+        let two_level = field(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED INT32 element;
+                }
+            }",
+        );
+        assert_eq!(shred(&two_level, &nested)?, expected);
+
+        let array = field(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group array {
+                        REQUIRED INT32 b;
+                    }
+                }
+            }",
+        );
+        let nested = list(
+            None,
+            vec![0, 2, 2],
+            Nested::Struct {
+                validity: None,
+                length: 2,
+                children: vec![primitive(None, 2)],
+            },
+        );
+        assert_eq!(shred(&array, &nested)?, expected);
+
+        // a repeated field without annotation is a required list
+        let repeated = field(
+            "message schema {
+                REPEATED INT32 a;
+            }",
+        );
+        let nested = list(None, vec![0, 2, 2], primitive(None, 2));
+        let expected = vec![leaf(vec![0, 1, 0], vec![1, 1, 0], vec![0, 1])];
+        assert_eq!(shred(&repeated, &nested)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_shape() {
+        let field = field(
+            "message schema {
+                OPTIONAL INT32 a;
+            }",
+        );
+        let nested = list(None, vec![0, 1], primitive(None, 1));
+        assert!(shred(&field, &nested).is_err());
+    }
+}
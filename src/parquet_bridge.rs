@@ -498,6 +498,8 @@ pub enum PrimitiveLogicalType {
     Json,
     Bson,
     Uuid,
+    /// An IEEE 754 half-precision float, annotating `FIXED_LEN_BYTE_ARRAY(2)`
+    Float16,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -576,6 +578,7 @@ impl TryFrom<ParquetLogicalType> for PrimitiveLogicalType {
             ParquetLogicalType::JSON(_) => PrimitiveLogicalType::Json,
             ParquetLogicalType::BSON(_) => PrimitiveLogicalType::Bson,
             ParquetLogicalType::UUID(_) => PrimitiveLogicalType::Uuid,
+            ParquetLogicalType::FLOAT16(_) => PrimitiveLogicalType::Float16,
             _ => return Err(Error::oos("LogicalType value out of range")),
         })
     }
@@ -630,6 +633,7 @@ impl From<PrimitiveLogicalType> for ParquetLogicalType {
             PrimitiveLogicalType::Json => ParquetLogicalType::JSON(Default::default()),
             PrimitiveLogicalType::Bson => ParquetLogicalType::BSON(Default::default()),
             PrimitiveLogicalType::Uuid => ParquetLogicalType::UUID(Default::default()),
+            PrimitiveLogicalType::Float16 => ParquetLogicalType::FLOAT16(Default::default()),
         }
     }
 }
@@ -659,6 +663,7 @@ mod tests {
             Json,
             Bson,
             Uuid,
+            Float16,
         ];
         for a in a {
             let c: ParquetLogicalType = a.into();
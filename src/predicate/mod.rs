@@ -0,0 +1,420 @@
+//! Predicates over the columns of a file, evaluated against statistics to skip data that
+//! cannot contain matching rows.
+//!
+//! A [`Predicate`] is a boolean expression over leaf columns, identified by their path in the
+//! schema. It is evaluated following SQL semantics: comparisons with a null value are
+//! neither true nor false, and a row matches when the predicate is true.
+//!
+//! Evaluating a predicate against statistics is conservative: it only rules out row groups
+//! ([`Predicate::may_match`]) or rows ([`Predicate::select_rows`], from column indexes) whose
+//! statistics prove that no row matches. Statistics whose sort order is undefined,
+//! missing statistics and repeated columns never rule out rows. Row groups can additionally
+//! be ruled out by the bloom filters of the columns compared for equality
+//! (`Predicate::may_match_with_bloom_filters`).
+mod pages;
+mod scalar;
+mod statistics;
+
+use crate::error::{Error, Result};
+use crate::indexes::{select_pages, FilteredPage, Index, Interval, PageLocation};
+use crate::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use crate::scalar::Scalar;
+
+use statistics::ColumnStatistics;
+
+/// A typed literal that a column is compared to.
+///
+/// Integer literals are compared to the value represented by a column: the unscaled value
+/// of a `DECIMAL`, the days of a `DATE`, the units since the epoch of a `TIMESTAMP`, etc.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Boolean(bool),
+    Int32(i32),
+    Int64(i64),
+    UInt32(u32),
+    UInt64(u64),
+    /// The unscaled value of a decimal
+    Decimal(i128),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Binary(Vec<u8>),
+}
+
+macro_rules! literal_from {
+    ($type:ty, $variant:ident) => {
+        impl From<$type> for Literal {
+            fn from(value: $type) -> Self {
+                Self::$variant(value.into())
+            }
+        }
+    };
+}
+
+literal_from!(bool, Boolean);
+literal_from!(i32, Int32);
+literal_from!(i64, Int64);
+literal_from!(u32, UInt32);
+literal_from!(u64, UInt64);
+literal_from!(i128, Decimal);
+literal_from!(f32, Float);
+literal_from!(f64, Double);
+literal_from!(String, String);
+literal_from!(&str, String);
+literal_from!(Vec<u8>, Binary);
+literal_from!(&[u8], Binary);
+
+/// A comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+/// A boolean expression over the leaf columns of a file
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    /// `column op value`
+    Compare {
+        /// The path of the column in the schema
+        column: Vec<String>,
+        op: CompareOp,
+        value: Literal,
+    },
+    /// `column IN (values)`
+    In {
+        /// The path of the column in the schema
+        column: Vec<String>,
+        values: Vec<Literal>,
+    },
+    /// `column IS NULL`
+    IsNull(Vec<String>),
+    /// `column IS NOT NULL`
+    IsNotNull(Vec<String>),
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+fn to_path(column: &[&str]) -> Vec<String> {
+    column.iter().map(|x| x.to_string()).collect()
+}
+
+impl Predicate {
+    /// Returns the predicate `column op value`
+    pub fn compare<L: Into<Literal>>(column: &[&str], op: CompareOp, value: L) -> Self {
+        Self::Compare {
+            column: to_path(column),
+            op,
+            value: value.into(),
+        }
+    }
+
+    /// Returns the predicate `column IN (values)`
+    pub fn is_in<L: Into<Literal>, I: IntoIterator<Item = L>>(column: &[&str], values: I) -> Self {
+        Self::In {
+            column: to_path(column),
+            values: values.into_iter().map(|x| x.into()).collect(),
+        }
+    }
+
+    /// Returns the predicate `column IS NULL`
+    pub fn is_null(column: &[&str]) -> Self {
+        Self::IsNull(to_path(column))
+    }
+
+    /// Returns the predicate `column IS NOT NULL`
+    pub fn is_not_null(column: &[&str]) -> Self {
+        Self::IsNotNull(to_path(column))
+    }
+
+    /// Returns the predicate `self AND other`
+    pub fn and(self, other: Predicate) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    /// Returns the predicate `self OR other`
+    pub fn or(self, other: Predicate) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Returns whether some row of `row_group` may match this predicate according to the
+    /// statistics of its column chunks. `false` guarantees that no row matches.
+    /// # Errors
+    /// Errors iff a column of the predicate does not exist, a literal cannot be compared with
+    /// its column or the statistics are invalid.
+    pub fn may_match(&self, row_group: &RowGroupMetaData) -> Result<bool> {
+        let outcomes = self.evaluate(row_group, &|column, _, op| {
+            statistics::column_outcomes(column, &|statistics| op.outcomes(statistics))
+        })?;
+        Ok(outcomes.is_true)
+    }
+
+    /// Returns whether some row of `row_group` may match this predicate according to the
+    /// statistics and the bloom filters of its column chunks. `false` guarantees that no row
+    /// matches.
+    ///
+    /// `bitsets` has the bloom filter of each column chunk of `row_group`, as read by
+    /// [`crate::bloom_filter::read`]. Only the bloom filters of the columns compared with `=`
+    /// or `IN` are used, so the others can be left empty.
+    /// # Errors
+    /// Errors iff [`Predicate::may_match`] errors or `bitsets` does not have one item per
+    /// column of `row_group`.
+    #[cfg(feature = "bloom_filter")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bloom_filter")))]
+    pub fn may_match_with_bloom_filters(
+        &self,
+        row_group: &RowGroupMetaData,
+        bitsets: &[Vec<u8>],
+    ) -> Result<bool> {
+        if bitsets.len() != row_group.columns().len() {
+            return Err(Error::InvalidParameter(format!(
+                "The row group has {} columns but there are {} bloom filters",
+                row_group.columns().len(),
+                bitsets.len()
+            )));
+        }
+        let outcomes = self.evaluate(row_group, &|column, i, op| {
+            let mut outcomes =
+                statistics::column_outcomes(column, &|statistics| op.outcomes(statistics))?;
+            let descriptor = &column.descriptor().descriptor;
+            if let (Some(values), 0) = (op.equal_values(), descriptor.max_rep_level) {
+                outcomes.is_true &= crate::bloom_filter::may_contain_scalars(
+                    &bitsets[i],
+                    &descriptor.primitive_type,
+                    values,
+                );
+            }
+            Ok(outcomes)
+        })?;
+        Ok(outcomes.is_true)
+    }
+
+    /// Returns the rows of `row_group` that may match this predicate according to the column
+    /// and offset indexes of its columns, as read by [`crate::read::read_columns_indexes`] and
+    /// [`crate::read::read_pages_locations`]. Rows outside of the result do not match.
+    ///
+    /// The rows selected by each column are intersected (`AND`) or united (`OR`) across
+    /// columns. When `indexes` and `locations` are empty (the row group has no indexes), all
+    /// rows are selected.
+    /// # Errors
+    /// Errors iff [`Predicate::may_match`] errors, `indexes` and `locations` do not have one
+    /// item per column of `row_group` or they are not consistent with each other.
+    pub fn select_rows(
+        &self,
+        row_group: &RowGroupMetaData,
+        indexes: &[Box<dyn Index>],
+        locations: &[Vec<PageLocation>],
+    ) -> Result<Vec<Interval>> {
+        let num_columns = row_group.columns().len();
+        if indexes.len() != locations.len() || (!indexes.is_empty() && indexes.len() != num_columns)
+        {
+            return Err(Error::InvalidParameter(format!(
+                "The row group has {} columns but there are {} column indexes and {} offset indexes",
+                num_columns,
+                indexes.len(),
+                locations.len()
+            )));
+        }
+        let num_rows = row_group.num_rows();
+        let outcomes = self.evaluate(row_group, &|column, i, op| {
+            let index = indexes.get(i).map(|index| index.as_ref());
+            let locations = locations.get(i).map(|locations| locations.as_slice());
+            pages::column_row_outcomes(column, index.zip(locations), num_rows, op)
+        })?;
+        Ok(outcomes.is_true)
+    }
+
+    /// Returns the pages of each column of `row_group` and their rows selected by
+    /// [`Predicate::select_rows`], to be read with e.g. [`crate::read::IndexedPageReader`].
+    /// # Errors
+    /// Errors iff [`Predicate::select_rows`] errors.
+    pub fn select_pages(
+        &self,
+        row_group: &RowGroupMetaData,
+        indexes: &[Box<dyn Index>],
+        locations: &[Vec<PageLocation>],
+    ) -> Result<Vec<Vec<FilteredPage>>> {
+        let rows = self.select_rows(row_group, indexes, locations)?;
+        locations
+            .iter()
+            .map(|locations| select_pages(&rows, locations, row_group.num_rows()))
+            .collect()
+    }
+
+    /// Evaluates this predicate, whose leaves are evaluated by `leaf` from the column chunk,
+    /// its position in the row group and the operation over its values.
+    fn evaluate<O: Logic>(&self, row_group: &RowGroupMetaData, leaf: Leaf<O>) -> Result<O> {
+        Ok(match self {
+            Self::Compare { column, op, value } => {
+                let (i, column) = find_column(row_group, column)?;
+                let value = Scalar::try_from_literal(value, column.descriptor())?;
+                leaf(column, i, &LeafOp::Compare(*op, &value))?
+            }
+            Self::In { column, values } => {
+                let (i, column) = find_column(row_group, column)?;
+                let values = values
+                    .iter()
+                    .map(|value| Scalar::try_from_literal(value, column.descriptor()))
+                    .collect::<Result<Vec<_>>>()?;
+                leaf(column, i, &LeafOp::In(&values))?
+            }
+            Self::IsNull(column) => {
+                let (i, column) = find_column(row_group, column)?;
+                leaf(column, i, &LeafOp::IsNull)?
+            }
+            Self::IsNotNull(column) => {
+                let (i, column) = find_column(row_group, column)?;
+                leaf(column, i, &LeafOp::IsNotNull)?
+            }
+            Self::And(lhs, rhs) => lhs
+                .evaluate(row_group, leaf)?
+                .and(rhs.evaluate(row_group, leaf)?),
+            Self::Or(lhs, rhs) => lhs
+                .evaluate(row_group, leaf)?
+                .or(rhs.evaluate(row_group, leaf)?),
+            Self::Not(predicate) => predicate.evaluate(row_group, leaf)?.not(),
+        })
+    }
+}
+
+/// The operation of a leaf of a predicate over the values of its column
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LeafOp<'a> {
+    Compare(CompareOp, &'a Scalar),
+    In(&'a [Scalar]),
+    IsNull,
+    IsNotNull,
+}
+
+impl<'a> LeafOp<'a> {
+    /// The outcomes of this operation over values with `statistics`
+    fn outcomes(&self, statistics: &ColumnStatistics) -> Outcomes {
+        match self {
+            Self::Compare(op, value) => statistics.compare(*op, value),
+            Self::In(values) => statistics.is_in(values),
+            Self::IsNull => statistics.is_null(),
+            Self::IsNotNull => statistics.is_null().not(),
+        }
+    }
+
+    /// The values that the column must be equal to, for `=` and `IN`
+    #[cfg(feature = "bloom_filter")]
+    fn equal_values(&self) -> Option<&'a [Scalar]> {
+        match *self {
+            Self::Compare(CompareOp::Eq, value) => Some(std::slice::from_ref(value)),
+            Self::In(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// Evaluates a leaf of a predicate over a column chunk, its position in the row group and the
+/// operation over its values
+type Leaf<'a, O> = &'a dyn Fn(&ColumnChunkMetaData, usize, &LeafOp) -> Result<O>;
+
+impl std::ops::Not for Predicate {
+    type Output = Predicate;
+
+    /// Returns the predicate `NOT self`
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+fn find_column<'a>(
+    row_group: &'a RowGroupMetaData,
+    path: &[String],
+) -> Result<(usize, &'a ColumnChunkMetaData)> {
+    row_group
+        .columns()
+        .iter()
+        .enumerate()
+        .find(|(_, column)| column.descriptor().path_in_schema == path)
+        .ok_or_else(|| {
+            Error::InvalidParameter(format!("The column \"{}\" does not exist", path.join(".")))
+        })
+}
+
+/// The possible results of a predicate over a set of rows, following SQL's three-valued logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Outcomes {
+    is_true: bool,
+    is_false: bool,
+    is_null: bool,
+}
+
+impl Outcomes {
+    /// Any result is possible
+    const ANY: Self = Self {
+        is_true: true,
+        is_false: true,
+        is_null: true,
+    };
+}
+
+/// The operations of SQL's three-valued logic over sets of possible results
+trait Logic: Sized {
+    fn not(self) -> Self;
+
+    fn and(self, other: Self) -> Self;
+
+    fn or(self, other: Self) -> Self {
+        self.not().and(other.not()).not()
+    }
+}
+
+impl Logic for Outcomes {
+    fn not(self) -> Self {
+        Self {
+            is_true: self.is_false,
+            is_false: self.is_true,
+            is_null: self.is_null,
+        }
+    }
+
+    fn and(self, other: Self) -> Self {
+        Self {
+            is_true: self.is_true && other.is_true,
+            is_false: self.is_false || other.is_false,
+            is_null: (self.is_null && (other.is_true || other.is_null))
+                || (other.is_null && (self.is_true || self.is_null)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRUE: Outcomes = Outcomes {
+        is_true: true,
+        is_false: false,
+        is_null: false,
+    };
+    const FALSE: Outcomes = Outcomes {
+        is_true: false,
+        is_false: true,
+        is_null: false,
+    };
+    const NULL: Outcomes = Outcomes {
+        is_true: false,
+        is_false: false,
+        is_null: true,
+    };
+
+    #[test]
+    fn three_valued_logic() {
+        assert_eq!(TRUE.and(NULL), NULL);
+        assert_eq!(FALSE.and(NULL), FALSE);
+        assert_eq!(TRUE.or(NULL), TRUE);
+        assert_eq!(FALSE.or(NULL), NULL);
+        assert_eq!(NULL.not(), NULL);
+        assert_eq!(Outcomes::ANY.and(FALSE), FALSE);
+        assert_eq!(Outcomes::ANY.or(TRUE), TRUE);
+    }
+}
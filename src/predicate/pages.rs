@@ -0,0 +1,339 @@
+use std::cmp::Ordering;
+
+use crate::error::{Error, Result};
+use crate::indexes::{
+    compute_page_row_intervals, intersect_intervals, push_interval, union_intervals, BooleanIndex,
+    BoundaryOrder, ByteIndex, FixedLenByteIndex, Index, Interval, NativeIndex, PageIndex,
+    PageLocation,
+};
+use crate::metadata::ColumnChunkMetaData;
+use crate::scalar::Scalar;
+use crate::schema::types::{PhysicalType, PrimitiveType};
+
+use super::scalar::is_float;
+use super::statistics::ColumnStatistics;
+use super::{CompareOp, LeafOp, Logic, Outcomes};
+
+/// The rows of a row group for which a predicate may be true, false or null
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct RowOutcomes {
+    pub is_true: Vec<Interval>,
+    pub is_false: Vec<Interval>,
+    pub is_null: Vec<Interval>,
+}
+
+impl RowOutcomes {
+    /// Any result is possible for all `num_rows` rows
+    fn any(num_rows: usize) -> Self {
+        let mut rows = vec![];
+        push_interval(&mut rows, Interval::new(0, num_rows));
+        Self {
+            is_true: rows.clone(),
+            is_false: rows.clone(),
+            is_null: rows,
+        }
+    }
+}
+
+impl Logic for RowOutcomes {
+    fn not(self) -> Self {
+        Self {
+            is_true: self.is_false,
+            is_false: self.is_true,
+            is_null: self.is_null,
+        }
+    }
+
+    fn and(self, other: Self) -> Self {
+        let lhs_null = intersect_intervals(
+            &self.is_null,
+            &union_intervals(&other.is_true, &other.is_null),
+        );
+        let rhs_null = intersect_intervals(
+            &other.is_null,
+            &union_intervals(&self.is_true, &self.is_null),
+        );
+        Self {
+            is_true: intersect_intervals(&self.is_true, &other.is_true),
+            is_false: union_intervals(&self.is_false, &other.is_false),
+            is_null: union_intervals(&lhs_null, &rhs_null),
+        }
+    }
+}
+
+/// The [`ColumnStatistics`] of `page`, whose min and max are converted by `scalar` unless
+/// `with_min_max` is false
+fn page_statistics<T, F: Fn(&T) -> Option<Scalar>>(
+    page: &PageIndex<T>,
+    is_float: bool,
+    with_min_max: bool,
+    scalar: F,
+) -> ColumnStatistics {
+    let min_max = if with_min_max {
+        let min = page.min.as_ref().and_then(&scalar);
+        let max = page.max.as_ref().and_then(&scalar);
+        min.zip(max)
+    } else {
+        None
+    };
+    ColumnStatistics {
+        min_max,
+        null_count: page.null_count,
+        // the min and max of null pages are not set
+        all_null: page.min.is_none() && page.max.is_none(),
+        is_float,
+    }
+}
+
+fn downcast<T: 'static>(index: &dyn Index) -> Result<&T> {
+    index
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| Error::oos("The column index does not match the type of its column"))
+}
+
+/// A column index downcasted to the type of its column
+enum TypedIndex<'a> {
+    Boolean(&'a BooleanIndex),
+    Int32(&'a NativeIndex<i32>),
+    Int64(&'a NativeIndex<i64>),
+    Int96(&'a NativeIndex<[u32; 3]>),
+    Float(&'a NativeIndex<f32>),
+    Double(&'a NativeIndex<f64>),
+    ByteArray(&'a ByteIndex),
+    FixedLenByteArray(&'a FixedLenByteIndex),
+}
+
+/// The pages of the column index of a column of `primitive_type`, whose
+/// [`ColumnStatistics`] are computed on demand
+struct IndexPages<'a> {
+    index: TypedIndex<'a>,
+    primitive_type: &'a PrimitiveType,
+    is_float: bool,
+}
+
+impl<'a> IndexPages<'a> {
+    fn try_new(index: &'a dyn Index, primitive_type: &'a PrimitiveType) -> Result<Self> {
+        let index = match primitive_type.physical_type {
+            PhysicalType::Boolean => TypedIndex::Boolean(downcast(index)?),
+            PhysicalType::Int32 => TypedIndex::Int32(downcast(index)?),
+            PhysicalType::Int64 => TypedIndex::Int64(downcast(index)?),
+            PhysicalType::Int96 => TypedIndex::Int96(downcast(index)?),
+            PhysicalType::Float => TypedIndex::Float(downcast(index)?),
+            PhysicalType::Double => TypedIndex::Double(downcast(index)?),
+            PhysicalType::ByteArray => TypedIndex::ByteArray(downcast(index)?),
+            PhysicalType::FixedLenByteArray(_) => TypedIndex::FixedLenByteArray(downcast(index)?),
+        };
+        Ok(Self {
+            index,
+            primitive_type,
+            is_float: is_float(primitive_type),
+        })
+    }
+
+    fn len(&self) -> usize {
+        match self.index {
+            TypedIndex::Boolean(index) => index.indexes.len(),
+            TypedIndex::Int32(index) => index.indexes.len(),
+            TypedIndex::Int64(index) => index.indexes.len(),
+            TypedIndex::Int96(index) => index.indexes.len(),
+            TypedIndex::Float(index) => index.indexes.len(),
+            TypedIndex::Double(index) => index.indexes.len(),
+            TypedIndex::ByteArray(index) => index.indexes.len(),
+            TypedIndex::FixedLenByteArray(index) => index.indexes.len(),
+        }
+    }
+
+    fn boundary_order(&self) -> BoundaryOrder {
+        match self.index {
+            TypedIndex::Boolean(index) => index.boundary_order,
+            TypedIndex::Int32(index) => index.boundary_order,
+            TypedIndex::Int64(index) => index.boundary_order,
+            TypedIndex::Int96(index) => index.boundary_order,
+            TypedIndex::Float(index) => index.boundary_order,
+            TypedIndex::Double(index) => index.boundary_order,
+            TypedIndex::ByteArray(index) => index.boundary_order,
+            TypedIndex::FixedLenByteArray(index) => index.boundary_order,
+        }
+    }
+
+    /// The [`ColumnStatistics`] of the `i`th page, without its min and max unless
+    /// `with_min_max`
+    fn get(&self, i: usize, with_min_max: bool) -> ColumnStatistics {
+        let primitive_type = self.primitive_type;
+        let is_float = self.is_float;
+        match self.index {
+            TypedIndex::Boolean(index) => {
+                page_statistics(&index.indexes[i], false, with_min_max, |value| {
+                    Some(Scalar::Boolean(*value))
+                })
+            }
+            TypedIndex::Int32(index) => {
+                page_statistics(&index.indexes[i], false, with_min_max, |value| {
+                    Scalar::from_int32(*value, primitive_type)
+                })
+            }
+            TypedIndex::Int64(index) => {
+                page_statistics(&index.indexes[i], false, with_min_max, |value| {
+                    Scalar::from_int64(*value, primitive_type)
+                })
+            }
+            // the order of INT96 is undefined
+            TypedIndex::Int96(index) => {
+                page_statistics(&index.indexes[i], false, with_min_max, |_| None)
+            }
+            TypedIndex::Float(index) => {
+                page_statistics(&index.indexes[i], is_float, with_min_max, |value| {
+                    Scalar::from_float(*value as f64)
+                })
+            }
+            TypedIndex::Double(index) => {
+                page_statistics(&index.indexes[i], is_float, with_min_max, |value| {
+                    Scalar::from_float(*value)
+                })
+            }
+            TypedIndex::ByteArray(index) => {
+                page_statistics(&index.indexes[i], is_float, with_min_max, |value| {
+                    Scalar::from_bytes(value, primitive_type)
+                })
+            }
+            TypedIndex::FixedLenByteArray(index) => {
+                page_statistics(&index.indexes[i], is_float, with_min_max, |value| {
+                    Scalar::from_bytes(value, primitive_type)
+                })
+            }
+        }
+    }
+}
+
+/// The number of leading `ordered` pages for which `f` of their min and max is true, which
+/// must be true for a prefix of them, found by binary search. `None` when the min and max of
+/// a page are unknown.
+fn partition<F: Fn(&Scalar, &Scalar) -> bool>(
+    pages: &IndexPages,
+    ordered: &[usize],
+    f: F,
+) -> Option<usize> {
+    let (mut low, mut high) = (0, ordered.len());
+    while low < high {
+        let middle = low + (high - low) / 2;
+        let (min, max) = pages.get(ordered[middle], true).min_max?;
+        if f(&min, &max) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    Some(low)
+}
+
+/// The outcomes of `value op literal` over each page of an ordered column index, computed
+/// from the positions of the literal among the min and max of its non-null pages by binary
+/// search instead of comparing it to every page.
+/// Returns `None` when the column index is not ordered or a page has no min or max.
+fn compare_ordered_pages(
+    pages: &IndexPages,
+    op: CompareOp,
+    literal: &Scalar,
+) -> Option<Vec<Outcomes>> {
+    let order = pages.boundary_order();
+    if order == BoundaryOrder::Unordered || literal.is_nan() {
+        return None;
+    }
+    // the order of a column index ignores its null pages
+    let mut ordered = (0..pages.len())
+        .filter(|i| !pages.get(*i, false).all_null)
+        .collect::<Vec<_>>();
+    if order == BoundaryOrder::Descending {
+        ordered.reverse();
+    }
+
+    // the pages whose min (max) is less than and less or equal to the literal are a prefix
+    let min_lt = partition(pages, &ordered, |min, _| min < literal)?;
+    let min_le = partition(pages, &ordered, |min, _| min <= literal)?;
+    let max_lt = partition(pages, &ordered, |_, max| max < literal)?;
+    let max_le = partition(pages, &ordered, |_, max| max <= literal)?;
+    let ordering = |position: usize, lt: usize, le: usize| {
+        Some(if position < lt {
+            Ordering::Less
+        } else if position < le {
+            Ordering::Equal
+        } else {
+            Ordering::Greater
+        })
+    };
+
+    let mut positions = vec![None; pages.len()];
+    ordered
+        .iter()
+        .enumerate()
+        .for_each(|(position, i)| positions[*i] = Some(position));
+    Some(
+        positions
+            .into_iter()
+            .enumerate()
+            .map(|(i, position)| {
+                let orderings = position.map(|position| {
+                    (
+                        ordering(position, min_lt, min_le),
+                        ordering(position, max_lt, max_le),
+                    )
+                });
+                pages.get(i, false).compare_orderings(op, orderings)
+            })
+            .collect(),
+    )
+}
+
+/// The outcomes of `op` over the rows of `column`, computed from the statistics of each of
+/// its pages in `indexes` (its column index and page locations). Comparisons over ordered
+/// column indexes use a binary search over the pages.
+/// Any outcome is possible when the column has no indexes or is repeated.
+pub(super) fn column_row_outcomes(
+    column: &ColumnChunkMetaData,
+    indexes: Option<(&dyn Index, &[PageLocation])>,
+    num_rows: usize,
+    op: &LeafOp,
+) -> Result<RowOutcomes> {
+    let descriptor = &column.descriptor().descriptor;
+    let (index, locations) = match indexes {
+        Some(indexes) if descriptor.max_rep_level == 0 => indexes,
+        _ => return Ok(RowOutcomes::any(num_rows)),
+    };
+
+    let pages = IndexPages::try_new(index, &descriptor.primitive_type)?;
+    let intervals = compute_page_row_intervals(locations, num_rows)?;
+    if pages.len() != intervals.len() {
+        return Err(Error::oos(
+            "The column index and the offset index must have the same number of pages",
+        ));
+    }
+
+    let ordered = match op {
+        LeafOp::Compare(op, literal) => compare_ordered_pages(&pages, *op, literal),
+        _ => None,
+    };
+    let page_outcomes = ordered.unwrap_or_else(|| {
+        (0..pages.len())
+            .map(|i| op.outcomes(&pages.get(i, true)))
+            .collect()
+    });
+
+    let mut outcomes = RowOutcomes {
+        is_true: vec![],
+        is_false: vec![],
+        is_null: vec![],
+    };
+    for (page, interval) in page_outcomes.into_iter().zip(intervals) {
+        if page.is_true {
+            push_interval(&mut outcomes.is_true, interval);
+        }
+        if page.is_false {
+            push_interval(&mut outcomes.is_false, interval);
+        }
+        if page.is_null {
+            push_interval(&mut outcomes.is_null, interval);
+        }
+    }
+    Ok(outcomes)
+}
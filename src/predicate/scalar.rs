@@ -0,0 +1,70 @@
+use crate::error::{Error, Result};
+use crate::metadata::{ColumnDescriptor, SortOrder};
+use crate::scalar::{is_float16, sort_order, Scalar};
+use crate::schema::types::{PhysicalType, PrimitiveType};
+
+use super::Literal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Boolean,
+    Integer,
+    Float,
+    Bytes,
+}
+
+fn kind(primitive_type: &PrimitiveType) -> Kind {
+    match primitive_type.physical_type {
+        PhysicalType::Boolean => Kind::Boolean,
+        PhysicalType::Int32 | PhysicalType::Int64 | PhysicalType::Int96 => Kind::Integer,
+        PhysicalType::Float | PhysicalType::Double => Kind::Float,
+        _ if is_float16(primitive_type) => Kind::Float,
+        // decimals
+        _ if sort_order(primitive_type) == SortOrder::Signed => Kind::Integer,
+        PhysicalType::ByteArray | PhysicalType::FixedLenByteArray(_) => Kind::Bytes,
+    }
+}
+
+/// Whether the values of `primitive_type` are floating point, whose NaNs are not part of
+/// their statistics
+pub(super) fn is_float(primitive_type: &PrimitiveType) -> bool {
+    kind(primitive_type) == Kind::Float
+}
+
+fn integer(literal: &Literal) -> Option<i128> {
+    match literal {
+        Literal::Int32(value) => Some(*value as i128),
+        Literal::Int64(value) => Some(*value as i128),
+        Literal::UInt32(value) => Some(*value as i128),
+        Literal::UInt64(value) => Some(*value as i128),
+        Literal::Decimal(value) => Some(*value),
+        _ => None,
+    }
+}
+
+impl Scalar {
+    /// Returns the [`Scalar`] of `literal` to compare with the values of `column`.
+    /// # Errors
+    /// Errors iff `literal` cannot be compared with the values of `column`.
+    pub fn try_from_literal(literal: &Literal, column: &ColumnDescriptor) -> Result<Self> {
+        let primitive_type = &column.descriptor.primitive_type;
+        let scalar = match (kind(primitive_type), literal) {
+            (Kind::Boolean, Literal::Boolean(value)) => Some(Scalar::Boolean(*value)),
+            (Kind::Integer, literal) => integer(literal).map(Scalar::Integer),
+            (Kind::Float, Literal::Float(value)) => Some(Scalar::Float(*value as f64)),
+            (Kind::Float, Literal::Double(value)) => Some(Scalar::Float(*value)),
+            (Kind::Float, literal) => integer(literal).map(|value| Scalar::Float(value as f64)),
+            (Kind::Bytes, Literal::String(value)) => Some(Scalar::Bytes(value.as_bytes().to_vec())),
+            (Kind::Bytes, Literal::Binary(value)) => Some(Scalar::Bytes(value.clone())),
+            _ => None,
+        };
+        scalar.ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "The literal {:?} cannot be compared with the column \"{}\" of type {:?}",
+                literal,
+                column.path_in_schema.join("."),
+                primitive_type.physical_type
+            ))
+        })
+    }
+}
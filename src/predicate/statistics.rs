@@ -0,0 +1,267 @@
+use std::cmp::Ordering;
+
+use crate::error::Result;
+use crate::metadata::ColumnChunkMetaData;
+use crate::scalar::Scalar;
+use crate::schema::types::{PhysicalType, PrimitiveType};
+use crate::statistics::{
+    BinaryStatistics, BooleanStatistics, FixedLenStatistics, PrimitiveStatistics, Statistics,
+};
+
+use super::scalar::is_float;
+use super::{CompareOp, Outcomes};
+
+/// The statistics of a set of values of a column
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct ColumnStatistics {
+    /// Lower and upper bounds of the non-null values, `None` when unknown. They are only used
+    /// as bounds since binary statistics may be truncated, in which case they are not values
+    /// of the column (and the min differs from the max).
+    pub min_max: Option<(Scalar, Scalar)>,
+    /// The number of nulls, `None` when unknown
+    pub null_count: Option<i64>,
+    /// Whether all values are null
+    pub all_null: bool,
+    /// Whether the values are floating point, whose NaNs are not part of `min_max`
+    pub is_float: bool,
+}
+
+impl ColumnStatistics {
+    fn may_be_null(&self) -> bool {
+        self.null_count.map(|count| count > 0).unwrap_or(true)
+    }
+
+    /// The outcomes of a predicate over the non-null values, whose `(is_true, is_false)`
+    /// are computed from the `min_max`
+    fn outcomes<F: Fn(&Scalar, &Scalar) -> (bool, bool)>(&self, is_true_false: F) -> Outcomes {
+        let is_true_false = self
+            .min_max
+            .as_ref()
+            .map(|(min, max)| is_true_false(min, max));
+        self.outcomes_of(is_true_false)
+    }
+
+    /// The outcomes of a predicate whose `(is_true, is_false)` over the non-null values are
+    /// `is_true_false`, `None` when unknown
+    fn outcomes_of(&self, is_true_false: Option<(bool, bool)>) -> Outcomes {
+        let is_null = self.may_be_null();
+        if self.all_null {
+            return Outcomes {
+                is_true: false,
+                is_false: false,
+                is_null,
+            };
+        }
+        let (is_true, is_false) = is_true_false.unwrap_or((true, true));
+        Outcomes {
+            is_true,
+            is_false,
+            is_null,
+        }
+    }
+
+    /// The outcomes of `value op literal`
+    pub fn compare(&self, op: CompareOp, literal: &Scalar) -> Outcomes {
+        if literal.is_nan() {
+            // comparisons with NaN are only true for `!=`
+            return self.outcomes(|_, _| (op == CompareOp::NotEq, op != CompareOp::NotEq));
+        }
+        let orderings = self
+            .min_max
+            .as_ref()
+            .map(|(min, max)| (min.partial_cmp(literal), max.partial_cmp(literal)));
+        self.compare_orderings(op, orderings)
+    }
+
+    /// The outcomes of `value op literal` from the orderings of the min and of the max
+    /// relative to a literal that is not NaN, `None` when the min and max are unknown
+    pub fn compare_orderings(
+        &self,
+        op: CompareOp,
+        orderings: Option<(Option<Ordering>, Option<Ordering>)>,
+    ) -> Outcomes {
+        let is_true_false = orderings.map(|(min, max)| {
+            let min_le = matches!(min, Some(Ordering::Less | Ordering::Equal));
+            let max_ge = matches!(max, Some(Ordering::Greater | Ordering::Equal));
+            let all_equal = min == Some(Ordering::Equal) && max == Some(Ordering::Equal);
+            let in_range = min_le && max_ge;
+            match op {
+                CompareOp::Eq => (in_range, !all_equal),
+                CompareOp::NotEq => (!all_equal, in_range),
+                CompareOp::Lt => (min == Some(Ordering::Less), max_ge),
+                CompareOp::LtEq => (min_le, max == Some(Ordering::Greater)),
+                CompareOp::Gt => (max == Some(Ordering::Greater), min_le),
+                CompareOp::GtEq => (max_ge, min == Some(Ordering::Less)),
+            }
+        });
+        let mut outcomes = self.outcomes_of(is_true_false);
+        if self.is_float && !self.all_null {
+            // a NaN value may be present, which is only different from the literal
+            outcomes.is_true |= op == CompareOp::NotEq;
+            outcomes.is_false |= op != CompareOp::NotEq;
+        }
+        outcomes
+    }
+
+    /// The outcomes of `value IN (literals)`
+    pub fn is_in(&self, literals: &[Scalar]) -> Outcomes {
+        let mut outcomes = self.outcomes(|min, max| {
+            let is_true = literals
+                .iter()
+                .any(|literal| min <= literal && literal <= max);
+            let is_false = min != max || !literals.contains(min);
+            (is_true, is_false)
+        });
+        if self.is_float && !self.all_null {
+            outcomes.is_false = true;
+        }
+        outcomes
+    }
+
+    /// The outcomes of `value IS NULL`
+    pub fn is_null(&self) -> Outcomes {
+        Outcomes {
+            is_true: self.may_be_null(),
+            is_false: !self.all_null,
+            is_null: false,
+        }
+    }
+}
+
+/// The minimum and maximum of `statistics`, or `None` if they are unknown or not ordered
+fn min_max(
+    statistics: &dyn Statistics,
+    primitive_type: &PrimitiveType,
+) -> Option<(Scalar, Scalar)> {
+    let statistics = statistics.as_any();
+    let (min, max) = match primitive_type.physical_type {
+        PhysicalType::Boolean => {
+            let statistics = statistics.downcast_ref::<BooleanStatistics>()?;
+            (
+                statistics.min_value.map(Scalar::Boolean),
+                statistics.max_value.map(Scalar::Boolean),
+            )
+        }
+        PhysicalType::Int32 => {
+            let statistics = statistics.downcast_ref::<PrimitiveStatistics<i32>>()?;
+            let scalar = |value| Scalar::from_int32(value, primitive_type);
+            (
+                statistics.min_value.and_then(scalar),
+                statistics.max_value.and_then(scalar),
+            )
+        }
+        PhysicalType::Int64 => {
+            let statistics = statistics.downcast_ref::<PrimitiveStatistics<i64>>()?;
+            let scalar = |value| Scalar::from_int64(value, primitive_type);
+            (
+                statistics.min_value.and_then(scalar),
+                statistics.max_value.and_then(scalar),
+            )
+        }
+        // the order of INT96 is undefined
+        PhysicalType::Int96 => (None, None),
+        PhysicalType::Float => {
+            let statistics = statistics.downcast_ref::<PrimitiveStatistics<f32>>()?;
+            let scalar = |value: f32| Scalar::from_float(value as f64);
+            (
+                statistics.min_value.and_then(scalar),
+                statistics.max_value.and_then(scalar),
+            )
+        }
+        PhysicalType::Double => {
+            let statistics = statistics.downcast_ref::<PrimitiveStatistics<f64>>()?;
+            (
+                statistics.min_value.and_then(Scalar::from_float),
+                statistics.max_value.and_then(Scalar::from_float),
+            )
+        }
+        PhysicalType::ByteArray => {
+            let statistics = statistics.downcast_ref::<BinaryStatistics>()?;
+            let scalar = |value: &Vec<u8>| Scalar::from_bytes(value, primitive_type);
+            (
+                statistics.min_value.as_ref().and_then(scalar),
+                statistics.max_value.as_ref().and_then(scalar),
+            )
+        }
+        PhysicalType::FixedLenByteArray(_) => {
+            let statistics = statistics.downcast_ref::<FixedLenStatistics>()?;
+            let scalar = |value: &Vec<u8>| Scalar::from_bytes(value, primitive_type);
+            (
+                statistics.min_value.as_ref().and_then(scalar),
+                statistics.max_value.as_ref().and_then(scalar),
+            )
+        }
+    };
+    min.zip(max)
+}
+
+/// The outcomes of a predicate over `column`, computed by `f` from the statistics of the
+/// column chunk. Any outcome is possible when the column chunk has no statistics or
+/// its column is repeated, since the statistics of a repeated column are not per row.
+pub(super) fn column_outcomes(
+    column: &ColumnChunkMetaData,
+    f: &dyn Fn(&ColumnStatistics) -> Outcomes,
+) -> Result<Outcomes> {
+    let descriptor = &column.descriptor().descriptor;
+    if descriptor.max_rep_level > 0 {
+        return Ok(Outcomes::ANY);
+    }
+    let statistics = match column.statistics() {
+        Some(statistics) => statistics?,
+        None => return Ok(Outcomes::ANY),
+    };
+    let primitive_type = &descriptor.primitive_type;
+    let null_count = statistics.null_count();
+    let statistics = ColumnStatistics {
+        min_max: min_max(statistics.as_ref(), primitive_type),
+        null_count,
+        all_null: null_count == Some(column.num_values()),
+        is_float: is_float(primitive_type),
+    };
+    Ok(f(&statistics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statistics(min: Scalar, max: Scalar, is_float: bool) -> ColumnStatistics {
+        ColumnStatistics {
+            min_max: Some((min, max)),
+            null_count: Some(0),
+            all_null: false,
+            is_float,
+        }
+    }
+
+    #[test]
+    fn compare() {
+        let stats = statistics(Scalar::Integer(1), Scalar::Integer(1), false);
+        let outcomes = stats.compare(CompareOp::Eq, &Scalar::Integer(1));
+        assert!(outcomes.is_true && !outcomes.is_false && !outcomes.is_null);
+        let outcomes = stats.is_in(&[Scalar::Integer(0), Scalar::Integer(1)]);
+        assert!(outcomes.is_true && !outcomes.is_false);
+        let outcomes = stats.is_in(&[]);
+        assert!(!outcomes.is_true && outcomes.is_false);
+
+        // NaNs may be present in floating point columns
+        let stats = statistics(Scalar::Float(1.0), Scalar::Float(1.0), true);
+        let outcomes = stats.compare(CompareOp::Eq, &Scalar::Float(1.0));
+        assert!(outcomes.is_true && outcomes.is_false);
+        let outcomes = stats.compare(CompareOp::NotEq, &Scalar::Float(2.0));
+        assert!(outcomes.is_true);
+        let outcomes = stats.compare(CompareOp::Eq, &Scalar::Float(f64::NAN));
+        assert!(!outcomes.is_true);
+
+        let stats = ColumnStatistics {
+            min_max: None,
+            null_count: Some(3),
+            all_null: true,
+            is_float: false,
+        };
+        let outcomes = stats.compare(CompareOp::NotEq, &Scalar::Integer(1));
+        assert!(!outcomes.is_true && !outcomes.is_false && outcomes.is_null);
+        let outcomes = stats.is_null();
+        assert!(outcomes.is_true && !outcomes.is_false);
+    }
+}
@@ -1,12 +1,18 @@
 use std::convert::TryInto;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 
+#[cfg(feature = "async")]
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
 use parquet_format_safe::ColumnChunk;
 use parquet_format_safe::{thrift::protocol::TCompactInputProtocol, OffsetIndex, PageLocation};
 
+#[cfg(feature = "encryption")]
+use crate::encryption::ColumnDecryptor;
 use crate::error::Error;
 use crate::indexes::Index;
 use crate::metadata::ColumnChunkMetaData;
+use crate::read::check_not_encrypted;
 
 use super::deserialize::deserialize;
 
@@ -79,19 +85,134 @@ fn deserialize_column_indexes(
 
 /// Reads the column indexes of all [`ColumnChunkMetaData`] and deserializes them into [`Index`].
 /// Returns an empty vector if indexes are not available
+/// # Errors
+/// Errors if any of the column chunks is encrypted (see [`read_columns_indexes_with_decryption`])
 pub fn read_columns_indexes<R: Read + Seek>(
     reader: &mut R,
     chunks: &[ColumnChunkMetaData],
 ) -> Result<Vec<Box<dyn Index>>, Error> {
+    check_indexes_not_encrypted(chunks)?;
     let (offset, lengths) = prepare_column_index_read(chunks)?;
 
     let length = lengths.iter().sum::<usize>();
+    let data = read_range(reader, offset, length)?;
 
+    deserialize_column_indexes(chunks, &data, lengths)
+}
+
+/// Reads the column indexes of all [`ColumnChunkMetaData`] and deserializes them into [`Index`],
+/// decrypting the indexes of the encrypted column chunks with their `decryptors`, one per chunk.
+/// Returns an empty vector if indexes are not available
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub fn read_columns_indexes_with_decryption<R: Read + Seek>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+    decryptors: &[Option<ColumnDecryptor>],
+) -> Result<Vec<Box<dyn Index>>, Error> {
+    let (offset, lengths) = prepare_column_index_read(chunks)?;
+
+    let length = lengths.iter().sum::<usize>();
+    let data = read_range(reader, offset, length)?;
+
+    let (data, lengths) = decrypt_indexes(&data, lengths, decryptors, |decryptor, module| {
+        decryptor.decrypt_column_index(module)
+    })?;
+    deserialize_column_indexes(chunks, &data, lengths)
+}
+
+/// Errors iff any of `chunks` is encrypted, since its indexes can't be read without decryption.
+fn check_indexes_not_encrypted(chunks: &[ColumnChunkMetaData]) -> Result<(), Error> {
+    chunks
+        .iter()
+        .try_for_each(|chunk| check_not_encrypted(chunk, "Reading indexes"))
+}
+
+/// Reads `length` bytes starting at `offset`.
+fn read_range<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    length: usize,
+) -> Result<Vec<u8>, Error> {
     reader.seek(SeekFrom::Start(offset))?;
 
     let mut data = vec![];
     data.try_reserve(length)?;
     reader.by_ref().take(length as u64).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Decrypts the consecutive indexes of `lengths` bytes in `data` with `decrypt` and their
+/// `decryptors`, returning the plaintext indexes and their lengths.
+#[cfg(feature = "encryption")]
+fn decrypt_indexes<F: Fn(&ColumnDecryptor, &[u8]) -> Result<Vec<u8>, Error>>(
+    data: &[u8],
+    lengths: Vec<usize>,
+    decryptors: &[Option<ColumnDecryptor>],
+    decrypt: F,
+) -> Result<(Vec<u8>, Vec<usize>), Error> {
+    if decryptors.len() != lengths.len() {
+        return Err(Error::InvalidParameter(
+            "There must be one decryptor per column chunk".to_string(),
+        ));
+    }
+    let mut plaintext = Vec::with_capacity(data.len());
+    let mut start = 0;
+    let lengths = lengths
+        .into_iter()
+        .zip(decryptors)
+        .map(|(length, decryptor)| {
+            let module = data
+                .get(start..start + length)
+                .ok_or_else(|| Error::oos("The index is larger than the data read"))?;
+            start += length;
+            match decryptor {
+                Some(decryptor) => {
+                    let index = decrypt(decryptor, module)?;
+                    plaintext.extend_from_slice(&index);
+                    Ok(index.len())
+                }
+                None => {
+                    plaintext.extend_from_slice(module);
+                    Ok(length)
+                }
+            }
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok((plaintext, lengths))
+}
+
+/// Asynchronously reads `length` bytes starting at `offset`, with a single ranged read.
+#[cfg(feature = "async")]
+async fn read_range_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+    reader: &mut R,
+    offset: u64,
+    length: usize,
+) -> Result<Vec<u8>, Error> {
+    reader.seek(SeekFrom::Start(offset)).await?;
+
+    let mut data = vec![];
+    data.try_reserve(length)?;
+    reader.take(length as u64).read_to_end(&mut data).await?;
+    Ok(data)
+}
+
+/// Asynchronously reads the column indexes of all [`ColumnChunkMetaData`] and deserializes them
+/// into [`Index`].
+/// Returns an empty vector if indexes are not available
+/// # Errors
+/// Errors if any of the column chunks is encrypted
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn read_columns_indexes_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Box<dyn Index>>, Error> {
+    check_indexes_not_encrypted(chunks)?;
+    let (offset, lengths) = prepare_column_index_read(chunks)?;
+
+    let length = lengths.iter().sum::<usize>();
+    let data = read_range_async(reader, offset, length).await?;
 
     deserialize_column_indexes(chunks, &data, lengths)
 }
@@ -114,19 +235,57 @@ fn deserialize_page_locations(
 
 /// Read [`PageLocation`]s from the [`ColumnChunkMetaData`]s.
 /// Returns an empty vector if indexes are not available
+/// # Errors
+/// Errors if any of the column chunks is encrypted (see [`read_pages_locations_with_decryption`])
 pub fn read_pages_locations<R: Read + Seek>(
     reader: &mut R,
     chunks: &[ColumnChunkMetaData],
 ) -> Result<Vec<Vec<PageLocation>>, Error> {
+    check_indexes_not_encrypted(chunks)?;
     let (offset, lengths) = prepare_offset_index_read(chunks)?;
 
     let length = lengths.iter().sum::<usize>();
+    let data = read_range(reader, offset, length)?;
 
-    reader.seek(SeekFrom::Start(offset))?;
+    deserialize_page_locations(&data, chunks.len())
+}
 
-    let mut data = vec![];
-    data.try_reserve(length)?;
-    reader.by_ref().take(length as u64).read_to_end(&mut data)?;
+/// Read [`PageLocation`]s from the [`ColumnChunkMetaData`]s, decrypting the offset indexes of
+/// the encrypted column chunks with their `decryptors`, one per chunk.
+/// Returns an empty vector if indexes are not available
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub fn read_pages_locations_with_decryption<R: Read + Seek>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+    decryptors: &[Option<ColumnDecryptor>],
+) -> Result<Vec<Vec<PageLocation>>, Error> {
+    let (offset, lengths) = prepare_offset_index_read(chunks)?;
+
+    let length = lengths.iter().sum::<usize>();
+    let data = read_range(reader, offset, length)?;
+
+    let (data, _) = decrypt_indexes(&data, lengths, decryptors, |decryptor, module| {
+        decryptor.decrypt_offset_index(module)
+    })?;
+    deserialize_page_locations(&data, chunks.len())
+}
+
+/// Asynchronously reads [`PageLocation`]s from the [`ColumnChunkMetaData`]s.
+/// Returns an empty vector if indexes are not available
+/// # Errors
+/// Errors if any of the column chunks is encrypted
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn read_pages_locations_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Vec<PageLocation>>, Error> {
+    check_indexes_not_encrypted(chunks)?;
+    let (offset, lengths) = prepare_offset_index_read(chunks)?;
+
+    let length = lengths.iter().sum::<usize>();
+    let data = read_range_async(reader, offset, length).await?;
 
     deserialize_page_locations(&data, chunks.len())
 }
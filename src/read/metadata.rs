@@ -8,9 +8,14 @@ use parquet_format_safe::thrift::protocol::TCompactInputProtocol;
 use parquet_format_safe::FileMetaData as TFileMetaData;
 
 use super::super::{
-    metadata::FileMetaData, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, HEADER_SIZE, PARQUET_MAGIC,
+    metadata::FileMetaData, DEFAULT_FOOTER_READ_SIZE, ENCRYPTED_MAGIC, FOOTER_SIZE, HEADER_SIZE,
+    PARQUET_MAGIC,
 };
 
+#[cfg(feature = "encryption")]
+use crate::encryption::{decrypt_metadata, FileDecryptionProperties, FileDecryptor};
+#[cfg(not(feature = "encryption"))]
+use crate::error::Feature;
 use crate::error::{Error, Result};
 
 pub(super) fn metadata_len(buffer: &[u8], len: usize) -> i32 {
@@ -38,11 +43,28 @@ pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
     read_metadata_with_size(reader, file_size)
 }
 
-/// Reads a [`FileMetaData`] from the reader, located at the end of the file, with known file size.
-pub fn read_metadata_with_size<R: Read + Seek>(
-    reader: &mut R,
-    file_size: u64,
-) -> Result<FileMetaData> {
+/// Errors iff `magic` is not the magic of a parquet file with a plaintext footer.
+pub(super) fn check_magic(magic: &[u8]) -> Result<()> {
+    if magic == ENCRYPTED_MAGIC {
+        #[cfg(feature = "encryption")]
+        return Err(Error::InvalidParameter(
+            "The footer of this file is encrypted and must be read with `read_metadata_with_decryption`"
+                .to_string(),
+        ));
+        #[cfg(not(feature = "encryption"))]
+        return Err(Error::FeatureNotActive(
+            Feature::Encryption,
+            "read files with an encrypted footer".to_string(),
+        ));
+    }
+    if magic != PARQUET_MAGIC {
+        return Err(Error::oos("The file must end with PAR1"));
+    }
+    Ok(())
+}
+
+/// Reads the footer of the file, i.e. the bytes of the metadata followed by the magic.
+fn read_footer<R: Read + Seek>(reader: &mut R, file_size: u64) -> Result<(Vec<u8>, [u8; 4])> {
     if file_size < HEADER_SIZE + FOOTER_SIZE {
         return Err(Error::oos(
             "A parquet file must containt a header and footer with at least 12 bytes",
@@ -59,8 +81,8 @@ pub fn read_metadata_with_size<R: Read + Seek>(
         .take(default_end_len as u64)
         .read_to_end(&mut buffer)?;
 
-    // check this is indeed a parquet file
-    if buffer[default_end_len - 4..] != PARQUET_MAGIC {
+    let magic: [u8; 4] = buffer[default_end_len - 4..].try_into().unwrap();
+    if magic != PARQUET_MAGIC && magic != ENCRYPTED_MAGIC {
         return Err(Error::oos("The file must end with PAR1"));
     }
 
@@ -75,10 +97,10 @@ pub fn read_metadata_with_size<R: Read + Seek>(
         ));
     }
 
-    let reader: &[u8] = if (footer_len as usize) < buffer.len() {
+    if (footer_len as usize) < buffer.len() {
         // the whole metadata is in the bytes we already read
         let remaining = buffer.len() - footer_len as usize;
-        &buffer[remaining..]
+        buffer.drain(..remaining);
     } else {
         // the end of file read by default is not long enough, read again including the metadata.
         reader.seek(SeekFrom::End(-(footer_len as i64)))?;
@@ -86,14 +108,40 @@ pub fn read_metadata_with_size<R: Read + Seek>(
         buffer.clear();
         buffer.try_reserve(footer_len as usize)?;
         reader.take(footer_len).read_to_end(&mut buffer)?;
-
-        &buffer
     };
+    buffer.truncate(metadata_len as usize);
+
+    Ok((buffer, magic))
+}
+
+/// Reads a [`FileMetaData`] from the reader, located at the end of the file, with known file size.
+pub fn read_metadata_with_size<R: Read + Seek>(
+    reader: &mut R,
+    file_size: u64,
+) -> Result<FileMetaData> {
+    let (footer, magic) = read_footer(reader, file_size)?;
+    check_magic(&magic)?;
 
     // a highly nested but sparse struct could result in many allocations
-    let max_size = reader.len() * 2 + 1024;
+    let max_size = footer.len() * 2 + 1024;
+
+    deserialize_metadata(footer.as_slice(), max_size)
+}
 
-    deserialize_metadata(reader, max_size)
+/// Reads a [`FileMetaData`] from the reader, located at the end of the file, decrypting
+/// the footer and column metadata encrypted with the keys declared in `properties`.
+///
+/// The returned [`FileDecryptor`] is used to decrypt the pages of each column chunk via
+/// [`crate::read::PageReader::with_decryptor`]. It is `None` when the file is not encrypted.
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub fn read_metadata_with_decryption<R: Read + Seek>(
+    reader: &mut R,
+    properties: &FileDecryptionProperties,
+) -> Result<(FileMetaData, Option<FileDecryptor>)> {
+    let file_size = stream_len(reader)?;
+    let (footer, magic) = read_footer(reader, file_size)?;
+    decrypt_metadata(&footer, magic == ENCRYPTED_MAGIC, properties)
 }
 
 /// Parse loaded metadata bytes
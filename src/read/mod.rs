@@ -4,6 +4,7 @@ mod indexes;
 pub mod levels;
 mod metadata;
 mod page;
+mod rows;
 #[cfg(feature = "async")]
 mod stream;
 
@@ -12,20 +13,50 @@ use std::sync::Arc;
 
 pub use column::*;
 pub use compression::{decompress, BasicDecompressor, Decompressor};
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub use metadata::read_metadata_with_decryption;
 pub use metadata::{deserialize_metadata, read_metadata, read_metadata_with_size};
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
-pub use page::{get_page_stream, get_page_stream_from_column_start};
+pub use page::{get_indexed_page_stream, get_page_stream, get_page_stream_from_column_start};
 pub use page::{IndexedPageReader, PageFilter, PageIterator, PageMetaData, PageReader};
+pub(crate) use rows::decimal_from_bytes;
+pub use rows::{rows, Field, Row, RowIterator};
 
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub use stream::read_metadata as read_metadata_async;
 
 use crate::metadata::{ColumnChunkMetaData, RowGroupMetaData};
-use crate::{error::Result, metadata::FileMetaData};
+use crate::predicate::Predicate;
+use crate::{
+    error::{Error, Result},
+    metadata::FileMetaData,
+};
 
 pub use indexes::{read_columns_indexes, read_pages_locations};
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use indexes::{read_columns_indexes_async, read_pages_locations_async};
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub use indexes::{read_columns_indexes_with_decryption, read_pages_locations_with_decryption};
+
+/// Errors iff `column_chunk` is encrypted, since `operation` does not support encrypted
+/// column chunks.
+pub(crate) fn check_not_encrypted(
+    column_chunk: &ColumnChunkMetaData,
+    operation: &str,
+) -> Result<()> {
+    if column_chunk.column_chunk().crypto_metadata.is_some() {
+        return Err(Error::FeatureNotSupported(format!(
+            "{} of encrypted column chunks",
+            operation
+        )));
+    }
+    Ok(())
+}
 
 /// Filters row group metadata to only those row groups,
 /// for which the predicate function returns true
@@ -44,6 +75,25 @@ pub fn filter_row_groups(
     metadata
 }
 
+/// Filters row group metadata to only those row groups whose statistics do not rule out
+/// that some of their rows match `predicate`
+/// # Errors
+/// Errors iff `predicate` is not valid for the schema of `metadata` (see [`Predicate::may_match`])
+pub fn filter_row_groups_with_predicate(
+    metadata: &FileMetaData,
+    predicate: &Predicate,
+) -> Result<FileMetaData> {
+    let mut filtered_row_groups = Vec::<RowGroupMetaData>::new();
+    for row_group_metadata in metadata.row_groups.iter() {
+        if predicate.may_match(row_group_metadata)? {
+            filtered_row_groups.push(row_group_metadata.clone());
+        }
+    }
+    let mut metadata = metadata.clone();
+    metadata.row_groups = filtered_row_groups;
+    Ok(metadata)
+}
+
 /// Returns a new [`PageReader`] by seeking `reader` to the begining of `column_chunk`.
 pub fn get_page_iterator<R: Read + Seek>(
     column_chunk: &ColumnChunkMetaData,
@@ -3,6 +3,8 @@ use std::{
     io::{Cursor, Read, Seek, SeekFrom},
 };
 
+#[cfg(feature = "encryption")]
+use crate::encryption::ColumnDecryptor;
 use crate::{
     error::Error,
     indexes::{FilteredPage, Interval},
@@ -11,7 +13,7 @@ use crate::{
     parquet_bridge::Compression,
 };
 
-use super::reader::{finish_page, read_page_header, PageMetaData};
+use super::reader::{finish_page, read_page_header, verify_crc, PageMetaData};
 
 #[derive(Debug, Clone, Copy)]
 enum State {
@@ -41,15 +43,25 @@ pub struct IndexedPageReader<R: Read + Seek> {
     pages: VecDeque<FilteredPage>,
 
     state: State,
+
+    // Whether to verify the CRC32 checksum of pages that declare one
+    verify_crc: bool,
+
+    // Decrypts the page headers and pages of encrypted column chunks
+    #[cfg(feature = "encryption")]
+    decryptor: Option<ColumnDecryptor>,
+
+    // the ordinal of the next data page in the column chunk, including skipped pages
+    #[cfg(feature = "encryption")]
+    page: usize,
 }
 
-fn read_page<R: Read + Seek>(
+fn read_buffer<R: Read + Seek>(
     reader: &mut R,
     start: u64,
     length: usize,
     buffer: &mut Vec<u8>,
-    data: &mut Vec<u8>,
-) -> Result<ParquetPageHeader, Error> {
+) -> Result<(), Error> {
     // seek to the page
     reader.seek(SeekFrom::Start(start))?;
 
@@ -57,7 +69,15 @@ fn read_page<R: Read + Seek>(
     buffer.clear();
     buffer.try_reserve(length)?;
     reader.by_ref().take(length as u64).read_to_end(buffer)?;
+    Ok(())
+}
 
+/// Deserializes the header of a page from `buffer` ([header][data]), copying its [data] into `data`.
+pub(super) fn deserialize_page(
+    buffer: &[u8],
+    data: &mut Vec<u8>,
+    check_crc: bool,
+) -> Result<ParquetPageHeader, Error> {
     // deserialize [header]
     let mut reader = Cursor::new(buffer);
     let page_header = read_page_header(&mut reader, 1024 * 1024)?;
@@ -67,21 +87,37 @@ fn read_page<R: Read + Seek>(
     // copy [data]
     data.clear();
     data.extend_from_slice(&buffer[header_size..]);
+
+    if check_crc {
+        verify_crc(&page_header, data)?;
+    }
     Ok(page_header)
 }
 
-fn read_dict_page<R: Read + Seek>(
-    reader: &mut R,
-    start: u64,
-    length: usize,
-    buffer: &mut Vec<u8>,
+/// Decrypts the header of an encrypted page from `buffer` ([header][data]), decrypting its
+/// [data] into `data`. The CRC32 checksum is verified on the encrypted [data].
+#[cfg(feature = "encryption")]
+fn decrypt_page(
+    decryptor: &mut ColumnDecryptor,
+    buffer: &[u8],
     data: &mut Vec<u8>,
-    compression: Compression,
-    descriptor: &Descriptor,
-) -> Result<CompressedDictPage, Error> {
-    let page_header = read_page(reader, start, length, buffer, data)?;
+    check_crc: bool,
+) -> Result<ParquetPageHeader, Error> {
+    let mut reader = Cursor::new(buffer);
+    let page_header = decryptor.read_page_header(&mut reader, 1024 * 1024)?;
+    let header_size = reader.position() as usize;
+
+    data.clear();
+    data.extend_from_slice(&buffer[header_size..]);
 
-    let page = finish_page(page_header, data, compression, descriptor, None)?;
+    if check_crc {
+        verify_crc(&page_header, data)?;
+    }
+    decryptor.decrypt_page(data)?;
+    Ok(page_header)
+}
+
+fn into_dict_page(page: CompressedPage) -> Result<CompressedDictPage, Error> {
     if let CompressedPage::Dict(page) = page {
         Ok(page)
     } else {
@@ -121,14 +157,52 @@ impl<R: Read + Seek> IndexedPageReader<R> {
             data_buffer,
             pages,
             state: State::MaybeDict,
+            verify_crc: false,
+            #[cfg(feature = "encryption")]
+            decryptor: None,
+            #[cfg(feature = "encryption")]
+            page: 0,
         }
     }
 
+    /// Sets whether the CRC32 checksum of each page that declares one is verified.
+    /// When enabled, a page whose checksum does not match its data results in [`Error::InvalidChecksum`].
+    pub fn with_crc_check(mut self, verify_crc: bool) -> Self {
+        self.verify_crc = verify_crc;
+        self
+    }
+
+    /// Sets the [`ColumnDecryptor`] used to decrypt the page headers and pages of an encrypted
+    /// column chunk, obtained from [`crate::encryption::FileDecryptor::column_decryptor`].
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn with_decryptor(mut self, decryptor: ColumnDecryptor) -> Self {
+        self.decryptor = Some(decryptor);
+        self
+    }
+
     /// consumes self into the reader and the two internal buffers
     pub fn into_inner(self) -> (R, Vec<u8>, Vec<u8>) {
         (self.reader, self.buffer, self.data_buffer)
     }
 
+    /// Reads the page at `start` of `length` bytes, returning its header and copying its
+    /// (decrypted) data into `data`.
+    fn read_page_header(
+        &mut self,
+        start: u64,
+        length: usize,
+        data: &mut Vec<u8>,
+    ) -> Result<ParquetPageHeader, Error> {
+        read_buffer(&mut self.reader, start, length, &mut self.buffer)?;
+
+        #[cfg(feature = "encryption")]
+        if let Some(decryptor) = self.decryptor.as_mut() {
+            return decrypt_page(decryptor, &self.buffer, data, self.verify_crc);
+        }
+        deserialize_page(&self.buffer, data, self.verify_crc)
+    }
+
     fn read_page(
         &mut self,
         start: u64,
@@ -138,7 +212,7 @@ impl<R: Read + Seek> IndexedPageReader<R> {
         // it will be read - take buffer
         let mut data = std::mem::take(&mut self.data_buffer);
 
-        let page_header = read_page(&mut self.reader, start, length, &mut self.buffer, &mut data)?;
+        let page_header = self.read_page_header(start, length, &mut data)?;
 
         finish_page(
             page_header,
@@ -167,15 +241,18 @@ impl<R: Read + Seek> IndexedPageReader<R> {
         // it will be read - take buffer
         let mut data = std::mem::take(&mut self.data_buffer);
 
-        let maybe_page = read_dict_page(
-            &mut self.reader,
-            start,
-            length,
-            &mut self.buffer,
-            &mut data,
-            self.compression,
-            &self.descriptor,
-        );
+        let maybe_page = self
+            .read_page_header(start, length, &mut data)
+            .and_then(|page_header| {
+                finish_page(
+                    page_header,
+                    &mut data,
+                    self.compression,
+                    &self.descriptor,
+                    None,
+                )
+            })
+            .and_then(into_dict_page);
         Some(maybe_page.map(CompressedPage::Dict))
     }
 }
@@ -195,6 +272,13 @@ impl<R: Read + Seek> Iterator for IndexedPageReader<R> {
             }
             State::Data => {
                 if let Some(page) = self.pages.pop_front() {
+                    #[cfg(feature = "encryption")]
+                    {
+                        if let Some(decryptor) = self.decryptor.as_mut() {
+                            decryptor.set_data_page(self.page);
+                        }
+                        self.page += 1;
+                    }
                     if page.selected_rows.is_empty() {
                         self.next()
                     } else {
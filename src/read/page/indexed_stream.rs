@@ -0,0 +1,103 @@
+use std::io::SeekFrom;
+
+use async_stream::try_stream;
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, Stream};
+
+use crate::error::{Error, Result};
+use crate::indexes::FilteredPage;
+use crate::metadata::ColumnChunkMetaData;
+use crate::page::CompressedPage;
+use crate::read::check_not_encrypted;
+
+use super::indexed_reader::deserialize_page;
+use super::reader::{finish_page, PageMetaData};
+
+/// Returns a stream of compressed pages that only reads the dictionary page (if any) and the
+/// pages selected in `pages`, e.g. via [`crate::indexes::select_pages`]. Each page is read with
+/// a single ranged read.
+///
+/// This is the async counterpart of [`super::IndexedPageReader`]: the data pages of this stream
+/// always have [`Some`] [`crate::page::CompressedDataPage::selected_rows()`].
+///
+/// When `verify_crc` is set, the CRC32 checksum of each page that declares one is verified
+/// and the stream yields [`Error::InvalidChecksum`] when a page's data does not match it.
+/// # Errors
+/// Errors if the column chunk is encrypted, which only [`super::IndexedPageReader`] supports.
+pub async fn get_indexed_page_stream<'a, RR: AsyncRead + AsyncSeek + Unpin + Send>(
+    column_metadata: &ColumnChunkMetaData,
+    reader: &'a mut RR,
+    pages: Vec<FilteredPage>,
+    scratch: Vec<u8>,
+    verify_crc: bool,
+) -> Result<impl Stream<Item = Result<CompressedPage>> + 'a> {
+    check_not_encrypted(column_metadata, "Streaming pages")?;
+    Ok(_get_indexed_page_stream(
+        reader,
+        column_metadata.into(),
+        pages,
+        scratch,
+        verify_crc,
+    ))
+}
+
+async fn read_range<R: AsyncRead + AsyncSeek + Unpin + Send>(
+    reader: &mut R,
+    start: u64,
+    length: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<()> {
+    reader.seek(SeekFrom::Start(start)).await?;
+
+    buffer.clear();
+    buffer.try_reserve(length)?;
+    let bytes_read = reader.take(length as u64).read_to_end(buffer).await?;
+    if bytes_read != length {
+        return Err(Error::oos("The page location reported the wrong page size"));
+    }
+    Ok(())
+}
+
+fn _get_indexed_page_stream<R: AsyncRead + AsyncSeek + Unpin + Send>(
+    reader: &mut R,
+    page_metadata: PageMetaData,
+    pages: Vec<FilteredPage>,
+    mut scratch: Vec<u8>,
+    check_crc: bool,
+) -> impl Stream<Item = Result<CompressedPage>> + '_ {
+    let compression = page_metadata.compression;
+    let descriptor = page_metadata.descriptor;
+    let column_start = page_metadata.column_start;
+    let mut data = vec![];
+    try_stream! {
+        // a dictionary page exists iff the first data page is not at the start of the column
+        if let Some(page) = pages.first() {
+            let length = (page.start - column_start) as usize;
+            if length > 0 {
+                read_range(reader, column_start, length, &mut scratch).await?;
+                let page_header = deserialize_page(&scratch, &mut data, check_crc)?;
+                let page = finish_page(page_header, &mut data, compression, &descriptor, None)?;
+                if !matches!(page, CompressedPage::Dict(_)) {
+                    Err(Error::oos(
+                        "The first page is not a dictionary page but it should",
+                    ))?
+                }
+                yield page;
+            }
+        }
+
+        for page in pages {
+            if page.selected_rows.is_empty() {
+                continue
+            }
+            read_range(reader, page.start, page.length, &mut scratch).await?;
+            let page_header = deserialize_page(&scratch, &mut data, check_crc)?;
+            yield finish_page(
+                page_header,
+                &mut data,
+                compression,
+                &descriptor,
+                Some(page.selected_rows),
+            )?;
+        }
+    }
+}
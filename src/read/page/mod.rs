@@ -1,4 +1,6 @@
 mod indexed_reader;
+#[cfg(feature = "async")]
+mod indexed_stream;
 mod reader;
 #[cfg(feature = "async")]
 mod stream;
@@ -15,3 +17,7 @@ pub trait PageIterator: Iterator<Item = Result<CompressedPage, Error>> {
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub use stream::{get_page_stream, get_page_stream_from_column_start};
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use indexed_stream::get_indexed_page_stream;
@@ -4,6 +4,8 @@ use std::{io::Read, sync::Arc};
 use parquet_format_safe::thrift::protocol::TCompactInputProtocol;
 
 use crate::compression::Compression;
+#[cfg(feature = "encryption")]
+use crate::encryption::ColumnDecryptor;
 use crate::error::{Error, Result};
 use crate::indexes::Interval;
 use crate::metadata::{ColumnChunkMetaData, Descriptor};
@@ -86,6 +88,13 @@ pub struct PageReader<R: Read> {
 
     // Maximum page size (compressed or uncompressed) to limit allocations
     max_page_size: usize,
+
+    // Whether to verify the CRC32 checksum of pages that declare one
+    verify_crc: bool,
+
+    // Decrypts the page headers and pages of encrypted column chunks
+    #[cfg(feature = "encryption")]
+    decryptor: Option<ColumnDecryptor>,
 }
 
 impl<R: Read> PageReader<R> {
@@ -122,7 +131,34 @@ impl<R: Read> PageReader<R> {
             pages_filter,
             scratch,
             max_page_size,
+            verify_crc: false,
+            #[cfg(feature = "encryption")]
+            decryptor: None,
+        }
+    }
+
+    /// Sets whether the CRC32 checksum of each page that declares one is verified.
+    /// When enabled, a page whose checksum does not match its data results in [`Error::InvalidChecksum`].
+    pub fn with_crc_check(mut self, verify_crc: bool) -> Self {
+        self.verify_crc = verify_crc;
+        self
+    }
+
+    /// Sets the [`ColumnDecryptor`] used to decrypt the page headers and pages of an encrypted
+    /// column chunk, obtained from [`crate::encryption::FileDecryptor::column_decryptor`].
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn with_decryptor(mut self, decryptor: ColumnDecryptor) -> Self {
+        self.decryptor = Some(decryptor);
+        self
+    }
+
+    fn read_page_header(&mut self) -> Result<ParquetPageHeader> {
+        #[cfg(feature = "encryption")]
+        if let Some(decryptor) = self.decryptor.as_mut() {
+            return decryptor.read_page_header(&mut self.reader, self.max_page_size);
         }
+        read_page_header(&mut self.reader, self.max_page_size)
     }
 
     /// Returns the reader and this Readers' interval buffer
@@ -186,7 +222,7 @@ pub(super) fn build_page<R: Read>(
     reader: &mut PageReader<R>,
     buffer: &mut Vec<u8>,
 ) -> Result<Option<CompressedPage>> {
-    let page_header = read_page_header(&mut reader.reader, reader.max_page_size)?;
+    let page_header = reader.read_page_header()?;
 
     reader.seen_num_values += get_page_header(&page_header)?
         .map(|x| x.num_values() as i64)
@@ -212,6 +248,15 @@ pub(super) fn build_page<R: Read>(
         ));
     }
 
+    if reader.verify_crc {
+        verify_crc(&page_header, buffer)?;
+    }
+
+    #[cfg(feature = "encryption")]
+    if let Some(decryptor) = reader.decryptor.as_mut() {
+        decryptor.decrypt_page(buffer)?;
+    }
+
     finish_page(
         page_header,
         buffer,
@@ -222,6 +267,18 @@ pub(super) fn build_page<R: Read>(
     .map(Some)
 }
 
+/// Verifies that the CRC32 checksum declared in `page_header`, if any, matches `data`.
+pub(super) fn verify_crc(page_header: &ParquetPageHeader, data: &[u8]) -> Result<()> {
+    if let Some(expected) = page_header.crc {
+        let expected = expected as u32;
+        let computed = crc32fast::hash(data);
+        if expected != computed {
+            return Err(Error::InvalidChecksum { expected, computed });
+        }
+    }
+    Ok(())
+}
+
 pub(super) fn finish_page(
     page_header: ParquetPageHeader,
     data: &mut Vec<u8>,
@@ -9,36 +9,48 @@ use crate::compression::Compression;
 use crate::error::{Error, Result};
 use crate::metadata::{ColumnChunkMetaData, Descriptor};
 use crate::page::{CompressedPage, ParquetPageHeader};
+use crate::read::check_not_encrypted;
 
-use super::reader::{finish_page, get_page_header, PageMetaData};
+use super::reader::{finish_page, get_page_header, verify_crc, PageMetaData};
 use super::PageFilter;
 
-/// Returns a stream of compressed data pages
+/// Returns a stream of compressed data pages.
+///
+/// When `verify_crc` is set, the CRC32 checksum of each page that declares one is verified
+/// and the stream yields [`Error::InvalidChecksum`] when a page's data does not match it.
+/// # Errors
+/// Errors if the column chunk is encrypted, which only [`crate::read::PageReader`] supports.
 pub async fn get_page_stream<'a, RR: AsyncRead + Unpin + Send + AsyncSeek>(
     column_metadata: &'a ColumnChunkMetaData,
     reader: &'a mut RR,
     scratch: Vec<u8>,
     pages_filter: PageFilter,
     max_page_size: usize,
+    verify_crc: bool,
 ) -> Result<impl Stream<Item = Result<CompressedPage>> + 'a> {
+    check_not_encrypted(column_metadata, "Streaming pages")?;
     get_page_stream_with_page_meta(
         column_metadata.into(),
         reader,
         scratch,
         pages_filter,
         max_page_size,
+        verify_crc,
     )
     .await
 }
 
-/// Returns a stream of compressed data pages from a reader that begins at the start of the column
+/// Returns a stream of compressed data pages from a reader that begins at the start of the
+/// column (see [`get_page_stream`] for `verify_crc` and errors)
 pub async fn get_page_stream_from_column_start<'a, R: AsyncRead + Unpin + Send>(
     column_metadata: &'a ColumnChunkMetaData,
     reader: &'a mut R,
     scratch: Vec<u8>,
     pages_filter: PageFilter,
     max_header_size: usize,
+    verify_crc: bool,
 ) -> Result<impl Stream<Item = Result<CompressedPage>> + 'a> {
+    check_not_encrypted(column_metadata, "Streaming pages")?;
     let page_metadata: PageMetaData = column_metadata.into();
     Ok(_get_page_stream(
         reader,
@@ -48,16 +60,20 @@ pub async fn get_page_stream_from_column_start<'a, R: AsyncRead + Unpin + Send>(
         scratch,
         pages_filter,
         max_header_size,
+        verify_crc,
     ))
 }
 
-/// Returns a stream of compressed data pages with [`PageMetaData`]
+/// Returns a stream of compressed data pages with [`PageMetaData`] (see [`get_page_stream`]
+/// for `verify_crc`). The pages of encrypted column chunks can't be streamed: their
+/// [`PageMetaData`] must not be used here.
 pub async fn get_page_stream_with_page_meta<RR: AsyncRead + Unpin + Send + AsyncSeek>(
     page_metadata: PageMetaData,
     reader: &mut RR,
     scratch: Vec<u8>,
     pages_filter: PageFilter,
     max_page_size: usize,
+    verify_crc: bool,
 ) -> Result<impl Stream<Item = Result<CompressedPage>> + '_> {
     let column_start = page_metadata.column_start;
     reader.seek(SeekFrom::Start(column_start)).await?;
@@ -69,9 +85,11 @@ pub async fn get_page_stream_with_page_meta<RR: AsyncRead + Unpin + Send + Async
         scratch,
         pages_filter,
         max_page_size,
+        verify_crc,
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn _get_page_stream<R: AsyncRead + Unpin + Send>(
     reader: &mut R,
     total_num_values: i64,
@@ -80,6 +98,7 @@ fn _get_page_stream<R: AsyncRead + Unpin + Send>(
     mut scratch: Vec<u8>,
     pages_filter: PageFilter,
     max_page_size: usize,
+    check_crc: bool,
 ) -> impl Stream<Item = Result<CompressedPage>> + '_ {
     let mut seen_values = 0i64;
     try_stream! {
@@ -117,6 +136,10 @@ fn _get_page_stream<R: AsyncRead + Unpin + Send>(
                 ))?
             }
 
+            if check_crc {
+                verify_crc(&page_header, &scratch)?;
+            }
+
             yield finish_page(
                 page_header,
                 &mut scratch,
@@ -0,0 +1,637 @@
+//! Row-oriented reading of parquet files into dynamically typed [`Field`]s.
+//!
+//! This is meant for debugging, tests and small files: each row group is read in full and
+//! its values are materialized one by one.
+use std::io::{Read, Seek};
+use std::vec::IntoIter;
+
+use crate::encoding::byte_stream_split;
+use crate::encoding::hybrid_rle::{BitmapIter, HybridRleDecoder};
+use crate::encoding::plain_byte_array::BinaryIter;
+use crate::encoding::Encoding;
+use crate::error::{Error, Result};
+use crate::metadata::{FileMetaData, RowGroupMetaData};
+use crate::nested::{decode_levels, is_map_annotated, list_nodes, num_leaves, read_nested, Nested};
+use crate::page::{split_buffer, DataPage, Page};
+use crate::schema::types::{
+    IntegerType, ParquetType, PhysicalType, PrimitiveConvertedType, PrimitiveLogicalType,
+    PrimitiveType, TimeUnit,
+};
+use crate::schema::Repetition;
+use crate::types::{decode, float16_to_f32, int96_to_i64_ns, NativeType};
+
+use super::get_column_iterator;
+
+/// A dynamically typed value of a parquet field, interpreted according to its logical or
+/// converted type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    /// A null value
+    Null,
+    /// A boolean
+    Bool(bool),
+    /// A `INT32` annotated as a signed 8 bit integer
+    Int8(i8),
+    /// A `INT32` annotated as a signed 16 bit integer
+    Int16(i16),
+    /// A `INT32`
+    Int32(i32),
+    /// A `INT64`
+    Int64(i64),
+    /// A `INT32` annotated as an unsigned 8 bit integer
+    UInt8(u8),
+    /// A `INT32` annotated as an unsigned 16 bit integer
+    UInt16(u16),
+    /// A `INT32` annotated as an unsigned 32 bit integer
+    UInt32(u32),
+    /// A `INT64` annotated as an unsigned 64 bit integer
+    UInt64(u64),
+    /// A `FLOAT`, or a `FLOAT16` converted to `f32`
+    Float(f32),
+    /// A `DOUBLE`
+    Double(f64),
+    /// A binary without a string annotation
+    Bytes(Vec<u8>),
+    /// A binary annotated as a string, enum or JSON
+    String(String),
+    /// A decimal, whose value is `value * 10^(-scale)`
+    Decimal {
+        /// The unscaled value
+        value: i128,
+        /// The precision of the decimal
+        precision: usize,
+        /// The scale of the decimal
+        scale: usize,
+    },
+    /// The number of days since the UNIX epoch
+    Date(i32),
+    /// The time since midnight
+    Time {
+        /// The time in `unit`s
+        value: i64,
+        /// The unit of `value`
+        unit: TimeUnit,
+    },
+    /// The time since the UNIX epoch. `INT96` values are timestamps in nanoseconds.
+    Timestamp {
+        /// The time in `unit`s
+        value: i64,
+        /// The unit of `value`
+        unit: TimeUnit,
+        /// Whether the timestamp is normalized to UTC
+        is_adjusted_to_utc: bool,
+    },
+    /// A list
+    List(Vec<Field>),
+    /// The (key, value) entries of a map
+    Map(Vec<(Field, Field)>),
+    /// The (name, value) fields of a group
+    Group(Vec<(String, Field)>),
+}
+
+/// The (name, value) of each (projected) field of a row.
+pub type Row = Vec<(String, Field)>;
+
+/// Returns an iterator over the rows of the file described by `metadata`.
+///
+/// `projection` declares the names of the top-level fields to read, in the order in which they
+/// are returned in each [`Row`]; when `None`, all fields are read.
+/// # Errors
+/// Errors iff a projected field is not part of the schema.
+pub fn rows<R: Read + Seek>(
+    reader: R,
+    metadata: &FileMetaData,
+    projection: Option<&[&str]>,
+) -> Result<RowIterator<R>> {
+    let schema_fields = metadata.schema().fields();
+    let fields = match projection {
+        Some(projection) => projection
+            .iter()
+            .map(|name| {
+                schema_fields
+                    .iter()
+                    .find(|field| field.name() == *name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::InvalidParameter(format!(
+                            "The field \"{}\" is not part of the schema",
+                            name
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => schema_fields.to_vec(),
+    };
+
+    Ok(RowIterator {
+        reader,
+        row_groups: metadata.row_groups.clone().into_iter(),
+        fields,
+        current: None,
+    })
+}
+
+/// The columns of a row group of a field
+struct FieldColumns {
+    nested: Nested,
+    // the values of each leaf
+    leaves: Vec<IntoIter<Field>>,
+}
+
+/// The state of the row group being iterated
+struct RowGroupRows {
+    columns: Vec<FieldColumns>,
+    row: usize,
+    num_rows: usize,
+}
+
+/// An [`Iterator`] of [`Row`]s, returned by [`rows`].
+pub struct RowIterator<R: Read + Seek> {
+    reader: R,
+    row_groups: IntoIter<RowGroupMetaData>,
+    fields: Vec<ParquetType>,
+    current: Option<RowGroupRows>,
+}
+
+fn read_row_group<R: Read + Seek>(
+    reader: &mut R,
+    fields: &[ParquetType],
+    row_group: &RowGroupMetaData,
+) -> Result<RowGroupRows> {
+    let columns = fields
+        .iter()
+        .map(|field| {
+            let columns = get_column_iterator(
+                &mut *reader,
+                row_group,
+                field.name(),
+                None,
+                vec![],
+                usize::MAX,
+            );
+            let (nested, pages) = read_nested(field, columns)?;
+            if nested.len() != row_group.num_rows() {
+                return Err(Error::oos(format!(
+                    "The field \"{}\" has {} rows but its row group has {}",
+                    field.name(),
+                    nested.len(),
+                    row_group.num_rows()
+                )));
+            }
+            let leaves = leaf_types(field)
+                .into_iter()
+                .zip(pages.iter())
+                .map(|(primitive_type, pages)| {
+                    leaf_values(primitive_type, pages).map(|values| values.into_iter())
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(FieldColumns { nested, leaves })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(RowGroupRows {
+        columns,
+        row: 0,
+        num_rows: row_group.num_rows(),
+    })
+}
+
+impl<R: Read + Seek> Iterator for RowIterator<R> {
+    type Item = Result<Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(current) = self.current.as_mut() {
+                if current.row < current.num_rows {
+                    let row = current.row;
+                    current.row += 1;
+                    let row = self
+                        .fields
+                        .iter()
+                        .zip(current.columns.iter_mut())
+                        .map(|(field, column)| {
+                            to_field(field, false, &column.nested, row, &mut column.leaves)
+                                .map(|value| (field.name().to_string(), value))
+                        })
+                        .collect();
+                    return Some(row);
+                }
+            }
+
+            let row_group = self.row_groups.next()?;
+            match read_row_group(&mut self.reader, &self.fields, &row_group) {
+                Ok(current) => self.current = Some(current),
+                Err(error) => {
+                    // the iterator is fused after an error
+                    self.row_groups = vec![].into_iter();
+                    self.current = None;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+/// The [`PrimitiveType`] of each leaf of `field`, in depth-first order
+fn leaf_types(field: &ParquetType) -> Vec<&PrimitiveType> {
+    match field {
+        ParquetType::PrimitiveType(primitive_type) => vec![primitive_type],
+        ParquetType::GroupType { fields, .. } => fields.iter().flat_map(leaf_types).collect(),
+    }
+}
+
+/// Converts the `index`th slot of `nested` into a [`Field`], consuming the values of `leaves`.
+/// * `required`: whether the repetition of `field` was already accounted for by its parent
+fn to_field(
+    field: &ParquetType,
+    required: bool,
+    nested: &Nested,
+    index: usize,
+    leaves: &mut [IntoIter<Field>],
+) -> Result<Field> {
+    if !nested.is_valid(index) {
+        return Ok(Field::Null);
+    }
+    if !required && field.get_field_info().repetition == Repetition::Repeated {
+        return to_list(field, true, nested, index, leaves).map(Field::List);
+    }
+
+    match field {
+        ParquetType::PrimitiveType(_) => leaves[0]
+            .next()
+            .ok_or_else(|| Error::oos("A column has fewer values than its definition levels")),
+        ParquetType::GroupType { fields, .. } => match (list_nodes(field), nested) {
+            (Some(nodes), _) => {
+                let items = to_list(
+                    nodes.element,
+                    nodes.element_is_repeated,
+                    nested,
+                    index,
+                    leaves,
+                )?;
+                if is_map_annotated(field) {
+                    items
+                        .into_iter()
+                        .map(to_entry)
+                        .collect::<Result<_>>()
+                        .map(Field::Map)
+                } else {
+                    Ok(Field::List(items))
+                }
+            }
+            (None, Nested::Struct { children, .. }) => {
+                let mut leaves = leaves;
+                fields
+                    .iter()
+                    .zip(children.iter())
+                    .map(|(field, child)| {
+                        let (field_leaves, remaining) =
+                            std::mem::take(&mut leaves).split_at_mut(num_leaves(field));
+                        leaves = remaining;
+                        to_field(field, false, child, index, field_leaves)
+                            .map(|value| (field.name().to_string(), value))
+                    })
+                    .collect::<Result<_>>()
+                    .map(Field::Group)
+            }
+            // `nested` was assembled from `field`
+            _ => unreachable!(),
+        },
+    }
+}
+
+/// Converts the items of the `index`th list of `nested`, whose items are `element`.
+fn to_list(
+    element: &ParquetType,
+    element_is_repeated: bool,
+    nested: &Nested,
+    index: usize,
+    leaves: &mut [IntoIter<Field>],
+) -> Result<Vec<Field>> {
+    match nested {
+        Nested::List { offsets, child, .. } => (offsets[index]..offsets[index + 1])
+            .map(|item| to_field(element, element_is_repeated, child, item, leaves))
+            .collect(),
+        // `nested` was assembled from a list
+        _ => unreachable!(),
+    }
+}
+
+/// Converts the key-value group of a map into an entry
+fn to_entry(item: Field) -> Result<(Field, Field)> {
+    match item {
+        Field::Group(fields) => {
+            let mut fields = fields.into_iter().map(|(_, value)| value);
+            let key = fields.next().unwrap_or(Field::Null);
+            let value = fields.next().unwrap_or(Field::Null);
+            Ok((key, value))
+        }
+        _ => Err(Error::oos("The entries of a map must be a group")),
+    }
+}
+
+/// How the physical values of a column are interpreted
+#[derive(Debug, Clone, Copy)]
+enum Interpretation {
+    Physical,
+    Int8,
+    Int16,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    String,
+    Decimal(usize, usize),
+    Date,
+    Time(TimeUnit),
+    Timestamp(TimeUnit, bool),
+    Float16,
+}
+
+fn interpretation(primitive_type: &PrimitiveType) -> Interpretation {
+    use Interpretation::*;
+    if let Some(logical_type) = primitive_type.logical_type {
+        return match logical_type {
+            PrimitiveLogicalType::String
+            | PrimitiveLogicalType::Enum
+            | PrimitiveLogicalType::Json => String,
+            PrimitiveLogicalType::Decimal(precision, scale) => Decimal(precision, scale),
+            PrimitiveLogicalType::Date => Date,
+            PrimitiveLogicalType::Time { unit, .. } => Time(unit),
+            PrimitiveLogicalType::Timestamp {
+                unit,
+                is_adjusted_to_utc,
+            } => Timestamp(unit, is_adjusted_to_utc),
+            PrimitiveLogicalType::Integer(integer) => match integer {
+                IntegerType::Int8 => Int8,
+                IntegerType::Int16 => Int16,
+                IntegerType::UInt8 => UInt8,
+                IntegerType::UInt16 => UInt16,
+                IntegerType::UInt32 => UInt32,
+                IntegerType::UInt64 => UInt64,
+                IntegerType::Int32 | IntegerType::Int64 => Physical,
+            },
+            PrimitiveLogicalType::Float16 => Float16,
+            PrimitiveLogicalType::Unknown
+            | PrimitiveLogicalType::Bson
+            | PrimitiveLogicalType::Uuid => Physical,
+        };
+    }
+    match primitive_type.converted_type {
+        Some(PrimitiveConvertedType::Utf8)
+        | Some(PrimitiveConvertedType::Enum)
+        | Some(PrimitiveConvertedType::Json) => String,
+        Some(PrimitiveConvertedType::Decimal(precision, scale)) => Decimal(precision, scale),
+        Some(PrimitiveConvertedType::Date) => Date,
+        Some(PrimitiveConvertedType::TimeMillis) => Time(TimeUnit::Milliseconds),
+        Some(PrimitiveConvertedType::TimeMicros) => Time(TimeUnit::Microseconds),
+        // SPEC: the legacy timestamps are normalized to UTC
+        Some(PrimitiveConvertedType::TimestampMillis) => Timestamp(TimeUnit::Milliseconds, true),
+        Some(PrimitiveConvertedType::TimestampMicros) => Timestamp(TimeUnit::Microseconds, true),
+        Some(PrimitiveConvertedType::Int8) => Int8,
+        Some(PrimitiveConvertedType::Int16) => Int16,
+        Some(PrimitiveConvertedType::Uint8) => UInt8,
+        Some(PrimitiveConvertedType::Uint16) => UInt16,
+        Some(PrimitiveConvertedType::Uint32) => UInt32,
+        Some(PrimitiveConvertedType::Uint64) => UInt64,
+        _ => Physical,
+    }
+}
+
+/// A physical value
+enum Value<'a> {
+    Boolean(bool),
+    Int32(i32),
+    Int64(i64),
+    Int96([u32; 3]),
+    Float(f32),
+    Double(f64),
+    Bytes(&'a [u8]),
+}
+
+/// Converts the big-endian two's complement representation of a decimal to its value
+pub(crate) fn decimal_from_bytes(bytes: &[u8]) -> Result<i128> {
+    if bytes.len() > 16 {
+        return Err(Error::FeatureNotSupported(
+            "Decimals represented by more than 16 bytes are not supported".to_string(),
+        ));
+    }
+    let negative = bytes.first().map(|x| x >> 7 == 1).unwrap_or(false);
+    let initial = if negative { -1 } else { 0 };
+    Ok(bytes
+        .iter()
+        .fold(initial, |acc, byte| (acc << 8) | *byte as i128))
+}
+
+fn to_value(value: Value, interpretation: Interpretation) -> Result<Field> {
+    use Interpretation as I;
+    Ok(match (value, interpretation) {
+        (Value::Boolean(value), _) => Field::Bool(value),
+        (Value::Int32(value), I::Int8) => Field::Int8(value as i8),
+        (Value::Int32(value), I::Int16) => Field::Int16(value as i16),
+        (Value::Int32(value), I::UInt8) => Field::UInt8(value as u8),
+        (Value::Int32(value), I::UInt16) => Field::UInt16(value as u16),
+        (Value::Int32(value), I::UInt32) => Field::UInt32(value as u32),
+        (Value::Int32(value), I::Date) => Field::Date(value),
+        (Value::Int32(value), I::Time(unit)) => Field::Time {
+            value: value as i64,
+            unit,
+        },
+        (Value::Int32(value), I::Decimal(precision, scale)) => Field::Decimal {
+            value: value as i128,
+            precision,
+            scale,
+        },
+        (Value::Int32(value), _) => Field::Int32(value),
+        (Value::Int64(value), I::UInt64) => Field::UInt64(value as u64),
+        (Value::Int64(value), I::Time(unit)) => Field::Time { value, unit },
+        (Value::Int64(value), I::Timestamp(unit, is_adjusted_to_utc)) => Field::Timestamp {
+            value,
+            unit,
+            is_adjusted_to_utc,
+        },
+        (Value::Int64(value), I::Decimal(precision, scale)) => Field::Decimal {
+            value: value as i128,
+            precision,
+            scale,
+        },
+        (Value::Int64(value), _) => Field::Int64(value),
+        (Value::Int96(value), _) => Field::Timestamp {
+            value: int96_to_i64_ns(value),
+            unit: TimeUnit::Nanoseconds,
+            is_adjusted_to_utc: false,
+        },
+        (Value::Float(value), _) => Field::Float(value),
+        (Value::Double(value), _) => Field::Double(value),
+        (Value::Bytes(value), I::String) => Field::String(
+            std::str::from_utf8(value)
+                .map_err(|_| Error::oos("A string column contains invalid utf8"))?
+                .to_string(),
+        ),
+        (Value::Bytes(value), I::Decimal(precision, scale)) => Field::Decimal {
+            value: decimal_from_bytes(value)?,
+            precision,
+            scale,
+        },
+        (Value::Bytes(value), I::Float16) if value.len() == 2 => {
+            Field::Float(float16_to_f32(u16::from_le_bytes([value[0], value[1]])))
+        }
+        (Value::Bytes(value), _) => Field::Bytes(value.to_vec()),
+    })
+}
+
+fn native<T: NativeType>(values: &[u8], length: usize) -> Result<impl Iterator<Item = T> + '_> {
+    let size = std::mem::size_of::<T>();
+    if values.len() < length * size {
+        return Err(Error::oos(
+            "The values of a page are smaller than its number of values",
+        ));
+    }
+    Ok(values[..length * size].chunks_exact(size).map(decode))
+}
+
+/// Decodes `length` plain-encoded values of `primitive_type`
+fn decode_plain(
+    values: &[u8],
+    primitive_type: &PrimitiveType,
+    length: usize,
+) -> Result<Vec<Field>> {
+    let interpretation = interpretation(primitive_type);
+    let convert = |value| to_value(value, interpretation);
+    match primitive_type.physical_type {
+        PhysicalType::Boolean => {
+            if values.len() * 8 < length {
+                return Err(Error::oos(
+                    "The values of a page are smaller than its number of values",
+                ));
+            }
+            BitmapIter::new(values, 0, length)
+                .map(|value| convert(Value::Boolean(value)))
+                .collect()
+        }
+        PhysicalType::Int32 => native(values, length)?
+            .map(|value| convert(Value::Int32(value)))
+            .collect(),
+        PhysicalType::Int64 => native(values, length)?
+            .map(|value| convert(Value::Int64(value)))
+            .collect(),
+        PhysicalType::Int96 => native(values, length)?
+            .map(|value| convert(Value::Int96(value)))
+            .collect(),
+        PhysicalType::Float => native(values, length)?
+            .map(|value| convert(Value::Float(value)))
+            .collect(),
+        PhysicalType::Double => native(values, length)?
+            .map(|value| convert(Value::Double(value)))
+            .collect(),
+        PhysicalType::ByteArray => {
+            let values = BinaryIter::new(values, Some(length))
+                .take(length)
+                .map(|value| convert(Value::Bytes(value?)))
+                .collect::<Result<Vec<_>>>()?;
+            if values.len() != length {
+                return Err(Error::oos(
+                    "The values of a page are smaller than its number of values",
+                ));
+            }
+            Ok(values)
+        }
+        PhysicalType::FixedLenByteArray(size) => {
+            if size == 0 || values.len() < length * size {
+                return Err(Error::oos(
+                    "The values of a page are smaller than its number of values",
+                ));
+            }
+            values[..length * size]
+                .chunks_exact(size)
+                .map(|value| convert(Value::Bytes(value)))
+                .collect()
+        }
+    }
+}
+
+/// Decodes the non-null values of a data page
+fn page_values(
+    page: &DataPage,
+    primitive_type: &PrimitiveType,
+    dict: Option<&[Field]>,
+) -> Result<Vec<Field>> {
+    let max_def_level = page.descriptor.max_def_level as u32;
+    let length = decode_levels(page)?
+        .def_levels
+        .iter()
+        .filter(|def| **def == max_def_level)
+        .count();
+    let (_, _, values) = split_buffer(page)?;
+
+    match (page.encoding(), dict) {
+        (Encoding::Plain, _) => decode_plain(values, primitive_type, length),
+        (Encoding::PlainDictionary | Encoding::RleDictionary, Some(dict)) => {
+            if length == 0 {
+                return Ok(vec![]);
+            }
+            // SPEC: the bit width of the indices is stored in the first byte
+            let (bit_width, indices) = values
+                .split_first()
+                .ok_or_else(|| Error::oos("A dictionary-encoded page has no bit width"))?;
+            HybridRleDecoder::try_new(indices, *bit_width as u32, length)?
+                .map(|index| {
+                    dict.get(index? as usize)
+                        .cloned()
+                        .ok_or_else(|| Error::oos("A dictionary index is out of bounds"))
+                })
+                .collect()
+        }
+        (Encoding::ByteStreamSplit, _) => {
+            let size = match primitive_type.physical_type {
+                PhysicalType::Int32 | PhysicalType::Float => 4,
+                PhysicalType::Int64 | PhysicalType::Double => 8,
+                PhysicalType::FixedLenByteArray(size) => size,
+                _ => {
+                    return Err(Error::oos(
+                        "BYTE_STREAM_SPLIT only encodes values of a fixed size",
+                    ))
+                }
+            };
+            let mut plain = vec![];
+            byte_stream_split::FixedLenDecoder::try_new(values, size)?.decode(&mut plain);
+            decode_plain(&plain, primitive_type, length)
+        }
+        (Encoding::PlainDictionary | Encoding::RleDictionary, None) => Err(Error::oos(
+            "A dictionary-encoded page requires a dictionary page",
+        )),
+        (encoding, _) => Err(Error::FeatureNotSupported(format!(
+            "Reading rows of pages encoded with {:?}",
+            encoding
+        ))),
+    }
+}
+
+/// Decodes the non-null values of the pages of a leaf column
+fn leaf_values(primitive_type: &PrimitiveType, pages: &[Page]) -> Result<Vec<Field>> {
+    let mut dict = None;
+    let mut values = vec![];
+    for page in pages {
+        match page {
+            Page::Dict(page) => {
+                dict = Some(decode_plain(&page.buffer, primitive_type, page.num_values)?)
+            }
+            Page::Data(page) => values.extend(page_values(page, primitive_type, dict.as_deref())?),
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal() -> Result<()> {
+        assert_eq!(decimal_from_bytes(&[0x01, 0x00])?, 256);
+        assert_eq!(decimal_from_bytes(&[0xff, 0xfe])?, -2);
+        assert_eq!(decimal_from_bytes(&[])?, 0);
+        assert!(decimal_from_bytes(&[0; 17]).is_err());
+        Ok(())
+    }
+}
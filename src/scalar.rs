@@ -0,0 +1,95 @@
+//! Values of columns in a representation ordered by their sort order.
+use crate::metadata::{get_sort_order, SortOrder};
+use crate::read::decimal_from_bytes;
+use crate::schema::types::{PhysicalType, PrimitiveLogicalType, PrimitiveType};
+use crate::types::float16_to_f32;
+
+/// A value of a column in a representation whose order is the sort order of the column.
+/// Only scalars of the same variant are compared.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub(crate) enum Scalar {
+    Boolean(bool),
+    /// Integers, including unsigned integers and decimals
+    Integer(i128),
+    Float(f64),
+    /// Byte arrays ordered lexicographically
+    Bytes(Vec<u8>),
+}
+
+pub(crate) fn sort_order(primitive_type: &PrimitiveType) -> SortOrder {
+    get_sort_order(
+        &primitive_type.logical_type,
+        &primitive_type.converted_type,
+        &primitive_type.physical_type,
+    )
+}
+
+pub(crate) fn is_float16(primitive_type: &PrimitiveType) -> bool {
+    primitive_type.physical_type == PhysicalType::FixedLenByteArray(2)
+        && primitive_type.logical_type == Some(PrimitiveLogicalType::Float16)
+}
+
+impl Scalar {
+    /// Whether this is a NaN, which is not comparable with statistics
+    pub fn is_nan(&self) -> bool {
+        matches!(self, Scalar::Float(value) if value.is_nan())
+    }
+
+    /// The scalar of a value of an `INT32` column, or `None` if its sort order is undefined
+    pub fn from_int32(value: i32, primitive_type: &PrimitiveType) -> Option<Self> {
+        match sort_order(primitive_type) {
+            SortOrder::Signed => Some(Scalar::Integer(value as i128)),
+            SortOrder::Unsigned => Some(Scalar::Integer(value as u32 as i128)),
+            SortOrder::Undefined => None,
+        }
+    }
+
+    /// The scalar of a value of an `INT64` column, or `None` if its sort order is undefined
+    pub fn from_int64(value: i64, primitive_type: &PrimitiveType) -> Option<Self> {
+        match sort_order(primitive_type) {
+            SortOrder::Signed => Some(Scalar::Integer(value as i128)),
+            SortOrder::Unsigned => Some(Scalar::Integer(value as u64 as i128)),
+            SortOrder::Undefined => None,
+        }
+    }
+
+    /// The scalar of a floating point value, or `None` if it is a NaN
+    pub fn from_float(value: f64) -> Option<Self> {
+        (!value.is_nan()).then_some(Scalar::Float(value))
+    }
+
+    /// The scalar of a value of a binary column, or `None` if its sort order is undefined
+    pub fn from_bytes(value: &[u8], primitive_type: &PrimitiveType) -> Option<Self> {
+        if is_float16(primitive_type) {
+            let value = u16::from_le_bytes(value.try_into().ok()?);
+            return Self::from_float(float16_to_f32(value) as f64);
+        }
+        match sort_order(primitive_type) {
+            SortOrder::Signed => decimal_from_bytes(value).ok().map(Scalar::Integer),
+            SortOrder::Unsigned => Some(Scalar::Bytes(value.to_vec())),
+            SortOrder::Undefined => None,
+        }
+    }
+
+    /// The scalar of a plain-encoded value of a column of `primitive_type` (e.g. the min or
+    /// max of a statistics), or `None` if its sort order is undefined or it is invalid
+    pub fn from_plain(value: &[u8], primitive_type: &PrimitiveType) -> Option<Self> {
+        match primitive_type.physical_type {
+            PhysicalType::Boolean => value.first().map(|value| Scalar::Boolean(*value != 0)),
+            PhysicalType::Int32 => {
+                Self::from_int32(i32::from_le_bytes(value.try_into().ok()?), primitive_type)
+            }
+            PhysicalType::Int64 => {
+                Self::from_int64(i64::from_le_bytes(value.try_into().ok()?), primitive_type)
+            }
+            PhysicalType::Int96 => None,
+            PhysicalType::Float => {
+                Self::from_float(f32::from_le_bytes(value.try_into().ok()?) as f64)
+            }
+            PhysicalType::Double => Self::from_float(f64::from_le_bytes(value.try_into().ok()?)),
+            PhysicalType::ByteArray | PhysicalType::FixedLenByteArray(_) => {
+                Self::from_bytes(value, primitive_type)
+            }
+        }
+    }
+}
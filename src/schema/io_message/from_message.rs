@@ -67,6 +67,7 @@ fn is_logical_type(s: &str) -> bool {
             | "UUID"
             | "UNKNOWN"
             | "INTERVAL"
+            | "FLOAT16"
     )
 }
 
@@ -610,6 +611,7 @@ impl<'a> Parser<'a> {
             "JSON" => PrimitiveLogicalType::Json,
             "BSON" => PrimitiveLogicalType::Bson,
             "UUID" => PrimitiveLogicalType::Uuid,
+            "FLOAT16" => PrimitiveLogicalType::Float16,
             "UNKNOWN" => PrimitiveLogicalType::Unknown,
             "INTERVAL" => return Err(Error::oos("Interval logical type not yet supported")),
             _ => unreachable!(),
@@ -1,3 +1,5 @@
 mod from_message;
+mod to_message;
 
 pub use from_message::from_message;
+pub use to_message::to_message;
@@ -0,0 +1,322 @@
+//! Parquet schema printer.
+//! Provides methods to render a Parquet [`ParquetType`](crate::schema::types::ParquetType)
+//! into the message type format parsed by [`from_message`](super::from_message).
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet2::schema::io_message::{from_message, to_message};
+//!
+//! let message_type = "
+//!   message spark_schema {
+//!     OPTIONAL BYTE_ARRAY a (STRING);
+//!     REQUIRED INT32 b = 1;
+//!   }
+//! ";
+//!
+//! let schema = from_message(message_type).expect("Expected valid schema");
+//! let message = to_message(&schema);
+//! assert_eq!(from_message(&message).unwrap(), schema);
+//! ```
+
+use std::fmt::Write;
+
+use crate::schema::types::{
+    FieldInfo, GroupConvertedType, GroupLogicalType, IntegerType, ParquetType, PhysicalType,
+    PrimitiveConvertedType, PrimitiveLogicalType, PrimitiveType, TimeUnit,
+};
+use crate::schema::Repetition;
+
+const INDENT: &str = "  ";
+
+/// Renders `schema` in the message type format, such that [`from_message`](super::from_message)
+/// parses it back into `schema`.
+///
+/// The name and fields of `schema` become the name and fields of the message.
+/// The format declares a single annotation per field: fields with both a logical and a
+/// converted type are rendered with their logical type.
+pub fn to_message(schema: &ParquetType) -> String {
+    let mut message = String::new();
+    message.push_str("message ");
+    message.push_str(schema.name());
+    message.push_str(" {\n");
+    match schema {
+        ParquetType::GroupType { fields, .. } => fields
+            .iter()
+            .for_each(|field| write_type(&mut message, field, 1)),
+        primitive => write_type(&mut message, primitive, 1),
+    }
+    message.push_str("}\n");
+    message
+}
+
+fn repetition_to_str(repetition: Repetition) -> &'static str {
+    match repetition {
+        Repetition::Required => "REQUIRED",
+        Repetition::Optional => "OPTIONAL",
+        Repetition::Repeated => "REPEATED",
+    }
+}
+
+fn physical_type_to_str(physical_type: PhysicalType) -> String {
+    match physical_type {
+        PhysicalType::Boolean => "BOOLEAN".to_string(),
+        PhysicalType::Int32 => "INT32".to_string(),
+        PhysicalType::Int64 => "INT64".to_string(),
+        PhysicalType::Int96 => "INT96".to_string(),
+        PhysicalType::Float => "FLOAT".to_string(),
+        PhysicalType::Double => "DOUBLE".to_string(),
+        PhysicalType::ByteArray => "BYTE_ARRAY".to_string(),
+        PhysicalType::FixedLenByteArray(length) => format!("FIXED_LEN_BYTE_ARRAY ({})", length),
+    }
+}
+
+fn time_unit_to_str(unit: TimeUnit) -> &'static str {
+    match unit {
+        TimeUnit::Milliseconds => "MILLIS",
+        TimeUnit::Microseconds => "MICROS",
+        TimeUnit::Nanoseconds => "NANOS",
+    }
+}
+
+fn integer_to_str(integer: IntegerType) -> &'static str {
+    match integer {
+        IntegerType::Int8 => "8,true",
+        IntegerType::Int16 => "16,true",
+        IntegerType::Int32 => "32,true",
+        IntegerType::Int64 => "64,true",
+        IntegerType::UInt8 => "8,false",
+        IntegerType::UInt16 => "16,false",
+        IntegerType::UInt32 => "32,false",
+        IntegerType::UInt64 => "64,false",
+    }
+}
+
+fn logical_primitive_to_str(logical_type: PrimitiveLogicalType) -> String {
+    match logical_type {
+        PrimitiveLogicalType::String => "STRING".to_string(),
+        PrimitiveLogicalType::Enum => "ENUM".to_string(),
+        PrimitiveLogicalType::Decimal(precision, scale) => {
+            format!("DECIMAL({},{})", precision, scale)
+        }
+        PrimitiveLogicalType::Date => "DATE".to_string(),
+        PrimitiveLogicalType::Time {
+            unit,
+            is_adjusted_to_utc,
+        } => format!("TIME({},{})", time_unit_to_str(unit), is_adjusted_to_utc),
+        PrimitiveLogicalType::Timestamp {
+            unit,
+            is_adjusted_to_utc,
+        } => format!(
+            "TIMESTAMP({},{})",
+            time_unit_to_str(unit),
+            is_adjusted_to_utc
+        ),
+        PrimitiveLogicalType::Integer(integer) => format!("INTEGER({})", integer_to_str(integer)),
+        PrimitiveLogicalType::Unknown => "UNKNOWN".to_string(),
+        PrimitiveLogicalType::Json => "JSON".to_string(),
+        PrimitiveLogicalType::Bson => "BSON".to_string(),
+        PrimitiveLogicalType::Uuid => "UUID".to_string(),
+        PrimitiveLogicalType::Float16 => "FLOAT16".to_string(),
+    }
+}
+
+fn converted_primitive_to_str(converted_type: PrimitiveConvertedType) -> String {
+    use PrimitiveConvertedType::*;
+    match converted_type {
+        Utf8 => "UTF8".to_string(),
+        Enum => "ENUM".to_string(),
+        Decimal(precision, scale) => format!("DECIMAL({},{})", precision, scale),
+        Date => "DATE".to_string(),
+        TimeMillis => "TIME_MILLIS".to_string(),
+        TimeMicros => "TIME_MICROS".to_string(),
+        TimestampMillis => "TIMESTAMP_MILLIS".to_string(),
+        TimestampMicros => "TIMESTAMP_MICROS".to_string(),
+        Uint8 => "UINT_8".to_string(),
+        Uint16 => "UINT_16".to_string(),
+        Uint32 => "UINT_32".to_string(),
+        Uint64 => "UINT_64".to_string(),
+        Int8 => "INT_8".to_string(),
+        Int16 => "INT_16".to_string(),
+        Int32 => "INT_32".to_string(),
+        Int64 => "INT_64".to_string(),
+        Json => "JSON".to_string(),
+        Bson => "BSON".to_string(),
+        Interval => "INTERVAL".to_string(),
+    }
+}
+
+fn group_to_str(
+    logical_type: Option<GroupLogicalType>,
+    converted_type: Option<GroupConvertedType>,
+) -> Option<&'static str> {
+    match (logical_type, converted_type) {
+        (Some(GroupLogicalType::Map), _) => Some("MAP"),
+        (Some(GroupLogicalType::List), _) => Some("LIST"),
+        (None, Some(GroupConvertedType::Map)) => Some("MAP"),
+        (None, Some(GroupConvertedType::MapKeyValue)) => Some("MAP_KEY_VALUE"),
+        (None, Some(GroupConvertedType::List)) => Some("LIST"),
+        (None, None) => None,
+    }
+}
+
+// Writes the ` = id` suffix of a field, if it has an id.
+fn write_id(message: &mut String, field_info: &FieldInfo) {
+    if let Some(id) = field_info.id {
+        // writing to a `String` is infallible
+        write!(message, " = {}", id).unwrap();
+    }
+}
+
+fn write_type(message: &mut String, tpe: &ParquetType, depth: usize) {
+    let indent = INDENT.repeat(depth);
+    match tpe {
+        ParquetType::PrimitiveType(PrimitiveType {
+            field_info,
+            logical_type,
+            converted_type,
+            physical_type,
+        }) => {
+            write!(
+                message,
+                "{}{} {} {}",
+                indent,
+                repetition_to_str(field_info.repetition),
+                physical_type_to_str(*physical_type),
+                field_info.name
+            )
+            .unwrap();
+            let annotation = logical_type
+                .map(logical_primitive_to_str)
+                .or_else(|| converted_type.map(converted_primitive_to_str));
+            if let Some(annotation) = annotation {
+                write!(message, " ({})", annotation).unwrap();
+            }
+            write_id(message, field_info);
+            message.push_str(";\n");
+        }
+        ParquetType::GroupType {
+            field_info,
+            logical_type,
+            converted_type,
+            fields,
+        } => {
+            write!(
+                message,
+                "{}{} group {}",
+                indent,
+                repetition_to_str(field_info.repetition),
+                field_info.name
+            )
+            .unwrap();
+            if let Some(annotation) = group_to_str(*logical_type, *converted_type) {
+                write!(message, " ({})", annotation).unwrap();
+            }
+            write_id(message, field_info);
+            message.push_str(" {\n");
+            fields
+                .iter()
+                .for_each(|field| write_type(message, field, depth + 1));
+            message.push_str(&indent);
+            message.push_str("}\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Result;
+    use crate::schema::io_message::from_message;
+
+    fn test_round_trip(message: &str) -> Result<()> {
+        let expected = from_message(message)?;
+        let result = from_message(&to_message(&expected))?;
+        assert_eq!(result, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        test_round_trip(
+            "
+    message root {
+      REQUIRED BOOLEAN a = 1;
+      OPTIONAL INT32 b (INTEGER(8,true));
+      OPTIONAL INT32 c (UINT_16);
+      REQUIRED INT64 d (TIMESTAMP(NANOS,true)) = 2;
+      REQUIRED INT64 e (TIMESTAMP_MICROS);
+      REQUIRED INT32 f (TIME(MILLIS,false));
+      OPTIONAL INT96 g;
+      REQUIRED FLOAT h;
+      REQUIRED DOUBLE i;
+      OPTIONAL BYTE_ARRAY j (STRING);
+      OPTIONAL BYTE_ARRAY k (UTF8);
+      OPTIONAL BYTE_ARRAY l (JSON);
+      OPTIONAL FIXED_LEN_BYTE_ARRAY (16) m (UUID);
+      OPTIONAL FIXED_LEN_BYTE_ARRAY (2) m16 (FLOAT16);
+      OPTIONAL FIXED_LEN_BYTE_ARRAY (9) n (DECIMAL(20,2));
+      OPTIONAL INT32 o (DATE);
+      OPTIONAL BYTE_ARRAY p (ENUM);
+      OPTIONAL group q (LIST) = 3 {
+        REPEATED group list {
+          OPTIONAL group element (MAP) {
+            REPEATED group key_value (MAP_KEY_VALUE) {
+              REQUIRED BYTE_ARRAY key (UTF8);
+              OPTIONAL INT64 value (INTEGER(64,false));
+            }
+          }
+        }
+      }
+      REQUIRED group r {
+        OPTIONAL INT64 s (TIME(MICROS,true));
+      }
+    }
+    ",
+        )
+    }
+
+    #[test]
+    fn format() -> Result<()> {
+        let message = "message root {
+  REQUIRED INT32 a (INTEGER(16,false)) = 1;
+  OPTIONAL group b (LIST) {
+    REPEATED group list {
+      OPTIONAL FIXED_LEN_BYTE_ARRAY (4) element (DECIMAL(9,2));
+    }
+  }
+}
+";
+        assert_eq!(to_message(&from_message(message)?), message);
+        Ok(())
+    }
+
+    #[test]
+    fn logical_over_converted() -> Result<()> {
+        let field = ParquetType::try_from_primitive(
+            "a".to_string(),
+            PhysicalType::Int64,
+            Repetition::Required,
+            Some(PrimitiveConvertedType::TimestampMillis),
+            Some(PrimitiveLogicalType::Timestamp {
+                unit: TimeUnit::Milliseconds,
+                is_adjusted_to_utc: true,
+            }),
+            None,
+        )?;
+        let schema = ParquetType::new_root("root".to_string(), vec![field]);
+        assert_eq!(
+            to_message(&schema),
+            "message root {\n  REQUIRED INT64 a (TIMESTAMP(MILLIS,true));\n}\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn schema_descriptor() -> Result<()> {
+        let message = "message root {\n  OPTIONAL BYTE_ARRAY a (STRING);\n}\n";
+        let schema = crate::metadata::SchemaDescriptor::try_from_message(message)?;
+        assert_eq!(schema.to_message(), message);
+        Ok(())
+    }
+}
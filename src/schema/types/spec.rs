@@ -171,6 +171,8 @@ pub fn check_logical_invariants(
         (String | Json | Bson, PhysicalType::ByteArray) => {}
         // https://github.com/apache/parquet-format/blob/master/LogicalTypes.md#uuid
         (Uuid, PhysicalType::FixedLenByteArray(16)) => {}
+        // https://github.com/apache/parquet-format/blob/master/LogicalTypes.md#float16
+        (Float16, PhysicalType::FixedLenByteArray(2)) => {}
         (a, b) => {
             return Err(Error::oos(format!(
                 "Cannot annotate {:?} from {:?} fields",
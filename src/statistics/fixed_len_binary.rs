@@ -36,15 +36,16 @@ pub fn read(
     size: usize,
     primitive_type: PrimitiveType,
 ) -> Result<Arc<dyn Statistics>> {
+    // writers may truncate the min and max, which are then bounds shorter than `size`
     if let Some(ref v) = v.max_value {
-        if v.len() != size {
+        if v.len() > size {
             return Err(Error::oos(
                 "The max_value of statistics MUST be plain encoded",
             ));
         }
     };
     if let Some(ref v) = v.min_value {
-        if v.len() != size {
+        if v.len() > size {
             return Err(Error::oos(
                 "The min_value of statistics MUST be plain encoded",
             ));
@@ -74,5 +75,7 @@ pub fn write(v: &FixedLenStatistics) -> ParquetStatistics {
         min_value: v.min_value.clone(),
         min: None,
         max: None,
+        is_max_value_exact: None,
+        is_min_value_exact: None,
     }
 }
@@ -99,6 +99,77 @@ impl NativeType for [u32; 3] {
     }
 }
 
+/// Returns whether the FLOAT16 represented by `value` is NaN.
+#[inline]
+pub fn is_float16_nan(value: u16) -> bool {
+    value & 0x7c00 == 0x7c00 && value & 0x03ff != 0
+}
+
+/// Returns the ordering of two FLOAT16 represented by their bit pattern, as signed floats.
+/// Like [`f32`], NaN is not ordered (it is considered equal to every value) and `-0 == +0`.
+#[inline]
+pub fn ord_float16(a: u16, b: u16) -> std::cmp::Ordering {
+    if is_float16_nan(a) || is_float16_nan(b) {
+        return std::cmp::Ordering::Equal;
+    }
+    // sign-magnitude to two's complement
+    let to_i32 = |x: u16| {
+        let magnitude = (x & 0x7fff) as i32;
+        if x & 0x8000 != 0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    };
+    to_i32(a).cmp(&to_i32(b))
+}
+
+/// Converts the FLOAT16 represented by `value` to a [`f32`].
+pub fn float16_to_f32(value: u16) -> f32 {
+    let sign = if value & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((value >> 10) & 0x1f) as i32;
+    let mantissa = (value & 0x3ff) as f32;
+    sign * match exponent {
+        0 => mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+/// Converts a [`f32`] to the bit pattern of the nearest FLOAT16 (ties to even).
+pub fn f32_to_float16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x0200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    // the exponent with the bias of a FLOAT16
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    let (half, shift) = if exponent <= 0 {
+        // subnormal
+        if exponent < -10 {
+            return sign;
+        }
+        let shift = (14 - exponent) as u32;
+        ((mantissa | 0x80_0000) >> shift, shift)
+    } else {
+        (((exponent as u32) << 10) | (mantissa >> 13), 13)
+    };
+    let remainder = (mantissa | 0x80_0000) & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    // a carry of the mantissa into the exponent is the correct rounding
+    let round = remainder > halfway || (remainder == halfway && half & 1 == 1);
+    sign | (half + round as u32) as u16
+}
+
 #[inline]
 pub fn int96_to_i64_ns(value: [u32; 3]) -> i64 {
     const JULIAN_DAY_OF_EPOCH: i64 = 2_440_588;
@@ -112,6 +183,17 @@ pub fn int96_to_i64_ns(value: [u32; 3]) -> i64 {
     seconds * NANOS_PER_SECOND + nanoseconds
 }
 
+/// The inverse of [`int96_to_i64_ns`].
+#[inline]
+pub fn i64_ns_to_int96(value: i64) -> [u32; 3] {
+    const JULIAN_DAY_OF_EPOCH: i64 = 2_440_588;
+    const NANOS_PER_DAY: i64 = 86_400 * 1_000_000_000;
+
+    let day = value.div_euclid(NANOS_PER_DAY) + JULIAN_DAY_OF_EPOCH;
+    let nanoseconds = value.rem_euclid(NANOS_PER_DAY);
+    [nanoseconds as u32, (nanoseconds >> 32) as u32, day as u32]
+}
+
 /// Returns the ordering of two binary values.
 pub fn ord_binary<'a>(a: &'a [u8], b: &'a [u8]) -> std::cmp::Ordering {
     use std::cmp::Ordering::*;
@@ -139,3 +221,53 @@ pub fn decode<T: NativeType>(chunk: &[u8]) -> T {
     };
     T::from_le_bytes(chunk)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering::*;
+
+    use super::*;
+
+    #[test]
+    fn float16() {
+        // 1.0, -2.0, +0, -0, +inf, NaN
+        let (one, minus_two, zero, minus_zero, inf, nan) =
+            (0x3c00, 0xc000, 0x0000, 0x8000, 0x7c00, 0x7e00);
+        assert_eq!(ord_float16(one, minus_two), Greater);
+        assert_eq!(ord_float16(minus_two, minus_zero), Less);
+        assert_eq!(ord_float16(zero, minus_zero), Equal);
+        assert_eq!(ord_float16(inf, one), Greater);
+        assert_eq!(ord_float16(nan, one), Equal);
+        assert!(is_float16_nan(nan));
+        assert!(!is_float16_nan(inf));
+    }
+
+    #[test]
+    fn float16_conversion() {
+        assert_eq!(float16_to_f32(0x3c00), 1.0);
+        assert_eq!(float16_to_f32(0xc000), -2.0);
+        assert_eq!(float16_to_f32(0x3555), 0.33325195);
+        assert_eq!(float16_to_f32(0x0001), 5.9604645e-8);
+        assert_eq!(float16_to_f32(0x7c00), f32::INFINITY);
+        assert!(float16_to_f32(0x7e00).is_nan());
+
+        for value in [
+            0x3c00, 0xc000, 0x3555, 0x0001, 0x03ff, 0x7bff, 0x8000, 0x7c00,
+        ] {
+            assert_eq!(f32_to_float16(float16_to_f32(value)), value);
+        }
+        // ties to even
+        assert_eq!(f32_to_float16(1.0 + 2f32.powi(-11)), 0x3c00);
+        assert_eq!(f32_to_float16(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+        assert_eq!(f32_to_float16(1e6), 0x7c00);
+        assert_eq!(f32_to_float16(1e-10), 0x0000);
+        assert!(is_float16_nan(f32_to_float16(f32::NAN)));
+    }
+
+    #[test]
+    fn int96() {
+        for value in [0, 1, -1, 1_600_000_000_123_456_789, -86_400_000_000_001] {
+            assert_eq!(int96_to_i64_ns(i64_ns_to_int96(value)), value);
+        }
+    }
+}
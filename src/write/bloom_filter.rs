@@ -0,0 +1,101 @@
+use std::io::Write;
+
+#[cfg(feature = "async")]
+use futures::{AsyncWrite, AsyncWriteExt};
+
+use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
+use parquet_format_safe::{
+    BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash, BloomFilterHeader,
+    SplitBlockAlgorithm, Uncompressed, XxHash,
+};
+
+use crate::error::{Error, Result};
+
+use super::encryption::ColumnEncryptor;
+
+/// Checks that `bitset` is a valid split block bloom filter bitset.
+fn check_bitset(bitset: &[u8]) -> Result<()> {
+    // SPEC: each block is 256 bits (32 bytes)
+    if bitset.is_empty() || bitset.len() % 32 != 0 {
+        return Err(Error::InvalidParameter(format!(
+            "The bitset of a bloom filter must be a non-zero multiple of 32 bytes, but it has {} bytes",
+            bitset.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Sets `bitset` as the bloom filter of the column `column` of the row group `row_group`
+/// in `bloom_filters`, the bitsets of each column of each row group written so far.
+pub(super) fn set_bloom_filter(
+    bloom_filters: &mut [Vec<Option<Vec<u8>>>],
+    row_group: usize,
+    column: usize,
+    bitset: Vec<u8>,
+) -> Result<()> {
+    check_bitset(&bitset)?;
+    let slot = bloom_filters
+        .get_mut(row_group)
+        .and_then(|columns| columns.get_mut(column))
+        .ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "There is no column {} in row group {}",
+                column, row_group
+            ))
+        })?;
+    *slot = Some(bitset);
+    Ok(())
+}
+
+fn header(bitset: &[u8]) -> Result<BloomFilterHeader> {
+    Ok(BloomFilterHeader {
+        num_bytes: bitset.len().try_into()?,
+        algorithm: BloomFilterAlgorithm::BLOCK(SplitBlockAlgorithm {}),
+        hash: BloomFilterHash::XXHASH(XxHash {}),
+        compression: BloomFilterCompression::UNCOMPRESSED(Uncompressed {}),
+    })
+}
+
+/// Serializes the header of the bloom filter `bitset`
+fn serialize_header(bitset: &[u8]) -> Result<Vec<u8>> {
+    let mut header_bytes = vec![];
+    let mut protocol = TCompactOutputProtocol::new(&mut header_bytes);
+    header(bitset)?.write_to_out_protocol(&mut protocol)?;
+    Ok(header_bytes)
+}
+
+/// Writes the header and `bitset` of a bloom filter, encrypted by `encryptor` if any,
+/// returning the number of bytes written.
+pub fn write_bloom_filter<W: Write>(
+    writer: &mut W,
+    bitset: &[u8],
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<u64> {
+    let header = serialize_header(bitset)?;
+    if let Some(encryptor) = encryptor {
+        let modules = encryptor.encrypt_bloom_filter(&header, bitset)?;
+        writer.write_all(&modules)?;
+        return Ok(modules.len() as u64);
+    }
+    writer.write_all(&header)?;
+    writer.write_all(bitset)?;
+    Ok((header.len() + bitset.len()) as u64)
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn write_bloom_filter_async<W: AsyncWrite + Unpin + Send>(
+    writer: &mut W,
+    bitset: &[u8],
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<u64> {
+    let header = serialize_header(bitset)?;
+    if let Some(encryptor) = encryptor {
+        let modules = encryptor.encrypt_bloom_filter(&header, bitset)?;
+        writer.write_all(&modules).await?;
+        return Ok(modules.len() as u64);
+    }
+    writer.write_all(&header).await?;
+    writer.write_all(bitset).await?;
+    Ok((header.len() + bitset.len()) as u64)
+}
@@ -9,7 +9,6 @@ use futures::AsyncWrite;
 #[cfg(feature = "async")]
 use parquet_format_safe::thrift::protocol::TCompactOutputStreamProtocol;
 
-use crate::statistics::serialize_statistics;
 use crate::FallibleStreamingIterator;
 use crate::{
     compression::Compression,
@@ -22,46 +21,162 @@ use crate::{
 #[cfg(feature = "async")]
 use super::page::write_page_async;
 
-use super::page::{write_page, PageWriteSpec};
-use super::statistics::reduce;
-use super::DynStreamingIterator;
+use super::encryption::ColumnEncryptor;
+use super::page::{is_data_page, write_page, PageWriteSpec};
+use super::statistics::{reduce, serialize_truncated};
+use super::{DynStreamingIterator, WriteOptions};
 
-pub fn write_column_chunk<'a, W, E>(
+/// Writes the `compressed_pages` starting at `offset`, returning the spec of each page
+fn write_pages<W, E>(
     writer: &mut W,
     mut offset: u64,
-    descriptor: &ColumnDescriptor,
-    mut compressed_pages: DynStreamingIterator<'a, CompressedPage, E>,
-) -> Result<(ColumnChunk, Vec<PageWriteSpec>, u64)>
+    mut compressed_pages: DynStreamingIterator<'_, CompressedPage, E>,
+    options: &WriteOptions,
+    mut encryptor: Option<&mut ColumnEncryptor>,
+) -> Result<Vec<PageWriteSpec>>
 where
     W: Write,
     Error: From<E>,
     E: std::error::Error,
 {
-    // write every page
-
-    let initial = offset;
-
     let mut specs = vec![];
     while let Some(compressed_page) = compressed_pages.next()? {
-        let spec = write_page(writer, offset, compressed_page)?;
+        let spec = write_page(
+            writer,
+            offset,
+            compressed_page,
+            options,
+            encryptor.as_deref_mut(),
+        )?;
         offset += spec.bytes_written;
         specs.push(spec);
     }
-    let mut bytes_written = offset - initial;
-
-    let column_chunk = build_column_chunk(&specs, descriptor)?;
+    Ok(specs)
+}
 
-    // write metadata
+/// Writes the metadata of `column_chunk`, returning the number of bytes written
+fn write_column_metadata<W: Write>(writer: &mut W, column_chunk: &ColumnChunk) -> Result<u64> {
     let mut protocol = TCompactOutputProtocol::new(writer);
-    bytes_written += column_chunk
+    Ok(column_chunk
         .meta_data
         .as_ref()
         .unwrap()
-        .write_to_out_protocol(&mut protocol)? as u64;
+        .write_to_out_protocol(&mut protocol)? as u64)
+}
+
+pub fn write_column_chunk<'a, W, E>(
+    writer: &mut W,
+    offset: u64,
+    descriptor: &ColumnDescriptor,
+    compressed_pages: DynStreamingIterator<'a, CompressedPage, E>,
+    options: &WriteOptions,
+    mut encryptor: Option<&mut ColumnEncryptor>,
+) -> Result<(ColumnChunk, Vec<PageWriteSpec>, u64)>
+where
+    W: Write,
+    Error: From<E>,
+    E: std::error::Error,
+{
+    // write every page
+    let specs = write_pages(
+        writer,
+        offset,
+        compressed_pages,
+        options,
+        encryptor.as_deref_mut(),
+    )?;
+    let mut bytes_written = specs.iter().map(|spec| spec.bytes_written).sum::<u64>();
+
+    let mut column_chunk = build_column_chunk(&specs, descriptor, options)?;
+
+    if let Some(encryptor) = encryptor {
+        // the metadata of encrypted columns is only written (encrypted) in the footer
+        column_chunk.crypto_metadata = Some(encryptor.crypto_metadata());
+        return Ok((column_chunk, specs, bytes_written));
+    }
+
+    // write metadata
+    bytes_written += write_column_metadata(writer, &column_chunk)?;
 
     Ok((column_chunk, specs, bytes_written))
 }
 
+/// A column chunk serialized to memory by [`encode_column_chunk`], to be appended to a file
+/// with [`crate::write::FileWriter::write_encoded`].
+///
+/// Its offsets are relative to the start of its buffer until it is appended to a file, when
+/// its metadata is written after its pages.
+#[derive(Debug)]
+pub struct EncodedColumnChunk {
+    buffer: Vec<u8>,
+    column_chunk: ColumnChunk,
+    specs: Vec<PageWriteSpec>,
+}
+
+impl EncodedColumnChunk {
+    /// The serialized pages of the column chunk
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// The [`ColumnChunk`] of the column chunk
+    pub fn column_chunk(&self) -> &ColumnChunk {
+        &self.column_chunk
+    }
+
+    /// The [`PageWriteSpec`] of each page of the column chunk
+    pub fn page_specs(&self) -> &[PageWriteSpec] {
+        &self.specs
+    }
+
+    /// Writes the column chunk starting at `offset`, returning its [`ColumnChunk`], page specs
+    /// and number of bytes written
+    pub(super) fn write<W: Write>(
+        mut self,
+        writer: &mut W,
+        offset: u64,
+    ) -> Result<(ColumnChunk, Vec<PageWriteSpec>, u64)> {
+        self.specs.iter_mut().for_each(|spec| spec.offset += offset);
+        let column_chunk = &mut self.column_chunk;
+        column_chunk.file_offset += offset as i64;
+        if let Some(metadata) = column_chunk.meta_data.as_mut() {
+            metadata.data_page_offset += offset as i64;
+            if let Some(dictionary_page_offset) = metadata.dictionary_page_offset.as_mut() {
+                *dictionary_page_offset += offset as i64;
+            }
+        }
+
+        writer.write_all(&self.buffer)?;
+        let bytes_written =
+            self.buffer.len() as u64 + write_column_metadata(writer, &self.column_chunk)?;
+        Ok((self.column_chunk, self.specs, bytes_written))
+    }
+}
+
+/// Serializes the `compressed_pages` of the column `descriptor` to memory.
+///
+/// Unlike [`crate::write::FileWriter::write`], this does not require the column chunk to be
+/// written to the file right away: the column chunks of a row group can be encoded
+/// independently (e.g. on different threads) and appended in order afterwards.
+pub fn encode_column_chunk<E>(
+    descriptor: &ColumnDescriptor,
+    compressed_pages: DynStreamingIterator<'_, CompressedPage, E>,
+    options: &WriteOptions,
+) -> Result<EncodedColumnChunk>
+where
+    Error: From<E>,
+    E: std::error::Error,
+{
+    let mut buffer = vec![];
+    let specs = write_pages(&mut buffer, 0, compressed_pages, options, None)?;
+    let column_chunk = build_column_chunk(&specs, descriptor, options)?;
+    Ok(EncodedColumnChunk {
+        buffer,
+        column_chunk,
+        specs,
+    })
+}
+
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub async fn write_column_chunk_async<W, E>(
@@ -69,6 +184,8 @@ pub async fn write_column_chunk_async<W, E>(
     mut offset: u64,
     descriptor: &ColumnDescriptor,
     mut compressed_pages: DynStreamingIterator<'_, CompressedPage, E>,
+    options: &WriteOptions,
+    mut encryptor: Option<&mut ColumnEncryptor>,
 ) -> Result<(ColumnChunk, Vec<PageWriteSpec>, u64)>
 where
     W: AsyncWrite + Unpin + Send,
@@ -79,13 +196,26 @@ where
     // write every page
     let mut specs = vec![];
     while let Some(compressed_page) = compressed_pages.next()? {
-        let spec = write_page_async(writer, offset, compressed_page).await?;
+        let spec = write_page_async(
+            writer,
+            offset,
+            compressed_page,
+            options,
+            encryptor.as_deref_mut(),
+        )
+        .await?;
         offset += spec.bytes_written;
         specs.push(spec);
     }
     let mut bytes_written = offset - initial;
 
-    let column_chunk = build_column_chunk(&specs, descriptor)?;
+    let mut column_chunk = build_column_chunk(&specs, descriptor, options)?;
+
+    if let Some(encryptor) = encryptor {
+        // the metadata of encrypted columns is only written (encrypted) in the footer
+        column_chunk.crypto_metadata = Some(encryptor.crypto_metadata());
+        return Ok((column_chunk, specs, bytes_written));
+    }
 
     // write metadata
     let mut protocol = TCompactOutputStreamProtocol::new(writer);
@@ -102,6 +232,7 @@ where
 fn build_column_chunk(
     specs: &[PageWriteSpec],
     descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
 ) -> Result<ColumnChunk> {
     // compute stats to build header at the end of the chunk
 
@@ -129,7 +260,17 @@ fn build_column_chunk(
         .iter()
         .map(|x| x.header_size as i64 + x.header.uncompressed_page_size as i64)
         .sum();
-    let data_page_offset = specs.first().map(|spec| spec.offset).unwrap_or(0) as i64;
+    let offset = specs.first().map(|spec| spec.offset).unwrap_or(0) as i64;
+    // SPEC: the dictionary page, when present, is the first page of the column chunk
+    let dictionary_page_offset = specs
+        .first()
+        .filter(|spec| !is_data_page(spec))
+        .map(|spec| spec.offset as i64);
+    let data_page_offset = specs
+        .iter()
+        .find(|spec| is_data_page(spec))
+        .map(|spec| spec.offset as i64)
+        .unwrap_or(offset);
     let num_values = specs
         .iter()
         .map(|spec| {
@@ -178,7 +319,8 @@ fn build_column_chunk(
 
     let statistics = specs.iter().map(|x| &x.statistics).collect::<Vec<_>>();
     let statistics = reduce(&statistics)?;
-    let statistics = statistics.map(|x| serialize_statistics(x.as_ref()));
+    let statistics =
+        statistics.map(|x| serialize_truncated(x.as_ref(), options.statistics_truncate_length));
 
     let (type_, _): (Type, Option<i32>) = descriptor.descriptor.primitive_type.physical_type.into();
 
@@ -193,15 +335,17 @@ fn build_column_chunk(
         key_value_metadata: None,
         data_page_offset,
         index_page_offset: None,
-        dictionary_page_offset: None,
+        dictionary_page_offset,
         statistics,
         encoding_stats: None,
         bloom_filter_offset: None,
+        bloom_filter_length: None,
+        size_statistics: None,
     };
 
     Ok(ColumnChunk {
         file_path: None, // same file for now.
-        file_offset: data_page_offset + total_compressed_size,
+        file_offset: offset + total_compressed_size,
         meta_data: Some(metadata),
         offset_index_offset: None,
         offset_index_length: None,
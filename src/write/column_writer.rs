@@ -0,0 +1,385 @@
+//! Value-level writing of a column chunk, split into pages by the writer.
+use crate::compression::CompressionOptions;
+use crate::error::{Error, Result};
+use crate::fallible_streaming_iterator::FallibleStreamingIterator;
+use crate::metadata::Descriptor;
+use crate::nested::ShreddedLeaf;
+use crate::page::{CompressedPage, Page};
+use crate::schema::types::PhysicalType;
+
+use super::rows::{encode_page, Values};
+use super::{compress, DictEncoder, DynStreamingIterator, WriteOptions, DEFAULT_MAX_DICT_SIZE};
+
+/// A batch of non-null values of a column, in their physical representation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnValues<'a> {
+    Boolean(&'a [bool]),
+    Int32(&'a [i32]),
+    Int64(&'a [i64]),
+    Int96(&'a [[u32; 3]]),
+    Float(&'a [f32]),
+    Double(&'a [f64]),
+    ByteArray(&'a [Vec<u8>]),
+    FixedLenByteArray(&'a [Vec<u8>]),
+}
+
+impl<'a> ColumnValues<'a> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Boolean(values) => values.len(),
+            Self::Int32(values) => values.len(),
+            Self::Int64(values) => values.len(),
+            Self::Int96(values) => values.len(),
+            Self::Float(values) => values.len(),
+            Self::Double(values) => values.len(),
+            Self::ByteArray(values) | Self::FixedLenByteArray(values) => values.len(),
+        }
+    }
+
+    /// Returns the owned [`Values`] of a column of `physical_type`
+    fn to_values(self, physical_type: PhysicalType) -> Result<Values> {
+        Ok(match (self, physical_type) {
+            (Self::Boolean(values), PhysicalType::Boolean) => Values::Boolean(values.to_vec()),
+            (Self::Int32(values), PhysicalType::Int32) => Values::Int32(values.to_vec()),
+            (Self::Int64(values), PhysicalType::Int64) => Values::Int64(values.to_vec()),
+            (Self::Int96(values), PhysicalType::Int96) => Values::Int96(values.to_vec()),
+            (Self::Float(values), PhysicalType::Float) => Values::Float(values.to_vec()),
+            (Self::Double(values), PhysicalType::Double) => Values::Double(values.to_vec()),
+            (Self::ByteArray(values), PhysicalType::ByteArray) => Values::Binary(values.to_vec()),
+            (Self::FixedLenByteArray(values), PhysicalType::FixedLenByteArray(size)) => {
+                if let Some(value) = values.iter().find(|value| value.len() != size) {
+                    return Err(Error::InvalidParameter(format!(
+                        "The values of the column must have {} bytes but one has {}",
+                        size,
+                        value.len()
+                    )));
+                }
+                Values::FixedLenBinary(values.to_vec())
+            }
+            (values, physical_type) => {
+                return Err(Error::InvalidParameter(format!(
+                    "Values {:?} cannot be written to a column of physical type {:?}",
+                    values, physical_type
+                )))
+            }
+        })
+    }
+}
+
+/// Options of [`ColumnWriter`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ColumnWriterOptions {
+    /// The compression of the pages
+    pub compression: CompressionOptions,
+    /// Whether to dictionary-encode the values of non-boolean columns. Each column chunk falls
+    /// back to [`crate::encoding::Encoding::Plain`] once its dictionary exceeds
+    /// [`DEFAULT_MAX_DICT_SIZE`].
+    pub dictionary: bool,
+    /// The (approximate) number of uncompressed bytes of values after which a data page is
+    /// finished
+    pub max_page_size: usize,
+    /// The maximum number of rows of a data page
+    pub max_rows_per_page: usize,
+    /// The maximum number of rows of a row group
+    pub max_rows_per_row_group: usize,
+    /// The (approximate) number of bytes of a column chunk after which its row group is full
+    pub max_bytes_per_row_group: usize,
+}
+
+impl Default for ColumnWriterOptions {
+    fn default() -> Self {
+        Self {
+            compression: CompressionOptions::Uncompressed,
+            dictionary: true,
+            max_page_size: 1024 * 1024,
+            max_rows_per_page: 20_000,
+            max_rows_per_row_group: 1024 * 1024,
+            max_bytes_per_row_group: 128 * 1024 * 1024,
+        }
+    }
+}
+
+/// A writer of the values and levels of a column that splits them into pages.
+///
+/// Batches of values and their levels are buffered until the current page reaches
+/// [`ColumnWriterOptions::max_page_size`] bytes or [`ColumnWriterOptions::max_rows_per_page`]
+/// rows, at which point it is encoded and compressed. Pages are only finished at the start of
+/// a record (repetition level 0), so a record is never split across pages, even when it spans
+/// multiple batches.
+///
+/// [`ColumnWriter::is_full`] reports when the column chunk reached the size of a row group,
+/// after which [`ColumnWriter::finish`] returns its pages, e.g. to be written by
+/// [`super::FileWriter::write`] or [`super::encode_column_chunk`].
+pub struct ColumnWriter {
+    descriptor: Descriptor,
+    write_options: WriteOptions,
+    options: ColumnWriterOptions,
+    dict: Option<DictEncoder>,
+    /// the finished pages of the column chunk
+    pages: Vec<CompressedPage>,
+    pages_size: usize,
+    /// the levels and values of the current page
+    levels: ShreddedLeaf,
+    values: Values,
+    /// the number of bytes of values and of rows of the current page
+    page_size: usize,
+    page_rows: usize,
+    num_rows: usize,
+}
+
+impl ColumnWriter {
+    /// Returns a new [`ColumnWriter`] of the column described by `descriptor`.
+    /// # Errors
+    /// Errors iff the dictionary encoder cannot be created for the column.
+    pub fn try_new(
+        descriptor: Descriptor,
+        write_options: WriteOptions,
+        options: ColumnWriterOptions,
+    ) -> Result<Self> {
+        let physical_type = descriptor.primitive_type.physical_type;
+        Ok(Self {
+            dict: Self::dict_encoder(physical_type, &options)?,
+            values: Values::new(physical_type),
+            descriptor,
+            write_options,
+            options,
+            pages: vec![],
+            pages_size: 0,
+            levels: ShreddedLeaf::default(),
+            page_size: 0,
+            page_rows: 0,
+            num_rows: 0,
+        })
+    }
+
+    fn dict_encoder(
+        physical_type: PhysicalType,
+        options: &ColumnWriterOptions,
+    ) -> Result<Option<DictEncoder>> {
+        Ok(
+            if options.dictionary && physical_type != PhysicalType::Boolean {
+                Some(DictEncoder::try_new(physical_type, DEFAULT_MAX_DICT_SIZE)?)
+            } else {
+                None
+            },
+        )
+    }
+
+    /// The [`Descriptor`] of the column of this writer
+    pub fn descriptor(&self) -> &Descriptor {
+        &self.descriptor
+    }
+
+    /// The number of rows of the current column chunk, including the last record, which may
+    /// continue in the next batch
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// The (approximate) number of bytes of the current column chunk: the compressed size of
+    /// its finished pages and the uncompressed size of its buffered values
+    pub fn estimated_size(&self) -> usize {
+        self.pages_size + self.page_size
+    }
+
+    /// Whether the current column chunk reached [`ColumnWriterOptions::max_rows_per_row_group`]
+    /// rows or [`ColumnWriterOptions::max_bytes_per_row_group`] bytes, in which case its row
+    /// group should be finished
+    pub fn is_full(&self) -> bool {
+        self.num_rows >= self.options.max_rows_per_row_group
+            || self.estimated_size() >= self.options.max_bytes_per_row_group
+    }
+
+    /// Writes a batch of `values` with their definition and repetition levels, one per entry.
+    ///
+    /// The levels must be `None` when the maximum level of the column is 0, in which case each
+    /// value is an entry. `values` are the non-null entries, whose definition level is the
+    /// maximum. The first entry of a column chunk must start a record.
+    /// # Errors
+    /// Errors iff the values or levels are not valid for the column, in which case nothing is
+    /// written, or iff encoding or compressing a page errors.
+    pub fn write(
+        &mut self,
+        values: ColumnValues,
+        def_levels: Option<&[u32]>,
+        rep_levels: Option<&[u32]>,
+    ) -> Result<()> {
+        let max_def_level = self.descriptor.max_def_level as u32;
+        let max_rep_level = self.descriptor.max_rep_level as u32;
+        let num_entries = def_levels
+            .or(rep_levels)
+            .map(|levels| levels.len())
+            .unwrap_or_else(|| values.len());
+
+        for (levels, max_level, name) in [
+            (def_levels, max_def_level, "definition"),
+            (rep_levels, max_rep_level, "repetition"),
+        ] {
+            match levels {
+                None if max_level > 0 => {
+                    return Err(Error::InvalidParameter(format!(
+                        "The {} levels are required by the column",
+                        name
+                    )))
+                }
+                Some(_) if max_level == 0 => {
+                    return Err(Error::InvalidParameter(format!(
+                        "The column has no {} levels",
+                        name
+                    )))
+                }
+                Some(levels) if levels.len() != num_entries => {
+                    return Err(Error::InvalidParameter(
+                        "The definition and repetition levels must have the same length"
+                            .to_string(),
+                    ))
+                }
+                Some(levels) if levels.iter().any(|level| *level > max_level) => {
+                    return Err(Error::InvalidParameter(format!(
+                        "The {} levels must not exceed {}",
+                        name, max_level
+                    )))
+                }
+                _ => {}
+            }
+        }
+        let num_values = def_levels
+            .map(|levels| {
+                levels
+                    .iter()
+                    .filter(|level| **level == max_def_level)
+                    .count()
+            })
+            .unwrap_or(num_entries);
+        if num_values != values.len() {
+            return Err(Error::InvalidParameter(format!(
+                "The levels have {} non-null entries but there are {} values",
+                num_values,
+                values.len()
+            )));
+        }
+        if let Some(levels) = rep_levels {
+            if self.num_rows == 0 && matches!(levels.first(), Some(level) if *level != 0) {
+                return Err(Error::InvalidParameter(
+                    "The first entry of a column chunk must start a record".to_string(),
+                ));
+            }
+        }
+        let values = values.to_values(self.descriptor.primitive_type.physical_type)?;
+
+        let start = self.levels.def_levels.len();
+        match def_levels {
+            Some(levels) => self.levels.def_levels.extend_from_slice(levels),
+            None => self.levels.def_levels.resize(start + num_entries, 0),
+        }
+        match rep_levels {
+            Some(levels) => self.levels.rep_levels.extend_from_slice(levels),
+            None => self.levels.rep_levels.resize(start + num_entries, 0),
+        }
+        let mut value = self.values.len();
+        self.values.extend(values);
+
+        // the page is finished at the first record that starts after it is full
+        let mut i = start;
+        while i < self.levels.def_levels.len() {
+            if self.levels.rep_levels[i] == 0 {
+                if i > 0
+                    && (self.page_size >= self.options.max_page_size
+                        || self.page_rows >= self.options.max_rows_per_page)
+                {
+                    self.finish_page(i, value)?;
+                    i = 0;
+                    value = 0;
+                }
+                self.page_rows += 1;
+                self.num_rows += 1;
+            }
+            if self.levels.def_levels[i] == max_def_level {
+                self.page_size += self.values.size_of(value);
+                value += 1;
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
+    /// Encodes and compresses the first `num_entries` buffered entries, with `num_values`
+    /// values, into a page
+    fn finish_page(&mut self, num_entries: usize, num_values: usize) -> Result<()> {
+        let page = encode_page(
+            &self.levels,
+            0..num_entries,
+            &self.values,
+            0..num_values,
+            &self.descriptor,
+            &self.write_options,
+            self.options.compression,
+            self.dict.as_mut(),
+        )?;
+        let page = compress(page, vec![], self.options.compression)?;
+        if let CompressedPage::Data(page) = &page {
+            self.pages_size += page.compressed_size();
+        }
+        self.pages.push(page);
+
+        self.levels.def_levels.drain(..num_entries);
+        self.levels.rep_levels.drain(..num_entries);
+        self.values.remove_first(num_values);
+        self.page_size = 0;
+        self.page_rows = 0;
+        Ok(())
+    }
+
+    /// Finishes the current column chunk, returning its compressed pages, starting with its
+    /// dictionary page, if any. The writer can then be used for the next column chunk.
+    /// # Errors
+    /// Errors iff encoding or compressing a page errors.
+    pub fn finish(&mut self) -> Result<DynStreamingIterator<'static, CompressedPage, Error>> {
+        let num_entries = self.levels.def_levels.len();
+        if num_entries > 0 || self.pages.is_empty() {
+            self.finish_page(num_entries, self.values.len())?;
+        }
+
+        let physical_type = self.descriptor.primitive_type.physical_type;
+        let dict = std::mem::replace(
+            &mut self.dict,
+            Self::dict_encoder(physical_type, &self.options)?,
+        );
+        let mut pages = std::mem::take(&mut self.pages);
+        if let Some(dict_page) = dict.and_then(|dict| dict.into_dict_page()) {
+            let dict_page = compress(Page::Dict(dict_page), vec![], self.options.compression)?;
+            pages.insert(0, dict_page);
+        }
+        self.pages_size = 0;
+        self.num_rows = 0;
+
+        Ok(DynStreamingIterator::new(Pages {
+            pages: pages.into_iter(),
+            current: None,
+        }))
+    }
+}
+
+/// A [`FallibleStreamingIterator`] over finished pages
+struct Pages {
+    pages: std::vec::IntoIter<CompressedPage>,
+    current: Option<CompressedPage>,
+}
+
+impl FallibleStreamingIterator for Pages {
+    type Item = CompressedPage;
+    type Error = Error;
+
+    fn advance(&mut self) -> Result<()> {
+        self.current = self.pages.next();
+        Ok(())
+    }
+
+    fn get(&self) -> Option<&Self::Item> {
+        self.current.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pages.size_hint()
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use crate::encoding::{hybrid_rle::encode_u32, plain, Encoding};
+use crate::error::{Error, Result};
+use crate::page::DictPage;
+use crate::schema::types::PhysicalType;
+use crate::types::NativeType;
+
+/// The default maximum size in bytes of a dictionary page.
+pub const DEFAULT_MAX_DICT_SIZE: usize = 1024 * 1024;
+
+/// The encoded values of a data page produced by [`DictEncoder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodedValues {
+    /// Indices into the dictionary, prefixed by their bit width as required by
+    /// [`Encoding::RleDictionary`].
+    Indices(Vec<u8>),
+    /// The values encoded as [`Encoding::Plain`], used once the dictionary exceeds its maximum size.
+    Plain(Vec<u8>),
+}
+
+impl EncodedValues {
+    /// The [`Encoding`] of this buffer, to be declared in the data page header.
+    pub fn encoding(&self) -> Encoding {
+        match self {
+            Self::Indices(_) => Encoding::RleDictionary,
+            Self::Plain(_) => Encoding::Plain,
+        }
+    }
+
+    /// The encoded buffer
+    pub fn buffer(&self) -> &[u8] {
+        match self {
+            Self::Indices(buffer) | Self::Plain(buffer) => buffer,
+        }
+    }
+
+    /// Returns the encoded buffer
+    pub fn into_inner(self) -> Vec<u8> {
+        match self {
+            Self::Indices(buffer) | Self::Plain(buffer) => buffer,
+        }
+    }
+}
+
+/// An encoder that builds a dictionary from the values of a column chunk and encodes
+/// every data page as indices into it.
+///
+/// Each call to [`DictEncoder::encode_native`] or [`DictEncoder::encode_binary`] encodes the
+/// (non-null) values of a single data page. Once the dictionary page would exceed its maximum
+/// size, the encoder falls back to [`Encoding::Plain`] for the current and all subsequent pages.
+///
+/// The dictionary page, returned by [`DictEncoder::dict_page`], must be written before
+/// the data pages of the column chunk.
+#[derive(Debug)]
+pub struct DictEncoder {
+    physical_type: PhysicalType,
+    max_dict_size: usize,
+    keys: HashMap<Vec<u8>, u32>,
+    // the dictionary, encoded as PLAIN
+    dict: Vec<u8>,
+    num_dict_values: usize,
+    has_indices: bool,
+    fallback: bool,
+}
+
+impl DictEncoder {
+    /// Returns a new [`DictEncoder`] of values of `physical_type` whose dictionary page
+    /// is at most `max_dict_size` bytes.
+    /// # Error
+    /// Errors iff `physical_type` is [`PhysicalType::Boolean`], which does not support dictionary encoding.
+    pub fn try_new(physical_type: PhysicalType, max_dict_size: usize) -> Result<Self> {
+        if physical_type == PhysicalType::Boolean {
+            return Err(Error::InvalidParameter(
+                "Boolean columns cannot be dictionary-encoded".to_string(),
+            ));
+        }
+        Ok(Self {
+            physical_type,
+            max_dict_size,
+            keys: HashMap::new(),
+            dict: vec![],
+            num_dict_values: 0,
+            has_indices: false,
+            fallback: false,
+        })
+    }
+
+    /// Whether the encoder has fallen back to [`Encoding::Plain`].
+    pub fn is_fallback(&self) -> bool {
+        self.fallback
+    }
+
+    /// The number of values in the dictionary
+    pub fn num_dict_values(&self) -> usize {
+        self.num_dict_values
+    }
+
+    /// Encodes the values of a data page of a native physical type.
+    /// # Error
+    /// Errors iff `T` does not correspond to the physical type of this encoder.
+    pub fn encode_native<T: NativeType>(&mut self, values: &[T]) -> Result<EncodedValues> {
+        if T::TYPE != self.physical_type {
+            return Err(Error::InvalidParameter(format!(
+                "Cannot dictionary-encode values of type {:?} into a column of type {:?}",
+                T::TYPE,
+                self.physical_type
+            )));
+        }
+        self.encode(values.iter().map(|x| x.to_le_bytes()), values.len())
+    }
+
+    /// Encodes the values of a data page of physical type [`PhysicalType::ByteArray`]
+    /// or [`PhysicalType::FixedLenByteArray`].
+    /// # Error
+    /// Errors iff the physical type of this encoder is not binary or if a value's length does
+    /// not match the size of a fixed-length column.
+    pub fn encode_binary<V: AsRef<[u8]>>(&mut self, values: &[V]) -> Result<EncodedValues> {
+        match self.physical_type {
+            PhysicalType::ByteArray => {}
+            PhysicalType::FixedLenByteArray(size) => {
+                if values.iter().any(|x| x.as_ref().len() != size) {
+                    return Err(Error::InvalidParameter(format!(
+                        "All values of a FixedLenByteArray({}) column must have {} bytes",
+                        size, size
+                    )));
+                }
+            }
+            other => {
+                return Err(Error::InvalidParameter(format!(
+                    "Cannot dictionary-encode binary values into a column of type {:?}",
+                    other
+                )))
+            }
+        }
+        self.encode(values.iter(), values.len())
+    }
+
+    fn encode<V: AsRef<[u8]>, I: Iterator<Item = V> + Clone>(
+        &mut self,
+        values: I,
+        length: usize,
+    ) -> Result<EncodedValues> {
+        if !self.fallback {
+            if let Some(indices) = self.try_insert(values.clone(), length)? {
+                self.has_indices = true;
+                return Ok(EncodedValues::Indices(indices));
+            }
+            self.fallback = true;
+            // the keys are no longer needed
+            self.keys = HashMap::new();
+        }
+
+        let mut buffer = vec![];
+        values.for_each(|value| encode_plain(self.physical_type, value.as_ref(), &mut buffer));
+        Ok(EncodedValues::Plain(buffer))
+    }
+
+    /// Inserts `values` in the dictionary and returns the encoded indices, or `None` if the
+    /// dictionary would exceed its maximum size, in which case the dictionary is left unchanged.
+    fn try_insert<V: AsRef<[u8]>, I: Iterator<Item = V>>(
+        &mut self,
+        values: I,
+        length: usize,
+    ) -> Result<Option<Vec<u8>>> {
+        let dict_len = self.dict.len();
+        let mut new_keys = vec![];
+
+        let mut indices = Vec::with_capacity(length);
+        for value in values {
+            let value = value.as_ref();
+            let index = if let Some(index) = self.keys.get(value) {
+                *index
+            } else {
+                let index: u32 = self
+                    .num_dict_values
+                    .try_into()
+                    .map_err(|_| Error::oos("A dictionary can only contain u32::MAX values"))?;
+                encode_plain(self.physical_type, value, &mut self.dict);
+                self.keys.insert(value.to_vec(), index);
+                new_keys.push(value.to_vec());
+                self.num_dict_values += 1;
+                index
+            };
+            indices.push(index);
+
+            if self.dict.len() > self.max_dict_size {
+                // rollback the values of this page
+                self.dict.truncate(dict_len);
+                self.num_dict_values -= new_keys.len();
+                new_keys.iter().for_each(|key| {
+                    self.keys.remove(key);
+                });
+                return Ok(None);
+            }
+        }
+
+        // SPEC: the bit width used to encode the entry ids stored as 1 byte (max bit width = 32),
+        // SPEC: followed by the values encoded using RLE/Bit packed described above (with the given bit width).
+        let num_bits =
+            (32 - (self.num_dict_values.saturating_sub(1) as u32).leading_zeros()).max(1);
+        let mut buffer = vec![num_bits as u8];
+        encode_u32(&mut buffer, indices.into_iter(), num_bits)?;
+        Ok(Some(buffer))
+    }
+
+    /// Returns the [`DictPage`] of this encoder, or `None` if no data page
+    /// was encoded with [`Encoding::RleDictionary`].
+    pub fn dict_page(&self) -> Option<DictPage> {
+        self.has_indices
+            .then(|| DictPage::new(self.dict.clone(), self.num_dict_values, false))
+    }
+
+    /// Consumes this encoder, returning its [`DictPage`], or `None` if no data page
+    /// was encoded with [`Encoding::RleDictionary`].
+    pub fn into_dict_page(self) -> Option<DictPage> {
+        self.has_indices
+            .then(|| DictPage::new(self.dict, self.num_dict_values, false))
+    }
+}
+
+fn encode_plain(physical_type: PhysicalType, value: &[u8], buffer: &mut Vec<u8>) {
+    if physical_type == PhysicalType::ByteArray {
+        plain::encode_binary_value(value, buffer)
+    } else {
+        buffer.extend_from_slice(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hybrid_rle::HybridRleDecoder;
+    use crate::encoding::plain_byte_array::BinaryIter;
+
+    fn decode_indices(buffer: &[u8], length: usize) -> Vec<u32> {
+        HybridRleDecoder::try_new(&buffer[1..], buffer[0] as u32, length)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn native() -> Result<()> {
+        let mut encoder = DictEncoder::try_new(PhysicalType::Int32, DEFAULT_MAX_DICT_SIZE)?;
+
+        let page = encoder.encode_native(&[1i32, 2, 1, 3])?;
+        assert_eq!(page.encoding(), Encoding::RleDictionary);
+        assert_eq!(decode_indices(page.buffer(), 4), vec![0, 1, 0, 2]);
+
+        let page = encoder.encode_native(&[3i32, 4])?;
+        assert_eq!(decode_indices(page.buffer(), 2), vec![2, 3]);
+
+        let dict = encoder.into_dict_page().unwrap();
+        assert_eq!(dict.num_values, 4);
+        let values = dict
+            .buffer
+            .chunks_exact(4)
+            .map(|x| i32::from_le_bytes(x.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![1, 2, 3, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn binary() -> Result<()> {
+        let mut encoder = DictEncoder::try_new(PhysicalType::ByteArray, DEFAULT_MAX_DICT_SIZE)?;
+
+        let page = encoder.encode_binary(&[b"aa".as_ref(), b"b", b"aa"])?;
+        assert_eq!(decode_indices(page.buffer(), 3), vec![0, 1, 0]);
+
+        let dict = encoder.into_dict_page().unwrap();
+        let values =
+            BinaryIter::new(&dict.buffer, Some(dict.num_values)).collect::<Result<Vec<_>>>()?;
+        assert_eq!(values, vec![b"aa".as_ref(), b"b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_len() -> Result<()> {
+        let mut encoder =
+            DictEncoder::try_new(PhysicalType::FixedLenByteArray(2), DEFAULT_MAX_DICT_SIZE)?;
+
+        assert!(encoder.encode_binary(&[b"a"]).is_err());
+
+        let page = encoder.encode_binary(&[b"ab", b"ab", b"cd"])?;
+        assert_eq!(decode_indices(page.buffer(), 3), vec![0, 0, 1]);
+        assert_eq!(encoder.into_dict_page().unwrap().buffer, b"abcd");
+        Ok(())
+    }
+
+    #[test]
+    fn fallback() -> Result<()> {
+        let mut encoder = DictEncoder::try_new(PhysicalType::Int64, 16)?;
+
+        let page = encoder.encode_native(&[1i64, 2, 1])?;
+        assert_eq!(page.encoding(), Encoding::RleDictionary);
+
+        // would require 4 values in the dictionary (32 bytes)
+        let page = encoder.encode_native(&[3i64, 4])?;
+        assert_eq!(page.encoding(), Encoding::Plain);
+        assert!(encoder.is_fallback());
+        assert_eq!(
+            page.into_inner(),
+            [3i64.to_le_bytes(), 4i64.to_le_bytes()].concat()
+        );
+
+        // once fallen back, all pages are plain
+        let page = encoder.encode_native(&[1i64])?;
+        assert_eq!(page.encoding(), Encoding::Plain);
+
+        let dict = encoder.into_dict_page().unwrap();
+        assert_eq!(dict.num_values, 2);
+        assert_eq!(dict.buffer.len(), 16);
+        Ok(())
+    }
+
+    #[test]
+    fn fallback_on_first_page() -> Result<()> {
+        let mut encoder = DictEncoder::try_new(PhysicalType::Int32, 4)?;
+
+        let page = encoder.encode_native(&[1i32, 2])?;
+        assert_eq!(page.encoding(), Encoding::Plain);
+        assert!(encoder.into_dict_page().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(DictEncoder::try_new(PhysicalType::Boolean, 10).is_err());
+
+        let mut encoder = DictEncoder::try_new(PhysicalType::Int32, 10).unwrap();
+        assert!(encoder.encode_native(&[1i64]).is_err());
+        assert!(encoder.encode_binary(&[b"a"]).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! The encryptors of the write path. Without the `encryption` feature they are uninhabited,
+//! so that the write path is the same with and without it.
+#[cfg(feature = "encryption")]
+pub(crate) use crate::encryption::{ColumnEncryptor, FileEncryptor};
+
+use crate::error::Result;
+use crate::metadata::ColumnDescriptor;
+
+#[cfg(not(feature = "encryption"))]
+pub(crate) use disabled::{ColumnEncryptor, FileEncryptor};
+
+/// Returns the [`ColumnEncryptor`] of the `column`th column of the `row_group`th row group of
+/// a file encrypted by `encryptor`, or `None` if the file or the column is not encrypted.
+pub(super) fn column_encryptor(
+    encryptor: Option<&FileEncryptor>,
+    row_group: usize,
+    column: usize,
+    descriptor: &ColumnDescriptor,
+) -> Result<Option<ColumnEncryptor>> {
+    encryptor
+        .map(|encryptor| encryptor.column_encryptor(row_group, column, descriptor))
+        .transpose()
+        .map(Option::flatten)
+}
+
+#[cfg(not(feature = "encryption"))]
+mod disabled {
+    use std::io::Write;
+
+    use parquet_format_safe::{ColumnCryptoMetaData, RowGroup};
+
+    use crate::error::Result;
+    use crate::metadata::{ColumnDescriptor, ThriftFileMetaData};
+
+    pub(crate) enum FileEncryptor {}
+
+    impl FileEncryptor {
+        pub(crate) fn column_encryptor(
+            &self,
+            _: usize,
+            _: usize,
+            _: &ColumnDescriptor,
+        ) -> Result<Option<ColumnEncryptor>> {
+            match *self {}
+        }
+
+        pub(crate) fn encrypt_column_metadata(&self, _: &mut [RowGroup]) -> Result<()> {
+            match *self {}
+        }
+
+        pub(crate) fn write_footer<W: Write>(
+            &self,
+            _: &mut W,
+            _: &mut ThriftFileMetaData,
+        ) -> Result<u64> {
+            match *self {}
+        }
+    }
+
+    pub(crate) enum ColumnEncryptor {}
+
+    impl ColumnEncryptor {
+        pub(crate) fn crypto_metadata(&self) -> ColumnCryptoMetaData {
+            match *self {}
+        }
+
+        pub(crate) fn encrypt_page(&self, _: bool, _: &[u8]) -> Result<Vec<u8>> {
+            match *self {}
+        }
+
+        pub(crate) fn encrypt_page_header(&mut self, _: bool, _: &[u8]) -> Result<Vec<u8>> {
+            match *self {}
+        }
+
+        pub(crate) fn encrypt_column_index(&self, _: &[u8]) -> Result<Vec<u8>> {
+            match *self {}
+        }
+
+        pub(crate) fn encrypt_offset_index(&self, _: &[u8]) -> Result<Vec<u8>> {
+            match *self {}
+        }
+
+        pub(crate) fn encrypt_bloom_filter(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>> {
+            match *self {}
+        }
+    }
+}
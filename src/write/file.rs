@@ -1,18 +1,23 @@
 use std::io::Write;
 
 use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
-use parquet_format_safe::RowGroup;
+use parquet_format_safe::{ColumnOrder, RowGroup, TypeDefinedOrder};
 
 use crate::metadata::ThriftFileMetaData;
 use crate::{
     error::{Error, Result},
-    metadata::SchemaDescriptor,
+    metadata::{SchemaDescriptor, SortingColumn},
     FOOTER_SIZE, PARQUET_MAGIC,
 };
 
+use super::bloom_filter::{set_bloom_filter, write_bloom_filter};
+use super::encryption::{column_encryptor, FileEncryptor};
 use super::indexes::{write_column_index, write_offset_index};
 use super::page::PageWriteSpec;
-use super::{row_group::write_row_group, RowGroupIter, WriteOptions};
+use super::row_group::{
+    build_row_group, check_sorted, check_sorting_columns, set_sorting_columns, write_row_group,
+};
+use super::{EncodedColumnChunk, RowGroupIter, WriteOptions};
 
 pub use crate::metadata::KeyValue;
 use crate::write::State;
@@ -22,6 +27,12 @@ pub(super) fn start_file<W: Write>(writer: &mut W) -> Result<u64> {
     Ok(PARQUET_MAGIC.len() as u64)
 }
 
+/// The column orders of the columns of `schema`. Statistics are written in the order defined
+/// by the type of each column (see [`crate::metadata::get_sort_order`]).
+pub(super) fn column_orders(schema: &SchemaDescriptor) -> Vec<ColumnOrder> {
+    vec![ColumnOrder::TYPEORDER(TypeDefinedOrder {}); schema.columns().len()]
+}
+
 pub(super) fn end_file<W: Write>(mut writer: &mut W, metadata: &ThriftFileMetaData) -> Result<u64> {
     // Write metadata
     let mut protocol = TCompactOutputProtocol::new(&mut writer);
@@ -52,6 +63,13 @@ pub struct FileWriter<W: Write> {
     offset: u64,
     row_groups: Vec<RowGroup>,
     page_specs: Vec<Vec<Vec<PageWriteSpec>>>,
+    // the bitsets of the bloom filters of each column of each row group
+    bloom_filters: Vec<Vec<Option<Vec<u8>>>>,
+    encryptor: Option<FileEncryptor>,
+    // the sorting columns of the row groups that are written
+    sorting_columns: Option<Vec<SortingColumn>>,
+    // whether the pages of the row groups are verified to be sorted as declared
+    verify_sorting_columns: bool,
     /// Used to store the current state for writing the file
     state: State,
     // when the file is written, metadata becomes available
@@ -111,11 +129,36 @@ impl<W: Write> FileWriter<W> {
             offset: 0,
             row_groups: vec![],
             page_specs: vec![],
+            bloom_filters: vec![],
+            encryptor: None,
+            sorting_columns: None,
+            verify_sorting_columns: false,
             state: State::Initialised,
             metadata: None,
         }
     }
 
+    /// Encrypts the file according to `properties`.
+    ///
+    /// Column indexes, offset indexes and bloom filters of encrypted columns are encrypted
+    /// with their column's key.
+    /// # Errors
+    /// Errors iff data has already been written to the file or `properties` are invalid
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn with_encryption(
+        mut self,
+        properties: crate::encryption::FileEncryptionProperties,
+    ) -> Result<Self> {
+        if self.offset != 0 {
+            return Err(Error::InvalidParameter(
+                "Encryption must be set before writing to the file".to_string(),
+            ));
+        }
+        self.encryptor = Some(FileEncryptor::try_new(properties, &self.schema)?);
+        Ok(self)
+    }
+
     /// Writes the header of the file.
     ///
     /// This is automatically called by [`Self::write`] if not called following [`Self::new`].
@@ -142,6 +185,7 @@ impl<W: Write> FileWriter<W> {
         Error: From<E>,
         E: std::error::Error,
     {
+        self.state.check_not_errored()?;
         if self.offset == 0 {
             self.start()?;
         }
@@ -152,16 +196,143 @@ impl<W: Write> FileWriter<W> {
             self.schema.columns(),
             row_group,
             ordinal,
+            &self.options,
+            self.encryptor.as_ref(),
         )?;
         self.offset += size;
+        // the pages are already written: the file can no longer be completed without them
+        if let Err(error) = self.check_sorted(&specs) {
+            self.state = State::Errored;
+            return Err(error);
+        }
+        self.push_row_group(group, specs);
+        Ok(())
+    }
+
+    /// Writes a row group whose column chunks were serialized with
+    /// [`crate::write::encode_column_chunk`], one per column of the schema and in its order.
+    /// They should be encoded with the same [`WriteOptions`] as the file.
+    ///
+    /// This call is IO-bounded
+    /// # Errors
+    /// Errors iff the column chunks do not match the columns of the schema or the file is
+    /// encrypted, since encrypted column chunks depend on their position in the file.
+    pub fn write_encoded(&mut self, columns: Vec<EncodedColumnChunk>) -> Result<()> {
+        self.state.check_not_errored()?;
+        if self.encryptor.is_some() {
+            return Err(Error::FeatureNotSupported(
+                "Writing encoded column chunks to an encrypted file".to_string(),
+            ));
+        }
+        let descriptors = self.schema.columns();
+        if columns.len() != descriptors.len() {
+            return Err(Error::InvalidParameter(format!(
+                "The schema has {} columns but there are {} column chunks",
+                descriptors.len(),
+                columns.len()
+            )));
+        }
+        let is_valid = columns.iter().zip(descriptors).all(|(column, descriptor)| {
+            column
+                .column_chunk()
+                .meta_data
+                .as_ref()
+                .map(|metadata| metadata.path_in_schema == descriptor.path_in_schema)
+                .unwrap_or(false)
+        });
+        if !is_valid {
+            return Err(Error::InvalidParameter(
+                "The column chunks must be in the order of the columns of the schema".to_string(),
+            ));
+        }
+        let specs = columns
+            .iter()
+            .map(|column| column.page_specs())
+            .collect::<Vec<_>>();
+        self.check_sorted(&specs)?;
+
+        if self.offset == 0 {
+            self.start()?;
+        }
+        let columns = columns
+            .into_iter()
+            .map(|column| {
+                let (column, specs, size) = column.write(&mut self.writer, self.offset)?;
+                self.offset += size;
+                Ok((column, specs))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (group, specs) = build_row_group(columns, self.row_groups.len())?;
+        self.push_row_group(group, specs);
+        Ok(())
+    }
+
+    /// Checks that the pages `specs` of a row group are sorted as declared, if verified
+    fn check_sorted<S: AsRef<[PageWriteSpec]>>(&self, specs: &[S]) -> Result<()> {
+        match &self.sorting_columns {
+            Some(sorting_columns) if self.verify_sorting_columns => {
+                check_sorted(specs, self.schema.columns(), sorting_columns)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn push_row_group(&mut self, mut group: RowGroup, specs: Vec<Vec<PageWriteSpec>>) {
+        if let Some(sorting_columns) = &self.sorting_columns {
+            set_sorting_columns(&mut group, sorting_columns);
+        }
         self.row_groups.push(group);
         self.page_specs.push(specs);
+        self.bloom_filters
+            .push(vec![None; self.schema.columns().len()]);
+    }
+
+    /// Declares the columns by which the rows of the row groups written after this call are
+    /// sorted, e.g. once for all row groups of the file or before writing each row group.
+    ///
+    /// The rows are not sorted by the writer. When `verify`, writing a row group errors if the
+    /// statistics of the pages of its first sorting column disagree with the declaration.
+    /// [`Self::write_encoded`] verifies the column chunks before writing them. [`Self::write`]
+    /// can only verify the pages once they are written, so it then leaves the writer errored:
+    /// every later call to write or end the file errors.
+    /// # Errors
+    /// Errors iff a sorting column is not a column of the schema
+    pub fn set_sorting_columns(
+        &mut self,
+        sorting_columns: Option<Vec<SortingColumn>>,
+        verify: bool,
+    ) -> Result<()> {
+        if let Some(sorting_columns) = &sorting_columns {
+            check_sorting_columns(self.schema.columns(), sorting_columns)?;
+        }
+        self.sorting_columns = sorting_columns;
+        self.verify_sorting_columns = verify;
         Ok(())
     }
 
+    /// Sets the bloom filter of the column `column` of the row group `row_group`, which must
+    /// have already been written. The filter is written before the footer and its offset
+    /// is recorded in the column's metadata.
+    ///
+    /// The `bitset` is usually initialized with [`crate::bloom_filter::optimal_num_bytes`]
+    /// and populated with [`crate::bloom_filter::insert`].
+    /// # Errors
+    /// Errors iff the row group or column does not exist or `bitset` is not a valid split
+    /// block bitset.
+    pub fn set_bloom_filter(
+        &mut self,
+        row_group: usize,
+        column: usize,
+        bitset: Vec<u8>,
+    ) -> Result<()> {
+        set_bloom_filter(&mut self.bloom_filters, row_group, column, bitset)
+    }
+
     /// Writes the footer of the parquet file. Returns the total size of the file and the
     /// underlying writer.
     pub fn end(&mut self, key_value_metadata: Option<Vec<KeyValue>>) -> Result<u64> {
+        self.state.check_not_errored()?;
         if self.offset == 0 {
             self.start()?;
         }
@@ -174,22 +345,77 @@ impl<W: Write> FileWriter<W> {
         // compute file stats
         let num_rows = self.row_groups.iter().map(|group| group.num_rows).sum();
 
+        // write bloom filters
+        self.row_groups
+            .iter_mut()
+            .zip(self.bloom_filters.iter())
+            .enumerate()
+            .try_for_each(|(row_group, (group, bitsets))| {
+                group
+                    .columns
+                    .iter_mut()
+                    .zip(bitsets.iter())
+                    .zip(self.schema.columns())
+                    .enumerate()
+                    .filter_map(|(index, ((column, bitset), descriptor))| {
+                        bitset
+                            .as_ref()
+                            .map(|bitset| (index, column, bitset, descriptor))
+                    })
+                    .try_for_each(|(index, column, bitset, descriptor)| {
+                        let encryptor = column_encryptor(
+                            self.encryptor.as_ref(),
+                            row_group,
+                            index,
+                            descriptor,
+                        )?;
+                        let metadata = column
+                            .meta_data
+                            .as_mut()
+                            .ok_or_else(|| Error::oos("Column chunks must contain metadata"))?;
+                        let length =
+                            write_bloom_filter(&mut self.writer, bitset, encryptor.as_ref())?;
+                        metadata.bloom_filter_offset = Some(self.offset as i64);
+                        metadata.bloom_filter_length = Some(length.try_into()?);
+                        self.offset += length;
+                        Result::Ok(())
+                    })?;
+                Result::Ok(())
+            })?;
+
         if self.options.write_statistics {
             // write column indexes (require page statistics)
             self.row_groups
                 .iter_mut()
                 .zip(self.page_specs.iter())
-                .try_for_each(|(group, pages)| {
-                    group.columns.iter_mut().zip(pages.iter()).try_for_each(
-                        |(column, pages)| {
+                .enumerate()
+                .try_for_each(|(row_group, (group, pages))| {
+                    group
+                        .columns
+                        .iter_mut()
+                        .zip(pages.iter())
+                        .zip(self.schema.columns())
+                        .enumerate()
+                        .try_for_each(|(index, ((column, pages), descriptor))| {
+                            let encryptor = column_encryptor(
+                                self.encryptor.as_ref(),
+                                row_group,
+                                index,
+                                descriptor,
+                            )?;
                             let offset = self.offset;
                             column.column_index_offset = Some(offset as i64);
-                            self.offset += write_column_index(&mut self.writer, pages)?;
+                            self.offset += write_column_index(
+                                &mut self.writer,
+                                pages,
+                                &descriptor.descriptor.primitive_type,
+                                self.options.statistics_truncate_length,
+                                encryptor.as_ref(),
+                            )?;
                             let length = self.offset - offset;
                             column.column_index_length = Some(length as i32);
                             Result::Ok(())
-                        },
-                    )?;
+                        })?;
                     Result::Ok(())
                 })?;
         };
@@ -198,34 +424,50 @@ impl<W: Write> FileWriter<W> {
         self.row_groups
             .iter_mut()
             .zip(self.page_specs.iter())
-            .try_for_each(|(group, pages)| {
+            .enumerate()
+            .try_for_each(|(row_group, (group, pages))| {
                 group
                     .columns
                     .iter_mut()
                     .zip(pages.iter())
-                    .try_for_each(|(column, pages)| {
+                    .zip(self.schema.columns())
+                    .enumerate()
+                    .try_for_each(|(index, ((column, pages), descriptor))| {
+                        let encryptor = column_encryptor(
+                            self.encryptor.as_ref(),
+                            row_group,
+                            index,
+                            descriptor,
+                        )?;
                         let offset = self.offset;
                         column.offset_index_offset = Some(offset as i64);
-                        self.offset += write_offset_index(&mut self.writer, pages)?;
+                        self.offset +=
+                            write_offset_index(&mut self.writer, pages, encryptor.as_ref())?;
                         column.offset_index_length = Some((self.offset - offset) as i32);
                         Result::Ok(())
                     })?;
                 Result::Ok(())
             })?;
 
-        let metadata = ThriftFileMetaData::new(
+        let mut metadata = ThriftFileMetaData::new(
             self.options.version.into(),
             self.schema.clone().into_thrift(),
             num_rows,
             self.row_groups.clone(),
             key_value_metadata,
             self.created_by.clone(),
-            None,
+            Some(column_orders(&self.schema)),
             None,
             None,
         );
 
-        let len = end_file(&mut self.writer, &metadata)?;
+        let len = match &self.encryptor {
+            Some(encryptor) => {
+                encryptor.encrypt_column_metadata(&mut metadata.row_groups)?;
+                encryptor.write_footer(&mut self.writer, &mut metadata)?
+            }
+            None => end_file(&mut self.writer, &metadata)?,
+        };
         self.state = State::Finished;
         self.metadata = Some(metadata);
         Ok(self.offset + len)
@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 use parquet_format_safe::BoundaryOrder;
 use parquet_format_safe::ColumnIndex;
 use parquet_format_safe::OffsetIndex;
@@ -5,11 +7,71 @@ use parquet_format_safe::PageLocation;
 
 use crate::error::{Error, Result};
 pub use crate::metadata::KeyValue;
-use crate::statistics::serialize_statistics;
+use crate::metadata::{get_sort_order, SortOrder};
+use crate::scalar::Scalar;
+use crate::schema::types::PrimitiveType;
 
 use crate::write::page::{is_data_page, PageWriteSpec};
+use crate::write::statistics::serialize_truncated;
+
+/// Whether `lhs` and `rhs` are comparable and `lhs` is not `unexpected` relative to `rhs`
+fn is_expected(lhs: &Scalar, rhs: &Scalar, unexpected: Ordering) -> bool {
+    matches!(lhs.partial_cmp(rhs), Some(ordering) if ordering != unexpected)
+}
 
-pub fn serialize_column_index(pages: &[PageWriteSpec]) -> Result<ColumnIndex> {
+/// The order of the min and max values of the non-null pages of a column of
+/// `primitive_type`, in the column's sort order. Values whose sort order is undefined are
+/// unordered.
+fn boundary_order(
+    min_values: &[Vec<u8>],
+    max_values: &[Vec<u8>],
+    null_pages: &[bool],
+    primitive_type: &PrimitiveType,
+) -> BoundaryOrder {
+    let sort_order = get_sort_order(
+        &primitive_type.logical_type,
+        &primitive_type.converted_type,
+        &primitive_type.physical_type,
+    );
+    if sort_order == SortOrder::Undefined {
+        return BoundaryOrder::UNORDERED;
+    }
+    let bounds = min_values
+        .iter()
+        .zip(max_values)
+        .zip(null_pages)
+        .filter(|(_, is_null)| !**is_null)
+        .map(|((min, max), _)| {
+            Scalar::from_plain(min, primitive_type).zip(Scalar::from_plain(max, primitive_type))
+        })
+        .collect::<Option<Vec<_>>>();
+    let bounds = match bounds {
+        Some(bounds) => bounds,
+        None => return BoundaryOrder::UNORDERED,
+    };
+    // consecutive mins and maxes are never `unexpected`
+    let is_ordered = |unexpected: Ordering| {
+        bounds.windows(2).all(|pair| {
+            is_expected(&pair[0].0, &pair[1].0, unexpected)
+                && is_expected(&pair[0].1, &pair[1].1, unexpected)
+        })
+    };
+    if is_ordered(Ordering::Greater) {
+        BoundaryOrder::ASCENDING
+    } else if is_ordered(Ordering::Less) {
+        BoundaryOrder::DESCENDING
+    } else {
+        BoundaryOrder::UNORDERED
+    }
+}
+
+/// Serializes the statistics of `pages` of a column of `primitive_type` into a column index,
+/// truncating binary min and max values to `truncate_length` bytes
+pub(super) fn serialize_column_index(
+    pages: &[PageWriteSpec],
+    primitive_type: &PrimitiveType,
+    truncate_length: Option<usize>,
+) -> Result<ColumnIndex> {
     let mut null_pages = Vec::with_capacity(pages.len());
     let mut min_values = Vec::with_capacity(pages.len());
     let mut max_values = Vec::with_capacity(pages.len());
@@ -20,7 +82,7 @@ pub fn serialize_column_index(pages: &[PageWriteSpec]) -> Result<ColumnIndex> {
         .filter(|x| is_data_page(x))
         .try_for_each(|spec| {
             if let Some(stats) = &spec.statistics {
-                let stats = serialize_statistics(stats.as_ref());
+                let stats = serialize_truncated(stats.as_ref(), truncate_length);
 
                 let null_count = stats
                     .null_count
@@ -48,16 +110,19 @@ pub fn serialize_column_index(pages: &[PageWriteSpec]) -> Result<ColumnIndex> {
                 ))
             }
         })?;
+    let boundary_order = boundary_order(&min_values, &max_values, &null_pages, primitive_type);
     Ok(ColumnIndex {
         null_pages,
         min_values,
         max_values,
-        boundary_order: BoundaryOrder::UNORDERED,
+        boundary_order,
         null_counts: Some(null_counts),
+        repetition_level_histograms: None,
+        definition_level_histograms: None,
     })
 }
 
-pub fn serialize_offset_index(pages: &[PageWriteSpec]) -> Result<OffsetIndex> {
+pub(super) fn serialize_offset_index(pages: &[PageWriteSpec]) -> Result<OffsetIndex> {
     let mut first_row_index = 0;
     let page_locations = pages
         .iter()
@@ -78,5 +143,8 @@ pub fn serialize_offset_index(pages: &[PageWriteSpec]) -> Result<OffsetIndex> {
         })
         .collect::<Result<Vec<_>>>()?;
 
-    Ok(OffsetIndex { page_locations })
+    Ok(OffsetIndex {
+        page_locations,
+        unencoded_byte_array_data_bytes: None,
+    })
 }
@@ -1,49 +1,100 @@
 use std::io::Write;
 
 #[cfg(feature = "async")]
-use futures::AsyncWrite;
+use futures::{AsyncWrite, AsyncWriteExt};
 #[cfg(feature = "async")]
 use parquet_format_safe::thrift::protocol::TCompactOutputStreamProtocol;
 
 use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
+use parquet_format_safe::{ColumnIndex, OffsetIndex};
 
 use crate::error::Result;
 pub use crate::metadata::KeyValue;
 
+use crate::schema::types::PrimitiveType;
+use crate::write::encryption::ColumnEncryptor;
 use crate::write::page::PageWriteSpec;
 
 use super::serialize::{serialize_column_index, serialize_offset_index};
 
-pub fn write_column_index<W: Write>(writer: &mut W, pages: &[PageWriteSpec]) -> Result<u64> {
-    let index = serialize_column_index(pages)?;
+/// Returns the module of the column index `index` encrypted by `encryptor`
+fn encrypt_column_index(index: &ColumnIndex, encryptor: &ColumnEncryptor) -> Result<Vec<u8>> {
+    let mut serialized = vec![];
+    index.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut serialized))?;
+    encryptor.encrypt_column_index(&serialized)
+}
+
+/// Returns the module of the offset index `index` encrypted by `encryptor`
+fn encrypt_offset_index(index: &OffsetIndex, encryptor: &ColumnEncryptor) -> Result<Vec<u8>> {
+    let mut serialized = vec![];
+    index.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut serialized))?;
+    encryptor.encrypt_offset_index(&serialized)
+}
+
+pub(crate) fn write_column_index<W: Write>(
+    writer: &mut W,
+    pages: &[PageWriteSpec],
+    primitive_type: &PrimitiveType,
+    truncate_length: Option<usize>,
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<u64> {
+    let index = serialize_column_index(pages, primitive_type, truncate_length)?;
+    if let Some(encryptor) = encryptor {
+        let module = encrypt_column_index(&index, encryptor)?;
+        writer.write_all(&module)?;
+        return Ok(module.len() as u64);
+    }
     let mut protocol = TCompactOutputProtocol::new(writer);
     Ok(index.write_to_out_protocol(&mut protocol)? as u64)
 }
 
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
-pub async fn write_column_index_async<W: AsyncWrite + Unpin + Send>(
+pub(crate) async fn write_column_index_async<W: AsyncWrite + Unpin + Send>(
     writer: &mut W,
     pages: &[PageWriteSpec],
+    primitive_type: &PrimitiveType,
+    truncate_length: Option<usize>,
+    encryptor: Option<&ColumnEncryptor>,
 ) -> Result<u64> {
-    let index = serialize_column_index(pages)?;
+    let index = serialize_column_index(pages, primitive_type, truncate_length)?;
+    if let Some(encryptor) = encryptor {
+        let module = encrypt_column_index(&index, encryptor)?;
+        writer.write_all(&module).await?;
+        return Ok(module.len() as u64);
+    }
     let mut protocol = TCompactOutputStreamProtocol::new(writer);
     Ok(index.write_to_out_stream_protocol(&mut protocol).await? as u64)
 }
 
-pub fn write_offset_index<W: Write>(writer: &mut W, pages: &[PageWriteSpec]) -> Result<u64> {
+pub(crate) fn write_offset_index<W: Write>(
+    writer: &mut W,
+    pages: &[PageWriteSpec],
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<u64> {
     let index = serialize_offset_index(pages)?;
+    if let Some(encryptor) = encryptor {
+        let module = encrypt_offset_index(&index, encryptor)?;
+        writer.write_all(&module)?;
+        return Ok(module.len() as u64);
+    }
     let mut protocol = TCompactOutputProtocol::new(&mut *writer);
     Ok(index.write_to_out_protocol(&mut protocol)? as u64)
 }
 
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
-pub async fn write_offset_index_async<W: AsyncWrite + Unpin + Send>(
+pub(crate) async fn write_offset_index_async<W: AsyncWrite + Unpin + Send>(
     writer: &mut W,
     pages: &[PageWriteSpec],
+    encryptor: Option<&ColumnEncryptor>,
 ) -> Result<u64> {
     let index = serialize_offset_index(pages)?;
+    if let Some(encryptor) = encryptor {
+        let module = encrypt_offset_index(&index, encryptor)?;
+        writer.write_all(&module).await?;
+        return Ok(module.len() as u64);
+    }
     let mut protocol = TCompactOutputStreamProtocol::new(&mut *writer);
     Ok(index.write_to_out_stream_protocol(&mut protocol).await? as u64)
 }
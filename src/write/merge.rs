@@ -0,0 +1,348 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use parquet_format_safe::thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol};
+use parquet_format_safe::{BloomFilterHeader, ColumnChunk, OffsetIndex, RowGroup};
+
+use crate::error::{Error, Result};
+use crate::metadata::{ColumnChunkMetaData, FileMetaData, KeyValue, ThriftFileMetaData};
+use crate::read::read_metadata;
+
+use super::file::{column_orders, end_file, start_file};
+use super::ColumnOffsetsMetadata;
+
+/// Copies `length` bytes of `reader` starting at `offset` to `writer`
+fn copy<R: Read + Seek, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    offset: u64,
+    length: u64,
+) -> Result<u64> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let copied = std::io::copy(&mut reader.by_ref().take(length), writer)?;
+    if copied != length {
+        return Err(Error::oos(format!(
+            "The file ended {} bytes before the end of the range {}..{}",
+            length - copied,
+            offset,
+            offset + length
+        )));
+    }
+    Ok(copied)
+}
+
+/// Copies the bloom filter at `offset` of `reader` to `writer`, returning the number of bytes
+/// written
+fn copy_bloom_filter<R: Read + Seek, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    offset: u64,
+) -> Result<u64> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut prot = TCompactInputProtocol::new(&mut *reader, usize::MAX); // max is ok since `BloomFilterHeader` never allocates
+    let header = BloomFilterHeader::read_from_in_protocol(&mut prot)?;
+    let header_size = reader.stream_position()? - offset;
+    let num_bytes: u64 = header.num_bytes.try_into()?;
+    copy(reader, writer, offset, header_size + num_bytes)
+}
+
+/// Copies the offset index at `offset` of `reader` to `writer` with its page locations moved
+/// by `delta`, returning the number of bytes written
+fn copy_offset_index<R: Read + Seek, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    offset: u64,
+    length: usize,
+    delta: i64,
+) -> Result<u64> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut data = vec![];
+    data.try_reserve(length)?;
+    reader.by_ref().take(length as u64).read_to_end(&mut data)?;
+
+    let mut prot = TCompactInputProtocol::new(data.as_slice(), length * 2 + 1024);
+    let mut index = OffsetIndex::read_from_in_protocol(&mut prot)?;
+    index
+        .page_locations
+        .iter_mut()
+        .for_each(|location| location.offset += delta);
+
+    let mut protocol = TCompactOutputProtocol::new(writer);
+    Ok(index.write_to_out_protocol(&mut protocol)? as u64)
+}
+
+/// A column chunk of an input file and its position in the output
+struct Chunk {
+    /// The index of the file of the column chunk
+    file: usize,
+    /// The metadata of the column chunk in the file
+    metadata: ColumnChunkMetaData,
+    /// The difference between the offsets of the column chunk in the output and in the file
+    delta: i64,
+}
+
+impl Chunk {
+    /// The [`ColumnChunk`] of the output, without indexes and bloom filter
+    fn column_chunk(&self, file_offset: i64) -> ColumnChunk {
+        let mut column_chunk = self.metadata.column_chunk().clone();
+        column_chunk.file_path = None;
+        column_chunk.file_offset = file_offset;
+        column_chunk.offset_index_offset = None;
+        column_chunk.offset_index_length = None;
+        column_chunk.column_index_offset = None;
+        column_chunk.column_index_length = None;
+        if let Some(metadata) = column_chunk.meta_data.as_mut() {
+            metadata.data_page_offset += self.delta;
+            if let Some(offset) = metadata.dictionary_page_offset.as_mut() {
+                *offset += self.delta;
+            }
+            if let Some(offset) = metadata.index_page_offset.as_mut() {
+                *offset += self.delta;
+            }
+            metadata.bloom_filter_offset = None;
+            metadata.bloom_filter_length = None;
+        }
+        column_chunk
+    }
+}
+
+/// Returns the union of the key-value metadata of `metadatas`. When a key is repeated,
+/// its first value is kept.
+fn merge_key_value_metadata(metadatas: &[&FileMetaData]) -> Option<Vec<KeyValue>> {
+    let mut result: Option<Vec<KeyValue>> = None;
+    metadatas
+        .iter()
+        .filter_map(|metadata| metadata.key_value_metadata.as_ref())
+        .flatten()
+        .for_each(|key_value| {
+            let result = result.get_or_insert_with(Vec::new);
+            if !result.iter().any(|x| x.key == key_value.key) {
+                result.push(key_value.clone());
+            }
+        });
+    result
+}
+
+/// Concatenates the parquet files of `readers` into `writer`, returning the total size of
+/// the written file.
+///
+/// The row groups are copied byte for byte with their sorting columns, without decoding their
+/// pages, followed by their bloom filters, column indexes and offset indexes, whose offsets
+/// are updated to their new position. The key-value metadata of all files is merged; when a
+/// key is repeated, the value of the first file is kept. The column orders are only written
+/// when all files have them, since the statistics of their column chunks are copied.
+/// # Errors
+/// Errors iff there are no files, their schemas are not equal, a file is encrypted or
+/// cannot be read.
+pub fn merge<R: Read + Seek, W: Write>(
+    readers: &mut [R],
+    writer: &mut W,
+    created_by: Option<String>,
+) -> Result<u64> {
+    let metadatas = readers
+        .iter_mut()
+        .map(read_metadata)
+        .collect::<Result<Vec<_>>>()?;
+    let metadatas = metadatas.iter().collect::<Vec<_>>();
+    let selection = metadatas
+        .iter()
+        .enumerate()
+        .flat_map(|(file, metadata)| (0..metadata.row_groups.len()).map(move |i| (file, i)))
+        .collect::<Vec<_>>();
+    copy_row_groups(
+        readers,
+        &metadatas,
+        &selection,
+        writer,
+        merge_key_value_metadata(&metadatas),
+        created_by,
+    )
+}
+
+/// Writes a file with the row groups of `selection`, pairs of the index of a file of
+/// `readers` and of one of its row groups, copying their column chunks, bloom filters and
+/// indexes. `metadatas` has the metadata of each file of `readers`.
+/// Returns the total size of the written file.
+/// # Errors
+/// Errors iff there are no files, their schemas are not equal, a file is encrypted, a row
+/// group does not exist or a file cannot be read.
+pub(super) fn copy_row_groups<R: Read + Seek, W: Write>(
+    readers: &mut [R],
+    metadatas: &[&FileMetaData],
+    selection: &[(usize, usize)],
+    writer: &mut W,
+    key_value_metadata: Option<Vec<KeyValue>>,
+    created_by: Option<String>,
+) -> Result<u64> {
+    let schema = metadatas
+        .first()
+        .ok_or_else(|| Error::InvalidParameter("There must be at least one file".to_string()))?
+        .schema();
+    if metadatas
+        .iter()
+        .any(|metadata| metadata.schema().fields() != schema.fields())
+    {
+        return Err(Error::InvalidParameter(
+            "All files must have the same schema".to_string(),
+        ));
+    }
+    let is_encrypted = metadatas.iter().any(|metadata| {
+        metadata.encryption_algorithm.is_some()
+            || metadata.row_groups.iter().any(|row_group| {
+                row_group
+                    .columns()
+                    .iter()
+                    .any(|column| column.column_chunk().crypto_metadata.is_some())
+            })
+    });
+    if is_encrypted {
+        return Err(Error::FeatureNotSupported(
+            "Copying the row groups of encrypted files".to_string(),
+        ));
+    }
+    let selected = selection
+        .iter()
+        .map(|&(file, row_group)| {
+            metadatas
+                .get(file)
+                .and_then(|metadata| metadata.row_groups.get(row_group))
+                .ok_or_else(|| {
+                    Error::InvalidParameter(format!(
+                        "There is no row group {} in file {}",
+                        row_group, file
+                    ))
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut offset = start_file(writer)?;
+
+    // copy the column chunks, each followed by its metadata
+    let mut row_groups = vec![];
+    let mut chunks: Vec<Vec<Chunk>> = vec![];
+    for (&(file, _), row_group) in selection.iter().zip(selected) {
+        let reader = &mut readers[file];
+        let mut columns = vec![];
+        let mut group = vec![];
+        for column in row_group.columns() {
+            let (start, length) = column.byte_range();
+            let chunk = Chunk {
+                file,
+                metadata: column.clone(),
+                delta: offset as i64 - start as i64,
+            };
+            offset += copy(reader, writer, start, length)?;
+
+            let column_chunk = chunk.column_chunk(offset as i64);
+            let mut protocol = TCompactOutputProtocol::new(&mut *writer);
+            offset += column_chunk
+                .meta_data
+                .as_ref()
+                .unwrap()
+                .write_to_out_protocol(&mut protocol)? as u64;
+
+            columns.push(column_chunk);
+            group.push(chunk);
+        }
+
+        let file_offset = columns.first().and_then(|column_chunk| {
+            ColumnOffsetsMetadata::from_column_chunk(column_chunk).calc_row_group_file_offset()
+        });
+        let total_compressed_size = columns
+            .iter()
+            .map(|column| column.meta_data.as_ref().unwrap().total_compressed_size)
+            .sum();
+        row_groups.push(RowGroup {
+            columns,
+            total_byte_size: row_group.total_byte_size() as i64,
+            num_rows: row_group.num_rows() as i64,
+            sorting_columns: row_group.sorting_columns().map(|sorting_columns| {
+                sorting_columns
+                    .iter()
+                    .map(|column| column.into_thrift())
+                    .collect()
+            }),
+            file_offset,
+            total_compressed_size: Some(total_compressed_size),
+            ordinal: row_groups.len().try_into().ok(),
+        });
+        chunks.push(group);
+    }
+
+    // copy the bloom filters
+    for (row_group, group) in row_groups.iter_mut().zip(chunks.iter()) {
+        for (column, chunk) in row_group.columns.iter_mut().zip(group.iter()) {
+            if let Some(bloom_filter_offset) = chunk.metadata.metadata().bloom_filter_offset {
+                let reader = &mut readers[chunk.file];
+                let length = copy_bloom_filter(reader, writer, bloom_filter_offset.try_into()?)?;
+                let metadata = column.meta_data.as_mut().unwrap();
+                metadata.bloom_filter_offset = Some(offset as i64);
+                metadata.bloom_filter_length = Some(length.try_into()?);
+                offset += length;
+            }
+        }
+    }
+
+    // copy the column indexes, which contain no offsets
+    for (row_group, group) in row_groups.iter_mut().zip(chunks.iter()) {
+        for (column, chunk) in row_group.columns.iter_mut().zip(group.iter()) {
+            let column_chunk = chunk.metadata.column_chunk();
+            if let (Some(index_offset), Some(length)) = (
+                column_chunk.column_index_offset,
+                column_chunk.column_index_length,
+            ) {
+                let reader = &mut readers[chunk.file];
+                column.column_index_offset = Some(offset as i64);
+                column.column_index_length = Some(length);
+                offset += copy(reader, writer, index_offset.try_into()?, length.try_into()?)?;
+            }
+        }
+    }
+
+    // copy the offset indexes, whose page locations are moved
+    for (row_group, group) in row_groups.iter_mut().zip(chunks.iter()) {
+        for (column, chunk) in row_group.columns.iter_mut().zip(group.iter()) {
+            let column_chunk = chunk.metadata.column_chunk();
+            if let (Some(index_offset), Some(length)) = (
+                column_chunk.offset_index_offset,
+                column_chunk.offset_index_length,
+            ) {
+                let reader = &mut readers[chunk.file];
+                let written = copy_offset_index(
+                    reader,
+                    writer,
+                    index_offset.try_into()?,
+                    length.try_into()?,
+                    chunk.delta,
+                )?;
+                column.offset_index_offset = Some(offset as i64);
+                column.offset_index_length = Some(written.try_into()?);
+                offset += written;
+            }
+        }
+    }
+
+    let num_rows = row_groups.iter().map(|row_group| row_group.num_rows).sum();
+    let version = metadatas
+        .iter()
+        .map(|metadata| metadata.version)
+        .max()
+        .unwrap();
+    // the statistics are copied and thus only follow the type-defined order if all files do
+    let column_orders = metadatas
+        .iter()
+        .all(|metadata| metadata.column_orders.is_some())
+        .then(|| column_orders(schema));
+    let metadata = ThriftFileMetaData::new(
+        version,
+        schema.clone().into_thrift(),
+        num_rows,
+        row_groups,
+        key_value_metadata,
+        created_by,
+        column_orders,
+        None,
+        None,
+    );
+
+    Ok(offset + end_file(writer, &metadata)?)
+}
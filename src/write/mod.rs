@@ -1,9 +1,16 @@
+mod bloom_filter;
 mod column_chunk;
+mod column_writer;
 mod compression;
+mod dictionary;
+mod encryption;
 mod file;
 mod indexes;
+mod merge;
 pub(crate) mod page;
 mod row_group;
+mod rows;
+mod split;
 pub(self) mod statistics;
 
 #[cfg(feature = "async")]
@@ -15,12 +22,28 @@ pub use stream::FileStreamer;
 mod dyn_iter;
 pub use dyn_iter::{DynIter, DynStreamingIterator};
 
+pub use column_chunk::{encode_column_chunk, EncodedColumnChunk};
+
+pub use column_writer::{ColumnValues, ColumnWriter, ColumnWriterOptions};
+
 pub use compression::{compress, Compressor};
 
+pub use dictionary::{DictEncoder, EncodedValues, DEFAULT_MAX_DICT_SIZE};
+
 pub use file::{write_metadata_sidecar, FileWriter};
 
+pub use merge::merge;
+
+pub use split::{extract_row_groups, split_row_groups};
+
+pub use page::PageWriteSpec;
+
 pub use row_group::ColumnOffsetsMetadata;
 
+#[cfg(feature = "bloom_filter")]
+pub(crate) use rows::decimal_to_bytes;
+pub use rows::{RowWriter, RowWriterOptions};
+
 use crate::page::CompressedPage;
 
 pub type RowGroupIter<'a, E> =
@@ -33,6 +56,29 @@ pub struct WriteOptions {
     pub write_statistics: bool,
     /// Which Parquet version to use
     pub version: Version,
+    /// Whether to write the CRC32 checksum of each page in its header
+    pub write_page_crc: bool,
+    /// The maximum number of bytes of the binary min and max values of the statistics of
+    /// column chunks, of page headers and of column indexes, or `None` to write them in full.
+    ///
+    /// Truncation only applies to lexicographically ordered `BYTE_ARRAY` and
+    /// `FIXED_LEN_BYTE_ARRAY` columns: the min is truncated down and the max is truncated and
+    /// incremented up (or kept in full when it cannot be incremented), so that both remain
+    /// bounds of the values. UTF-8 values are truncated at character boundaries. The
+    /// statistics of column chunks and page headers declare whether their min and max are
+    /// exact via `is_min_value_exact` and `is_max_value_exact`.
+    pub statistics_truncate_length: Option<usize>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            write_statistics: true,
+            version: Version::V1,
+            write_page_crc: false,
+            statistics_truncate_length: None,
+        }
+    }
 }
 
 /// The parquet version to use
@@ -48,6 +94,22 @@ enum State {
     Initialised,
     Started,
     Finished,
+    /// The pages of a row group were written but the row group was rejected, so the file
+    /// cannot be completed
+    Errored,
+}
+
+impl State {
+    /// Errors iff the writer is [`State::Errored`]
+    fn check_not_errored(&self) -> crate::error::Result<()> {
+        if *self == State::Errored {
+            return Err(crate::error::Error::InvalidParameter(
+                "The writer errored when a row group was rejected after its pages were written"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl From<Version> for i32 {
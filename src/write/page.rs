@@ -15,7 +15,11 @@ use crate::error::{Error, Result};
 use crate::page::{
     CompressedDataPage, CompressedDictPage, CompressedPage, DataPageHeader, ParquetPageHeader,
 };
-use crate::statistics::Statistics;
+use crate::statistics::{ParquetStatistics, Statistics};
+
+use super::encryption::ColumnEncryptor;
+use super::statistics::serialize_truncated;
+use super::WriteOptions;
 
 pub(crate) fn is_data_page(page: &PageWriteSpec) -> bool {
     page.header.type_ == PageType::DATA_PAGE || page.header.type_ == PageType::DATA_PAGE_V2
@@ -40,6 +44,7 @@ fn maybe_bytes(uncompressed: usize, compressed: usize) -> Result<(i32, i32)> {
 }
 
 /// Contains page write metrics.
+#[derive(Debug)]
 pub struct PageWriteSpec {
     pub header: ParquetPageHeader,
     pub num_values: usize,
@@ -55,27 +60,37 @@ pub fn write_page<W: Write>(
     writer: &mut W,
     offset: u64,
     compressed_page: &CompressedPage,
+    options: &WriteOptions,
+    encryptor: Option<&mut ColumnEncryptor>,
 ) -> Result<PageWriteSpec> {
     let num_values = compressed_page.num_values();
     let selected_rows = compressed_page.selected_rows();
+    let is_dictionary = matches!(compressed_page, CompressedPage::Dict(_));
 
-    let header = match &compressed_page {
-        CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
-        CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
-    }?;
-
-    let header_size = write_page_header(writer, &header)?;
-    let mut bytes_written = header_size;
-
-    bytes_written += match &compressed_page {
+    let mut header = match &compressed_page {
         CompressedPage::Data(compressed_page) => {
-            writer.write_all(&compressed_page.buffer)?;
-            compressed_page.buffer.len() as u64
+            assemble_data_page_header(compressed_page, options)
         }
         CompressedPage::Dict(compressed_page) => {
-            writer.write_all(&compressed_page.buffer)?;
-            compressed_page.buffer.len() as u64
+            assemble_dict_page_header(compressed_page, options.write_page_crc)
         }
+    }?;
+
+    let buffer = match &compressed_page {
+        CompressedPage::Data(compressed_page) => &compressed_page.buffer,
+        CompressedPage::Dict(compressed_page) => &compressed_page.buffer,
+    };
+
+    let (header_size, bytes_written) = if let Some(encryptor) = encryptor {
+        let (serialized, buffer) =
+            encrypt_page(&mut header, buffer, is_dictionary, options, encryptor)?;
+        writer.write_all(&serialized)?;
+        writer.write_all(&buffer)?;
+        (serialized.len() as u64, buffer.len() as u64)
+    } else {
+        let header_size = write_page_header(writer, &header)?;
+        writer.write_all(buffer)?;
+        (header_size, buffer.len() as u64)
     };
 
     let statistics = match &compressed_page {
@@ -87,7 +102,7 @@ pub fn write_page<W: Write>(
         header,
         header_size,
         offset,
-        bytes_written,
+        bytes_written: header_size + bytes_written,
         compression: compressed_page.compression(),
         statistics,
         num_rows: selected_rows.map(|x| x.last().unwrap().length),
@@ -101,27 +116,37 @@ pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
     writer: &mut W,
     offset: u64,
     compressed_page: &CompressedPage,
+    options: &WriteOptions,
+    encryptor: Option<&mut ColumnEncryptor>,
 ) -> Result<PageWriteSpec> {
     let num_values = compressed_page.num_values();
     let selected_rows = compressed_page.selected_rows();
+    let is_dictionary = matches!(compressed_page, CompressedPage::Dict(_));
 
-    let header = match &compressed_page {
-        CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
-        CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
-    }?;
-
-    let header_size = write_page_header_async(writer, &header).await?;
-    let mut bytes_written = header_size as u64;
-
-    bytes_written += match &compressed_page {
+    let mut header = match &compressed_page {
         CompressedPage::Data(compressed_page) => {
-            writer.write_all(&compressed_page.buffer).await?;
-            compressed_page.buffer.len() as u64
+            assemble_data_page_header(compressed_page, options)
         }
         CompressedPage::Dict(compressed_page) => {
-            writer.write_all(&compressed_page.buffer).await?;
-            compressed_page.buffer.len() as u64
+            assemble_dict_page_header(compressed_page, options.write_page_crc)
         }
+    }?;
+
+    let buffer = match &compressed_page {
+        CompressedPage::Data(compressed_page) => &compressed_page.buffer,
+        CompressedPage::Dict(compressed_page) => &compressed_page.buffer,
+    };
+
+    let (header_size, bytes_written) = if let Some(encryptor) = encryptor {
+        let (serialized, buffer) =
+            encrypt_page(&mut header, buffer, is_dictionary, options, encryptor)?;
+        writer.write_all(&serialized).await?;
+        writer.write_all(&buffer).await?;
+        (serialized.len() as u64, buffer.len() as u64)
+    } else {
+        let header_size = write_page_header_async(writer, &header).await?;
+        writer.write_all(buffer).await?;
+        (header_size, buffer.len() as u64)
     };
 
     let statistics = match &compressed_page {
@@ -133,7 +158,7 @@ pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
         header,
         header_size,
         offset,
-        bytes_written,
+        bytes_written: header_size + bytes_written,
         compression: compressed_page.compression(),
         statistics,
         num_rows: selected_rows.map(|x| x.last().unwrap().length),
@@ -141,7 +166,54 @@ pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
     })
 }
 
-fn assemble_data_page_header(page: &CompressedDataPage) -> Result<ParquetPageHeader> {
+/// Encrypts the page `buffer` and its `header`, which is updated to declare the encrypted
+/// page. Returns the encrypted header and page to write.
+fn encrypt_page(
+    header: &mut ParquetPageHeader,
+    buffer: &[u8],
+    is_dictionary: bool,
+    options: &WriteOptions,
+    encryptor: &mut ColumnEncryptor,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let buffer = encryptor.encrypt_page(is_dictionary, buffer)?;
+    // SPEC: the header of an encrypted page declares the size and checksum of the encrypted page
+    header.compressed_page_size = maybe_bytes(0, buffer.len())?.1;
+    if options.write_page_crc {
+        header.crc = Some(compute_crc(&buffer));
+    }
+
+    let mut serialized = vec![];
+    write_page_header(&mut serialized, header)?;
+    let serialized = encryptor.encrypt_page_header(is_dictionary, &serialized)?;
+    Ok((serialized, buffer))
+}
+
+/// Returns the CRC32 checksum of the (compressed) page data, as declared in the page header.
+fn compute_crc(buffer: &[u8]) -> i32 {
+    // SPEC: the CRC is computed on the serialization binary representation of the page
+    // SPEC: (as written to disk), excluding the page header.
+    crc32fast::hash(buffer) as i32
+}
+
+/// The statistics `header_statistics` of the header of `page`, with their binary min and max
+/// truncated (see [`WriteOptions::statistics_truncate_length`]).
+fn truncate_statistics(
+    page: &CompressedDataPage,
+    header_statistics: &Option<ParquetStatistics>,
+    options: &WriteOptions,
+) -> Result<Option<ParquetStatistics>> {
+    if options.statistics_truncate_length.is_none() {
+        return Ok(header_statistics.clone());
+    }
+    Ok(page.statistics().transpose()?.map(|statistics| {
+        serialize_truncated(statistics.as_ref(), options.statistics_truncate_length)
+    }))
+}
+
+fn assemble_data_page_header(
+    page: &CompressedDataPage,
+    options: &WriteOptions,
+) -> Result<ParquetPageHeader> {
     let (uncompressed_page_size, compressed_page_size) =
         maybe_bytes(page.uncompressed_size(), page.compressed_size())?;
 
@@ -152,7 +224,7 @@ fn assemble_data_page_header(page: &CompressedDataPage) -> Result<ParquetPageHea
         },
         uncompressed_page_size,
         compressed_page_size,
-        crc: None,
+        crc: options.write_page_crc.then(|| compute_crc(&page.buffer)),
         data_page_header: None,
         index_page_header: None,
         dictionary_page_header: None,
@@ -161,16 +233,23 @@ fn assemble_data_page_header(page: &CompressedDataPage) -> Result<ParquetPageHea
 
     match page.header() {
         DataPageHeader::V1(header) => {
-            page_header.data_page_header = Some(header.clone());
+            let mut header = header.clone();
+            header.statistics = truncate_statistics(page, &header.statistics, options)?;
+            page_header.data_page_header = Some(header);
         }
         DataPageHeader::V2(header) => {
-            page_header.data_page_header_v2 = Some(header.clone());
+            let mut header = header.clone();
+            header.statistics = truncate_statistics(page, &header.statistics, options)?;
+            page_header.data_page_header_v2 = Some(header);
         }
     }
     Ok(page_header)
 }
 
-fn assemble_dict_page_header(page: &CompressedDictPage) -> Result<ParquetPageHeader> {
+fn assemble_dict_page_header(
+    page: &CompressedDictPage,
+    write_crc: bool,
+) -> Result<ParquetPageHeader> {
     let (uncompressed_page_size, compressed_page_size) =
         maybe_bytes(page.uncompressed_page_size, page.buffer.len())?;
 
@@ -185,7 +264,7 @@ fn assemble_dict_page_header(page: &CompressedDictPage) -> Result<ParquetPageHea
         type_: PageType::DICTIONARY_PAGE,
         uncompressed_page_size,
         compressed_page_size,
-        crc: None,
+        crc: write_crc.then(|| compute_crc(&page.buffer)),
         data_page_header: None,
         index_page_header: None,
         dictionary_page_header: Some(DictionaryPageHeader {
@@ -227,7 +306,7 @@ mod tests {
             100,
             false,
         );
-        assert!(assemble_dict_page_header(&page).is_err());
+        assert!(assemble_dict_page_header(&page, false).is_err());
     }
 
     #[test]
@@ -239,6 +318,24 @@ mod tests {
             i32::MAX as usize + 1,
             false,
         );
-        assert!(assemble_dict_page_header(&page).is_err());
+        assert!(assemble_dict_page_header(&page, false).is_err());
+    }
+
+    #[test]
+    fn dict_crc() -> Result<()> {
+        let page = CompressedDictPage::new(
+            b"123456789".to_vec(),
+            Compression::Uncompressed,
+            9,
+            1,
+            false,
+        );
+        assert_eq!(assemble_dict_page_header(&page, false)?.crc, None);
+        // the check value of CRC-32
+        assert_eq!(
+            assemble_dict_page_header(&page, true)?.crc,
+            Some(0xCBF43926u32 as i32)
+        );
+        Ok(())
     }
 }
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::io::Write;
 
 #[cfg(feature = "async")]
@@ -7,8 +8,11 @@ use parquet_format_safe::{ColumnChunk, RowGroup};
 
 use crate::{
     error::{Error, Result},
-    metadata::{ColumnChunkMetaData, ColumnDescriptor},
+    metadata::{ColumnChunkMetaData, ColumnDescriptor, SortingColumn},
     page::CompressedPage,
+    scalar::Scalar,
+    schema::types::PrimitiveType,
+    statistics::serialize_statistics,
 };
 
 #[cfg(feature = "async")]
@@ -16,8 +20,9 @@ use super::column_chunk::write_column_chunk_async;
 
 use super::{
     column_chunk::write_column_chunk,
+    encryption::{column_encryptor, FileEncryptor},
     page::{is_data_page, PageWriteSpec},
-    DynIter, DynStreamingIterator,
+    DynIter, DynStreamingIterator, WriteOptions,
 };
 
 pub struct ColumnOffsetsMetadata {
@@ -75,35 +80,11 @@ fn compute_num_rows(columns: &[(ColumnChunk, Vec<PageWriteSpec>)]) -> Result<i64
         .unwrap_or(Ok(0))
 }
 
-pub fn write_row_group<
-    'a,
-    W,
-    E, // external error any of the iterators may emit
->(
-    writer: &mut W,
-    mut offset: u64,
-    descriptors: &[ColumnDescriptor],
-    columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
+/// Returns the [`RowGroup`] at `ordinal` of the written `columns` and their page specs
+pub(super) fn build_row_group(
+    columns: Vec<(ColumnChunk, Vec<PageWriteSpec>)>,
     ordinal: usize,
-) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
-where
-    W: Write,
-    Error: From<E>,
-    E: std::error::Error,
-{
-    let column_iter = descriptors.iter().zip(columns);
-
-    let initial = offset;
-    let columns = column_iter
-        .map(|(descriptor, page_iter)| {
-            let (column, page_specs, size) =
-                write_column_chunk(writer, offset, descriptor, page_iter?)?;
-            offset += size;
-            Ok((column, page_specs))
-        })
-        .collect::<Result<Vec<_>>>()?;
-    let bytes_written = offset - initial;
-
+) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>)> {
     let num_rows = compute_num_rows(&columns)?;
 
     // compute row group stats
@@ -136,10 +117,52 @@ where
             ordinal: ordinal.try_into().ok(),
         },
         specs,
-        bytes_written,
     ))
 }
 
+pub fn write_row_group<
+    'a,
+    W,
+    E, // external error any of the iterators may emit
+>(
+    writer: &mut W,
+    mut offset: u64,
+    descriptors: &[ColumnDescriptor],
+    columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
+    ordinal: usize,
+    options: &WriteOptions,
+    encryptor: Option<&FileEncryptor>,
+) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
+where
+    W: Write,
+    Error: From<E>,
+    E: std::error::Error,
+{
+    let column_iter = descriptors.iter().zip(columns);
+
+    let initial = offset;
+    let columns = column_iter
+        .enumerate()
+        .map(|(column, (descriptor, page_iter))| {
+            let mut encryptor = column_encryptor(encryptor, ordinal, column, descriptor)?;
+            let (column, page_specs, size) = write_column_chunk(
+                writer,
+                offset,
+                descriptor,
+                page_iter?,
+                options,
+                encryptor.as_mut(),
+            )?;
+            offset += size;
+            Ok((column, page_specs))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let bytes_written = offset - initial;
+
+    let (group, specs) = build_row_group(columns, ordinal)?;
+    Ok((group, specs, bytes_written))
+}
+
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub async fn write_row_group_async<
@@ -152,6 +175,8 @@ pub async fn write_row_group_async<
     descriptors: &[ColumnDescriptor],
     columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
     ordinal: usize,
+    options: &WriteOptions,
+    encryptor: Option<&FileEncryptor>,
 ) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
 where
     W: AsyncWrite + Unpin + Send,
@@ -162,46 +187,122 @@ where
 
     let initial = offset;
     let mut columns = vec![];
-    for (descriptor, page_iter) in column_iter {
-        let (column, page_specs, size) =
-            write_column_chunk_async(writer, offset, descriptor, page_iter?).await?;
+    for (column, (descriptor, page_iter)) in column_iter.enumerate() {
+        let mut encryptor = column_encryptor(encryptor, ordinal, column, descriptor)?;
+        let (column, page_specs, size) = write_column_chunk_async(
+            writer,
+            offset,
+            descriptor,
+            page_iter?,
+            options,
+            encryptor.as_mut(),
+        )
+        .await?;
         offset += size;
         columns.push((column, page_specs));
     }
     let bytes_written = offset - initial;
 
-    let num_rows = compute_num_rows(&columns)?;
-
-    // compute row group stats
-    let file_offset = columns
-        .get(0)
-        .map(|(column_chunk, _)| {
-            ColumnOffsetsMetadata::from_column_chunk(column_chunk).calc_row_group_file_offset()
-        })
-        .unwrap_or(None);
+    let (group, specs) = build_row_group(columns, ordinal)?;
+    Ok((group, specs, bytes_written))
+}
 
-    let total_byte_size = columns
-        .iter()
-        .map(|(c, _)| c.meta_data.as_ref().unwrap().total_uncompressed_size)
-        .sum();
-    let total_compressed_size = columns
+/// Checks that the `sorting_columns` are columns of `descriptors`
+pub(super) fn check_sorting_columns(
+    descriptors: &[ColumnDescriptor],
+    sorting_columns: &[SortingColumn],
+) -> Result<()> {
+    match sorting_columns
         .iter()
-        .map(|(c, _)| c.meta_data.as_ref().unwrap().total_compressed_size)
-        .sum();
+        .find(|column| column.column_idx >= descriptors.len())
+    {
+        Some(column) => Err(Error::InvalidParameter(format!(
+            "The sorting column {} is not one of the {} columns of the schema",
+            column.column_idx,
+            descriptors.len()
+        ))),
+        None => Ok(()),
+    }
+}
 
-    let (columns, specs) = columns.into_iter().unzip();
+/// Checks that the statistics of the pages `specs` of the first of the `sorting_columns` are
+/// ordered accordingly
+pub(super) fn check_sorted<S: AsRef<[PageWriteSpec]>>(
+    specs: &[S],
+    descriptors: &[ColumnDescriptor],
+    sorting_columns: &[SortingColumn],
+) -> Result<()> {
+    if let Some(column) = sorting_columns.first() {
+        let primitive_type = &descriptors[column.column_idx].descriptor.primitive_type;
+        if !is_sorted(specs[column.column_idx].as_ref(), primitive_type, column) {
+            return Err(Error::InvalidParameter(format!(
+                "The pages of the column {} are not sorted as declared",
+                column.column_idx
+            )));
+        }
+    }
+    Ok(())
+}
 
-    Ok((
-        RowGroup {
-            columns,
-            total_byte_size,
-            num_rows: num_rows as i64,
-            sorting_columns: None,
-            file_offset,
-            total_compressed_size: Some(total_compressed_size),
-            ordinal: ordinal.try_into().ok(),
-        },
-        specs,
-        bytes_written,
-    ))
+/// Declares the `sorting_columns` of `row_group`
+pub(super) fn set_sorting_columns(row_group: &mut RowGroup, sorting_columns: &[SortingColumn]) {
+    row_group.sorting_columns = Some(
+        sorting_columns
+            .iter()
+            .map(|column| column.into_thrift())
+            .collect(),
+    );
+}
+
+/// Whether the statistics of `pages` agree with `sorting_column`. Pages without statistics
+/// or whose sort order is undefined are assumed to be sorted.
+fn is_sorted(
+    pages: &[PageWriteSpec],
+    primitive_type: &PrimitiveType,
+    sorting_column: &SortingColumn,
+) -> bool {
+    let mut previous: Option<(Scalar, Scalar)> = None;
+    let mut has_nulls = false;
+    for page in pages.iter().filter(|page| is_data_page(page)) {
+        let statistics = match &page.statistics {
+            Some(statistics) => serialize_statistics(statistics.as_ref()),
+            None => return true,
+        };
+        // nulls are either before or after all values
+        let page_has_nulls = matches!(statistics.null_count, Some(count) if count > 0);
+        let nulls_are_misplaced = if sorting_column.nulls_first {
+            page_has_nulls && previous.is_some()
+        } else {
+            has_nulls && statistics.min_value.is_some()
+        };
+        if nulls_are_misplaced {
+            return false;
+        }
+        has_nulls |= page_has_nulls;
+
+        let bounds = statistics
+            .min_value
+            .zip(statistics.max_value)
+            .map(|(min, max)| {
+                Scalar::from_plain(&min, primitive_type)
+                    .zip(Scalar::from_plain(&max, primitive_type))
+            });
+        let (min, max) = match bounds {
+            Some(Some(bounds)) => bounds,
+            Some(None) => return true,
+            None => continue,
+        };
+        if let Some((previous_min, previous_max)) = &previous {
+            let is_ordered = if sorting_column.descending {
+                !matches!(previous_min.partial_cmp(&max), Some(Ordering::Less))
+            } else {
+                !matches!(previous_max.partial_cmp(&min), Some(Ordering::Greater))
+            };
+            if !is_ordered {
+                return false;
+            }
+        }
+        previous = Some((min, max));
+    }
+    true
 }
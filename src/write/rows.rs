@@ -0,0 +1,938 @@
+//! Row-oriented writing of dynamically typed values, the counterpart of [`crate::read::rows`].
+use std::borrow::Cow;
+use std::io::Write;
+
+use crate::compression::CompressionOptions;
+use crate::encoding::{hybrid_rle::encode_u32, plain, Encoding};
+use crate::error::{Error, Result};
+use crate::metadata::{
+    get_sort_order, Descriptor, KeyValue, SchemaDescriptor, SortOrder, SortingColumn,
+};
+use crate::nested::{
+    extend, is_map_annotated, list_nodes, num_leaves, push, push_validity, shred, skeleton,
+    ListNodes, Nested, ShreddedLeaf,
+};
+use crate::page::{DataPage, DataPageHeader, DataPageHeaderV1, DataPageHeaderV2, Page};
+use crate::read::levels::get_bit_width;
+use crate::read::{Field, Row};
+use crate::schema::types::{
+    ParquetType, PhysicalType, PrimitiveLogicalType, PrimitiveType, TimeUnit,
+};
+use crate::schema::Repetition;
+use crate::statistics::{
+    serialize_statistics, BinaryStatistics, BooleanStatistics, FixedLenStatistics,
+    PrimitiveStatistics, Statistics,
+};
+use crate::types::{f32_to_float16, i64_ns_to_int96, is_float16_nan, ord_float16, NativeType};
+
+use super::{
+    encode_column_chunk, Compressor, DictEncoder, DynIter, DynStreamingIterator, FileWriter,
+    Version, WriteOptions, DEFAULT_MAX_DICT_SIZE,
+};
+
+/// Options of [`RowWriter`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RowWriterOptions {
+    /// The compression of the pages
+    pub compression: CompressionOptions,
+    /// Whether to dictionary-encode the values of non-boolean columns. Each column chunk falls
+    /// back to [`Encoding::Plain`] once its dictionary exceeds [`DEFAULT_MAX_DICT_SIZE`].
+    pub dictionary: bool,
+    /// The maximum number of rows of a row group
+    pub max_rows_per_row_group: usize,
+    /// The (approximate) maximum number of bytes of the buffered values of a row group
+    pub max_bytes_per_row_group: usize,
+    /// The (approximate) number of bytes of values after which a data page is finished.
+    /// Pages always start at a row boundary.
+    pub max_page_size: usize,
+}
+
+impl Default for RowWriterOptions {
+    fn default() -> Self {
+        Self {
+            compression: CompressionOptions::Uncompressed,
+            dictionary: true,
+            max_rows_per_row_group: 1024 * 1024,
+            max_bytes_per_row_group: 128 * 1024 * 1024,
+            max_page_size: 1024 * 1024,
+        }
+    }
+}
+
+/// The non-null values of a leaf column, in their physical representation
+#[derive(Debug)]
+pub(super) enum Values {
+    Boolean(Vec<bool>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Int96(Vec<[u32; 3]>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    Binary(Vec<Vec<u8>>),
+    FixedLenBinary(Vec<Vec<u8>>),
+}
+
+impl Values {
+    pub(super) fn new(physical_type: PhysicalType) -> Self {
+        match physical_type {
+            PhysicalType::Boolean => Self::Boolean(vec![]),
+            PhysicalType::Int32 => Self::Int32(vec![]),
+            PhysicalType::Int64 => Self::Int64(vec![]),
+            PhysicalType::Int96 => Self::Int96(vec![]),
+            PhysicalType::Float => Self::Float(vec![]),
+            PhysicalType::Double => Self::Double(vec![]),
+            PhysicalType::ByteArray => Self::Binary(vec![]),
+            PhysicalType::FixedLenByteArray(_) => Self::FixedLenBinary(vec![]),
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        match self {
+            Self::Boolean(values) => values.len(),
+            Self::Int32(values) => values.len(),
+            Self::Int64(values) => values.len(),
+            Self::Int96(values) => values.len(),
+            Self::Float(values) => values.len(),
+            Self::Double(values) => values.len(),
+            Self::Binary(values) | Self::FixedLenBinary(values) => values.len(),
+        }
+    }
+
+    /// The number of bytes of the plain-encoded `index`th value
+    pub(super) fn size_of(&self, index: usize) -> usize {
+        match self {
+            Self::Boolean(_) => 1,
+            Self::Int32(_) | Self::Float(_) => 4,
+            Self::Int64(_) | Self::Double(_) => 8,
+            Self::Int96(_) => 12,
+            Self::Binary(values) => values[index].len() + 4,
+            Self::FixedLenBinary(values) => values[index].len(),
+        }
+    }
+
+    /// Appends `other`, of the same variant, to `self`
+    pub(super) fn extend(&mut self, other: Self) {
+        match (self, other) {
+            (Self::Boolean(values), Self::Boolean(other)) => values.extend(other),
+            (Self::Int32(values), Self::Int32(other)) => values.extend(other),
+            (Self::Int64(values), Self::Int64(other)) => values.extend(other),
+            (Self::Int96(values), Self::Int96(other)) => values.extend(other),
+            (Self::Float(values), Self::Float(other)) => values.extend(other),
+            (Self::Double(values), Self::Double(other)) => values.extend(other),
+            (Self::Binary(values), Self::Binary(other)) => values.extend(other),
+            (Self::FixedLenBinary(values), Self::FixedLenBinary(other)) => values.extend(other),
+            _ => unreachable!("values of the same column have the same physical type"),
+        }
+    }
+
+    /// Removes the first `length` values
+    pub(super) fn remove_first(&mut self, length: usize) {
+        match self {
+            Self::Boolean(values) => drop(values.drain(..length)),
+            Self::Int32(values) => drop(values.drain(..length)),
+            Self::Int64(values) => drop(values.drain(..length)),
+            Self::Int96(values) => drop(values.drain(..length)),
+            Self::Float(values) => drop(values.drain(..length)),
+            Self::Double(values) => drop(values.drain(..length)),
+            Self::Binary(values) | Self::FixedLenBinary(values) => drop(values.drain(..length)),
+        }
+    }
+
+    fn encode_plain(&self, range: std::ops::Range<usize>, buffer: &mut Vec<u8>) {
+        match self {
+            Self::Boolean(values) => plain::encode_bool(values[range].iter().copied(), buffer),
+            Self::Int32(values) => plain::encode_native(&values[range], buffer),
+            Self::Int64(values) => plain::encode_native(&values[range], buffer),
+            Self::Int96(values) => plain::encode_native(&values[range], buffer),
+            Self::Float(values) => plain::encode_native(&values[range], buffer),
+            Self::Double(values) => plain::encode_native(&values[range], buffer),
+            Self::Binary(values) => plain::encode_binary(values[range].iter(), buffer),
+            // the length of fixed-len values was validated when they were written
+            Self::FixedLenBinary(values) => values[range]
+                .iter()
+                .for_each(|value| buffer.extend_from_slice(value)),
+        }
+    }
+
+    fn encode_dict(
+        &self,
+        range: std::ops::Range<usize>,
+        encoder: &mut DictEncoder,
+    ) -> Result<(Encoding, Vec<u8>)> {
+        let encoded = match self {
+            Self::Boolean(_) => unreachable!("boolean columns are not dictionary-encoded"),
+            Self::Int32(values) => encoder.encode_native(&values[range])?,
+            Self::Int64(values) => encoder.encode_native(&values[range])?,
+            Self::Int96(values) => encoder.encode_native(&values[range])?,
+            Self::Float(values) => encoder.encode_native(&values[range])?,
+            Self::Double(values) => encoder.encode_native(&values[range])?,
+            Self::Binary(values) | Self::FixedLenBinary(values) => {
+                encoder.encode_binary(&values[range])?
+            }
+        };
+        Ok((encoded.encoding(), encoded.into_inner()))
+    }
+
+    /// The statistics of the values in `range`. The minimum and maximum are only computed
+    /// when the sort order of `primitive_type` is defined.
+    fn statistics(
+        &self,
+        range: std::ops::Range<usize>,
+        primitive_type: &PrimitiveType,
+        null_count: usize,
+    ) -> Box<dyn Statistics> {
+        let sort_order = get_sort_order(
+            &primitive_type.logical_type,
+            &primitive_type.converted_type,
+            &primitive_type.physical_type,
+        );
+        let null_count = Some(null_count as i64);
+        match self {
+            Self::Boolean(values) => {
+                let values = &values[range];
+                Box::new(BooleanStatistics {
+                    null_count,
+                    distinct_count: None,
+                    max_value: values.iter().copied().reduce(|a, b| a | b),
+                    min_value: values.iter().copied().reduce(|a, b| a & b),
+                })
+            }
+            Self::Int32(values) => {
+                let values = values[range].iter().copied();
+                let (min_value, max_value) = match sort_order {
+                    SortOrder::Signed => min_max(values, |a, b| a.cmp(b)),
+                    SortOrder::Unsigned => min_max(values, |a, b| (*a as u32).cmp(&(*b as u32))),
+                    SortOrder::Undefined => (None, None),
+                };
+                native_statistics(primitive_type, null_count, min_value, max_value)
+            }
+            Self::Int64(values) => {
+                let values = values[range].iter().copied();
+                let (min_value, max_value) = match sort_order {
+                    SortOrder::Signed => min_max(values, |a, b| a.cmp(b)),
+                    SortOrder::Unsigned => min_max(values, |a, b| (*a as u64).cmp(&(*b as u64))),
+                    SortOrder::Undefined => (None, None),
+                };
+                native_statistics(primitive_type, null_count, min_value, max_value)
+            }
+            Self::Int96(values) => {
+                let (min_value, max_value) = match sort_order {
+                    SortOrder::Signed => min_max(values[range].iter().copied(), NativeType::ord),
+                    _ => (None, None),
+                };
+                native_statistics(primitive_type, null_count, min_value, max_value)
+            }
+            Self::Float(values) => {
+                let values = values[range].iter().copied().filter(|x| !x.is_nan());
+                let (min_value, max_value) = min_max(values, NativeType::ord);
+                native_statistics(primitive_type, null_count, min_value, max_value)
+            }
+            Self::Double(values) => {
+                let values = values[range].iter().copied().filter(|x| !x.is_nan());
+                let (min_value, max_value) = min_max(values, NativeType::ord);
+                native_statistics(primitive_type, null_count, min_value, max_value)
+            }
+            Self::Binary(values) | Self::FixedLenBinary(values) => {
+                let values = &values[range];
+                if primitive_type.logical_type == Some(PrimitiveLogicalType::Float16) {
+                    let values = values
+                        .iter()
+                        .map(|x| u16::from_le_bytes([x[0], x[1]]))
+                        .filter(|x| !is_float16_nan(*x));
+                    let (min_value, max_value) = min_max(values, |a, b| ord_float16(*a, *b));
+                    return Box::new(FixedLenStatistics {
+                        primitive_type: primitive_type.clone(),
+                        null_count,
+                        distinct_count: None,
+                        min_value: min_value.map(|x| x.to_le_bytes().to_vec()),
+                        max_value: max_value.map(|x| x.to_le_bytes().to_vec()),
+                    });
+                }
+                // binary values are only ordered lexicographically (i.e. unsigned)
+                let (min_value, max_value) = match sort_order {
+                    SortOrder::Unsigned => min_max(values.iter().cloned(), |a, b| a.cmp(b)),
+                    _ => (None, None),
+                };
+                match primitive_type.physical_type {
+                    PhysicalType::FixedLenByteArray(_) => Box::new(FixedLenStatistics {
+                        primitive_type: primitive_type.clone(),
+                        null_count,
+                        distinct_count: None,
+                        min_value,
+                        max_value,
+                    }),
+                    _ => Box::new(BinaryStatistics {
+                        primitive_type: primitive_type.clone(),
+                        null_count,
+                        distinct_count: None,
+                        min_value,
+                        max_value,
+                    }),
+                }
+            }
+        }
+    }
+}
+
+fn min_max<T, I: Iterator<Item = T>, F: Fn(&T, &T) -> std::cmp::Ordering>(
+    values: I,
+    ord: F,
+) -> (Option<T>, Option<T>)
+where
+    T: Clone,
+{
+    values.fold((None, None), |(min, max), value| {
+        let min = match min {
+            Some(min) if ord(&min, &value).is_le() => min,
+            _ => value.clone(),
+        };
+        let max = match max {
+            Some(max) if ord(&max, &value).is_ge() => max,
+            _ => value,
+        };
+        (Some(min), Some(max))
+    })
+}
+
+fn native_statistics<T: NativeType>(
+    primitive_type: &PrimitiveType,
+    null_count: Option<i64>,
+    min_value: Option<T>,
+    max_value: Option<T>,
+) -> Box<dyn Statistics> {
+    Box::new(PrimitiveStatistics::<T> {
+        primitive_type: primitive_type.clone(),
+        null_count,
+        distinct_count: None,
+        min_value,
+        max_value,
+    })
+}
+
+/// The buffered rows of a field of the schema
+#[derive(Debug)]
+struct FieldBuffer {
+    nested: Nested,
+    // the non-null values of each leaf of the field
+    leaves: Vec<Values>,
+}
+
+impl FieldBuffer {
+    fn new(field: &ParquetType) -> Self {
+        let mut leaves = vec![];
+        collect_leaves(field, &mut leaves);
+        Self {
+            nested: skeleton(field, false),
+            leaves,
+        }
+    }
+
+    fn extend(&mut self, other: Self) {
+        extend(&mut self.nested, other.nested);
+        self.leaves
+            .iter_mut()
+            .zip(other.leaves)
+            .for_each(|(values, other)| values.extend(other));
+    }
+}
+
+fn collect_leaves(field: &ParquetType, leaves: &mut Vec<Values>) {
+    match field {
+        ParquetType::PrimitiveType(primitive_type) => {
+            leaves.push(Values::new(primitive_type.physical_type))
+        }
+        ParquetType::GroupType { fields, .. } => fields
+            .iter()
+            .for_each(|field| collect_leaves(field, leaves)),
+    }
+}
+
+fn invalid_value(value: &Field, field: &ParquetType) -> Error {
+    Error::InvalidParameter(format!(
+        "The value {:?} cannot be written to the field \"{}\"",
+        value,
+        field.name()
+    ))
+}
+
+/// Appends `value` to `nested` and its leaf values to `leaves`, returning the (approximate)
+/// number of bytes appended.
+/// * `required`: whether the repetition of `field` was already accounted for by its parent
+fn push_field(
+    field: &ParquetType,
+    required: bool,
+    nested: &mut Nested,
+    value: &Field,
+    leaves: &mut [Values],
+) -> Result<usize> {
+    let repetition = field.get_field_info().repetition;
+    if matches!(value, Field::Null) {
+        return if !required && repetition == Repetition::Optional {
+            push(nested, false);
+            Ok(0)
+        } else {
+            Err(Error::InvalidParameter(format!(
+                "The field \"{}\" is not nullable",
+                field.name()
+            )))
+        };
+    }
+    if !required && repetition == Repetition::Repeated {
+        // a repeated field is a list of itself
+        let items = match value {
+            Field::List(items) => items,
+            _ => return Err(invalid_value(value, field)),
+        };
+        let nodes = ListNodes {
+            element: field,
+            element_is_repeated: true,
+        };
+        return push_list(nodes, nested, items, leaves);
+    }
+
+    match field {
+        ParquetType::PrimitiveType(primitive_type) => {
+            let size = push_value(primitive_type, value, &mut leaves[0])?;
+            push(nested, true);
+            Ok(size)
+        }
+        ParquetType::GroupType { fields, .. } => {
+            if let Some(nodes) = list_nodes(field) {
+                let items = match value {
+                    Field::List(items) if !is_map_annotated(field) => Cow::Borrowed(items),
+                    Field::Map(entries) if is_map_annotated(field) => {
+                        Cow::Owned(map_items(nodes.element, entries)?)
+                    }
+                    _ => return Err(invalid_value(value, field)),
+                };
+                return push_list(nodes, nested, &items, leaves);
+            }
+
+            let values = match value {
+                Field::Group(values) if values.len() == fields.len() => values,
+                _ => return Err(invalid_value(value, field)),
+            };
+            push(nested, true);
+            let children = match nested {
+                Nested::Struct { children, .. } => children,
+                _ => unreachable!("the nested of a group is a struct"),
+            };
+
+            let mut size = 0;
+            let mut leaves = leaves;
+            for ((field, child), (name, value)) in fields.iter().zip(children).zip(values) {
+                if name != field.name() {
+                    return Err(Error::InvalidParameter(format!(
+                        "Expected the field \"{}\" but got \"{}\"",
+                        field.name(),
+                        name
+                    )));
+                }
+                let (field_leaves, remaining) = leaves.split_at_mut(num_leaves(field));
+                size += push_field(field, false, child, value, field_leaves)?;
+                leaves = remaining;
+            }
+            Ok(size)
+        }
+    }
+}
+
+fn push_list(
+    nodes: ListNodes,
+    nested: &mut Nested,
+    items: &[Field],
+    leaves: &mut [Values],
+) -> Result<usize> {
+    let length = nested.len();
+    let (validity, offsets, child) = match nested {
+        Nested::List {
+            validity,
+            offsets,
+            child,
+        } => (validity, offsets, child),
+        _ => unreachable!("the nested of a list is a list"),
+    };
+
+    let mut size = 0;
+    for item in items {
+        size += push_field(
+            nodes.element,
+            nodes.element_is_repeated,
+            child,
+            item,
+            leaves,
+        )?;
+    }
+    push_validity(validity, length, true);
+    offsets.push(offsets.last().unwrap() + items.len());
+    Ok(size)
+}
+
+/// Converts the entries of a map into the groups of its key-value node
+fn map_items(key_value: &ParquetType, entries: &[(Field, Field)]) -> Result<Vec<Field>> {
+    let fields = match key_value {
+        ParquetType::GroupType { fields, .. } if !fields.is_empty() && fields.len() <= 2 => fields,
+        _ => {
+            return Err(Error::InvalidParameter(format!(
+                "The key-value node \"{}\" of a map must be a group of a key and a value",
+                key_value.name()
+            )))
+        }
+    };
+    entries
+        .iter()
+        .map(|(key, value)| {
+            let mut group = vec![(fields[0].name().to_string(), key.clone())];
+            if let Some(field) = fields.get(1) {
+                group.push((field.name().to_string(), value.clone()));
+            } else if !matches!(value, Field::Null) {
+                return Err(invalid_value(value, key_value));
+            }
+            Ok(Field::Group(group))
+        })
+        .collect()
+}
+
+/// Returns the big-endian two's complement representation of `value` in `size` bytes,
+/// or in the minimal number of bytes when `size` is `None`.
+pub(crate) fn decimal_to_bytes(value: i128, size: Option<usize>) -> Option<Vec<u8>> {
+    let bytes = value.to_be_bytes();
+    let fill = if value < 0 { 0xff } else { 0 };
+    // a leading byte is redundant when it only extends the sign of the next byte
+    let redundant = bytes
+        .iter()
+        .zip(bytes.iter().skip(1))
+        .take_while(|(byte, next)| **byte == fill && (**next & 0x80) == (fill & 0x80))
+        .count();
+    let minimal = bytes.len() - redundant;
+    match size {
+        None => Some(bytes[redundant..].to_vec()),
+        Some(size) if size >= minimal => {
+            let mut result = vec![fill; size.saturating_sub(bytes.len())];
+            result.extend_from_slice(&bytes[bytes.len() - size.min(bytes.len())..]);
+            Some(result)
+        }
+        _ => None,
+    }
+}
+
+/// Appends `value` to the `values` of a leaf of `primitive_type`, returning its size in bytes.
+fn push_value(primitive_type: &PrimitiveType, value: &Field, values: &mut Values) -> Result<usize> {
+    let invalid = || {
+        Error::InvalidParameter(format!(
+            "The value {:?} cannot be written to the column \"{}\" of type {:?}",
+            value, primitive_type.field_info.name, primitive_type.physical_type
+        ))
+    };
+    match values {
+        Values::Boolean(values) => match value {
+            Field::Bool(value) => values.push(*value),
+            _ => return Err(invalid()),
+        },
+        Values::Int32(values) => values.push(match value {
+            Field::Int8(value) => *value as i32,
+            Field::Int16(value) => *value as i32,
+            Field::Int32(value) => *value,
+            Field::UInt8(value) => *value as i32,
+            Field::UInt16(value) => *value as i32,
+            // unsigned values are stored as their bit pattern
+            Field::UInt32(value) => *value as i32,
+            Field::Date(value) => *value,
+            Field::Time { value, .. } => i32::try_from(*value).map_err(|_| invalid())?,
+            Field::Decimal { value, .. } => i32::try_from(*value).map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        }),
+        Values::Int64(values) => values.push(match value {
+            Field::Int64(value) => *value,
+            Field::UInt64(value) => *value as i64,
+            Field::Time { value, .. } | Field::Timestamp { value, .. } => *value,
+            Field::Decimal { value, .. } => i64::try_from(*value).map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        }),
+        Values::Int96(values) => match value {
+            Field::Timestamp { value, unit, .. } => {
+                let ns = match unit {
+                    TimeUnit::Milliseconds => value.checked_mul(1_000_000),
+                    TimeUnit::Microseconds => value.checked_mul(1_000),
+                    TimeUnit::Nanoseconds => Some(*value),
+                };
+                values.push(i64_ns_to_int96(ns.ok_or_else(invalid)?))
+            }
+            _ => return Err(invalid()),
+        },
+        Values::Float(values) => match value {
+            Field::Float(value) => values.push(*value),
+            _ => return Err(invalid()),
+        },
+        Values::Double(values) => match value {
+            Field::Double(value) => values.push(*value),
+            _ => return Err(invalid()),
+        },
+        Values::Binary(values) | Values::FixedLenBinary(values) => {
+            let bytes = match (primitive_type.physical_type, value) {
+                (PhysicalType::ByteArray, Field::Bytes(value)) => value.clone(),
+                (PhysicalType::ByteArray, Field::String(value)) => value.as_bytes().to_vec(),
+                (PhysicalType::ByteArray, Field::Decimal { value, .. }) => {
+                    decimal_to_bytes(*value, None).unwrap()
+                }
+                (PhysicalType::FixedLenByteArray(size), Field::Bytes(value))
+                    if value.len() == size =>
+                {
+                    value.clone()
+                }
+                (PhysicalType::FixedLenByteArray(size), Field::Decimal { value, .. }) => {
+                    decimal_to_bytes(*value, Some(size)).ok_or_else(invalid)?
+                }
+                (PhysicalType::FixedLenByteArray(2), Field::Float(value))
+                    if primitive_type.logical_type == Some(PrimitiveLogicalType::Float16) =>
+                {
+                    f32_to_float16(*value).to_le_bytes().to_vec()
+                }
+                _ => return Err(invalid()),
+            };
+            values.push(bytes)
+        }
+    };
+    Ok(values.size_of(values.len() - 1))
+}
+
+fn encode_levels(levels: &[u32], max_level: i16) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+    if max_level > 0 {
+        encode_u32(
+            &mut buffer,
+            levels.iter().copied(),
+            get_bit_width(max_level),
+        )?;
+    }
+    Ok(buffer)
+}
+
+/// Encodes the entries `range` of `leaf` and their `values` into a data page
+#[allow(clippy::too_many_arguments)]
+pub(super) fn encode_page(
+    leaf: &ShreddedLeaf,
+    range: std::ops::Range<usize>,
+    values: &Values,
+    values_range: std::ops::Range<usize>,
+    descriptor: &Descriptor,
+    options: &WriteOptions,
+    compression: CompressionOptions,
+    dict: Option<&mut DictEncoder>,
+) -> Result<Page> {
+    let rep_levels = &leaf.rep_levels[range.clone()];
+    let num_values = rep_levels.len();
+    let num_rows = rep_levels.iter().filter(|level| **level == 0).count();
+    let num_nulls = num_values - values_range.len();
+
+    let statistics = options.write_statistics.then(|| {
+        serialize_statistics(
+            values
+                .statistics(values_range.clone(), &descriptor.primitive_type, num_nulls)
+                .as_ref(),
+        )
+    });
+
+    let rep_levels = encode_levels(rep_levels, descriptor.max_rep_level)?;
+    let def_levels = encode_levels(&leaf.def_levels[range], descriptor.max_def_level)?;
+
+    let (encoding, encoded) = match dict {
+        Some(encoder) => values.encode_dict(values_range, encoder)?,
+        None => {
+            let mut buffer = vec![];
+            values.encode_plain(values_range, &mut buffer);
+            (Encoding::Plain, buffer)
+        }
+    };
+
+    let mut buffer = vec![];
+    let header = match options.version {
+        Version::V1 => {
+            // levels of v1 pages are prefixed by their length
+            for (levels, max_level) in [
+                (rep_levels, descriptor.max_rep_level),
+                (def_levels, descriptor.max_def_level),
+            ] {
+                if max_level > 0 {
+                    buffer.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+                    buffer.extend_from_slice(&levels);
+                }
+            }
+            buffer.extend_from_slice(&encoded);
+            DataPageHeader::V1(DataPageHeaderV1 {
+                num_values: num_values.try_into()?,
+                encoding: encoding.into(),
+                definition_level_encoding: Encoding::Rle.into(),
+                repetition_level_encoding: Encoding::Rle.into(),
+                statistics,
+            })
+        }
+        Version::V2 => {
+            buffer.extend_from_slice(&rep_levels);
+            buffer.extend_from_slice(&def_levels);
+            buffer.extend_from_slice(&encoded);
+            DataPageHeader::V2(DataPageHeaderV2 {
+                num_values: num_values.try_into()?,
+                num_nulls: num_nulls.try_into()?,
+                num_rows: num_rows.try_into()?,
+                encoding: encoding.into(),
+                definition_levels_byte_length: def_levels.len().try_into()?,
+                repetition_levels_byte_length: rep_levels.len().try_into()?,
+                is_compressed: Some(compression != CompressionOptions::Uncompressed),
+                statistics,
+            })
+        }
+    };
+
+    Ok(Page::Data(DataPage::new(
+        header,
+        buffer,
+        descriptor.clone(),
+        Some(num_rows),
+    )))
+}
+
+/// Encodes the levels of `leaf` and its `values` into the pages of a column chunk. Pages are
+/// split at row boundaries once their values exceed `options.max_page_size`.
+fn encode_column(
+    leaf: &ShreddedLeaf,
+    values: &Values,
+    descriptor: &Descriptor,
+    write_options: &WriteOptions,
+    options: &RowWriterOptions,
+) -> Result<Vec<Page>> {
+    let physical_type = descriptor.primitive_type.physical_type;
+    let mut dict = if options.dictionary && physical_type != PhysicalType::Boolean {
+        Some(DictEncoder::try_new(physical_type, DEFAULT_MAX_DICT_SIZE)?)
+    } else {
+        None
+    };
+    let max_def_level = descriptor.max_def_level as u32;
+
+    let mut pages = vec![];
+    let mut start = 0;
+    let mut values_start = 0;
+    let mut values_end = 0;
+    let mut size = 0;
+    let num_entries = leaf.def_levels.len();
+    for i in 0..num_entries {
+        if leaf.rep_levels[i] == 0 && i > start && size >= options.max_page_size {
+            pages.push(encode_page(
+                leaf,
+                start..i,
+                values,
+                values_start..values_end,
+                descriptor,
+                write_options,
+                options.compression,
+                dict.as_mut(),
+            )?);
+            start = i;
+            values_start = values_end;
+            size = 0;
+        }
+        if leaf.def_levels[i] == max_def_level {
+            size += values.size_of(values_end);
+            values_end += 1;
+        }
+    }
+    if start < num_entries || pages.is_empty() {
+        pages.push(encode_page(
+            leaf,
+            start..num_entries,
+            values,
+            values_start..values_end,
+            descriptor,
+            write_options,
+            options.compression,
+            dict.as_mut(),
+        )?);
+    }
+
+    if let Some(dict_page) = dict.and_then(|dict| dict.into_dict_page()) {
+        pages.insert(0, Page::Dict(dict_page));
+    }
+    Ok(pages)
+}
+
+/// A writer of rows of dynamically typed [`Field`]s, the counterpart of [`crate::read::rows`].
+///
+/// Rows are buffered per leaf column and written as a row group, shredded, encoded
+/// ([`Encoding::Plain`] or dictionary-encoded) and compressed, whenever
+/// [`RowWriterOptions::max_rows_per_row_group`] rows or
+/// [`RowWriterOptions::max_bytes_per_row_group`] bytes are buffered, or when
+/// [`RowWriter::flush`] is called.
+pub struct RowWriter<W: Write> {
+    writer: FileWriter<W>,
+    options: RowWriterOptions,
+    fields: Vec<FieldBuffer>,
+    num_rows: usize,
+    num_bytes: usize,
+}
+
+impl<W: Write> RowWriter<W> {
+    /// Returns a new [`RowWriter`] of rows of `schema`.
+    pub fn new(
+        writer: W,
+        schema: SchemaDescriptor,
+        options: WriteOptions,
+        row_options: RowWriterOptions,
+    ) -> Self {
+        let fields = schema.fields().iter().map(FieldBuffer::new).collect();
+        Self {
+            writer: FileWriter::new(writer, schema, options, None),
+            options: row_options,
+            fields,
+            num_rows: 0,
+            num_bytes: 0,
+        }
+    }
+
+    /// The [`SchemaDescriptor`] of the rows of this writer
+    pub fn schema(&self) -> &SchemaDescriptor {
+        self.writer.schema()
+    }
+
+    /// The number of buffered rows, not yet written to a row group
+    pub fn num_buffered_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Declares the columns by which the rows of the row groups written after this call are
+    /// sorted (see [`FileWriter::set_sorting_columns`]), including the buffered rows. Row
+    /// groups are verified before they are written.
+    /// # Errors
+    /// Errors iff a sorting column is not a column of the schema
+    pub fn set_sorting_columns(
+        &mut self,
+        sorting_columns: Option<Vec<SortingColumn>>,
+        verify: bool,
+    ) -> Result<()> {
+        self.writer.set_sorting_columns(sorting_columns, verify)
+    }
+
+    /// Buffers a row, writing a row group when a limit of the [`RowWriterOptions`] is reached.
+    ///
+    /// The fields of `row` must be the fields of the schema, in order. Lists are
+    /// [`Field::List`], maps are [`Field::Map`] and other groups are [`Field::Group`].
+    /// # Errors
+    /// Errors iff `row` does not match the schema, in which case it is not buffered, or
+    /// iff writing the row group errors.
+    pub fn write(&mut self, row: &Row) -> Result<()> {
+        let fields = self.writer.schema().fields();
+        if row.len() != fields.len() {
+            return Err(Error::InvalidParameter(format!(
+                "The schema has {} fields but the row has {}",
+                fields.len(),
+                row.len()
+            )));
+        }
+
+        // the row is converted first so that an invalid row leaves the buffers untouched
+        let mut buffers = Vec::with_capacity(fields.len());
+        let mut num_bytes = 0;
+        for (field, (name, value)) in fields.iter().zip(row) {
+            if name != field.name() {
+                return Err(Error::InvalidParameter(format!(
+                    "Expected the field \"{}\" but got \"{}\"",
+                    field.name(),
+                    name
+                )));
+            }
+            let mut buffer = FieldBuffer::new(field);
+            num_bytes += push_field(field, false, &mut buffer.nested, value, &mut buffer.leaves)?;
+            buffers.push(buffer);
+        }
+        self.fields
+            .iter_mut()
+            .zip(buffers)
+            .for_each(|(buffer, row)| buffer.extend(row));
+        self.num_rows += 1;
+        self.num_bytes += num_bytes;
+
+        if self.num_rows >= self.options.max_rows_per_row_group
+            || self.num_bytes >= self.options.max_bytes_per_row_group
+        {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered rows, if any, as a row group.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.num_rows == 0 {
+            return Ok(());
+        }
+        let schema = self.writer.schema().clone();
+        let buffers = std::mem::replace(
+            &mut self.fields,
+            schema.fields().iter().map(FieldBuffer::new).collect(),
+        );
+        self.num_rows = 0;
+        self.num_bytes = 0;
+
+        let write_options = *self.writer.options();
+        let mut columns = schema.columns().iter();
+        let mut pages = vec![];
+        for (field, buffer) in schema.fields().iter().zip(buffers) {
+            let leaves = shred(field, &buffer.nested)?;
+            for (leaf, values) in leaves.iter().zip(buffer.leaves.iter()) {
+                let column = columns.next().unwrap();
+                pages.push(encode_column(
+                    leaf,
+                    values,
+                    &column.descriptor,
+                    &write_options,
+                    &self.options,
+                )?);
+            }
+        }
+
+        // the column chunks are encoded to memory so that the row group is verified before it
+        // is written
+        let columns = pages
+            .into_iter()
+            .zip(schema.columns())
+            .map(|(pages, column)| {
+                let pages = DynStreamingIterator::new(Compressor::new_from_vec(
+                    DynIter::new(pages.into_iter().map(Ok)),
+                    self.options.compression,
+                    vec![],
+                ));
+                encode_column_chunk(column, pages, &write_options)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.writer.write_encoded(columns)
+    }
+
+    /// Writes the buffered rows and the footer of the file, returning the total size of the file.
+    pub fn end(&mut self, key_value_metadata: Option<Vec<KeyValue>>) -> Result<u64> {
+        self.flush()?;
+        self.writer.end(key_value_metadata)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal() {
+        assert_eq!(decimal_to_bytes(0, None), Some(vec![0]));
+        assert_eq!(decimal_to_bytes(127, None), Some(vec![127]));
+        assert_eq!(decimal_to_bytes(128, None), Some(vec![0, 128]));
+        assert_eq!(decimal_to_bytes(-1, None), Some(vec![255]));
+        assert_eq!(decimal_to_bytes(-129, None), Some(vec![255, 127]));
+        assert_eq!(
+            decimal_to_bytes(-129, Some(4)),
+            Some(vec![255, 255, 255, 127])
+        );
+        assert_eq!(decimal_to_bytes(128, Some(1)), None);
+        assert_eq!(decimal_to_bytes(-1, Some(17)), Some(vec![255; 17]));
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::{Read, Seek, Write};
+use std::ops::Range;
+
+use crate::error::Result;
+use crate::metadata::FileMetaData;
+
+use super::merge::copy_row_groups;
+
+/// Writes a file with the `row_groups` of the file of `reader`, whose metadata is `metadata`,
+/// returning the total size of the written file.
+///
+/// The column chunks are copied byte for byte, without decoding their pages, and keep their
+/// statistics. The row groups keep their sorting columns. Their bloom filters, column indexes
+/// and offset indexes are copied with their offsets updated to their new position. The
+/// key-value metadata and `created_by` of the file are kept.
+/// # Errors
+/// Errors iff a row group does not exist, the file is encrypted or it cannot be read.
+pub fn extract_row_groups<R: Read + Seek, W: Write>(
+    reader: &mut R,
+    metadata: &FileMetaData,
+    row_groups: &[usize],
+    writer: &mut W,
+) -> Result<u64> {
+    let selection = row_groups
+        .iter()
+        .map(|row_group| (0, *row_group))
+        .collect::<Vec<_>>();
+    copy_row_groups(
+        std::slice::from_mut(reader),
+        &[metadata],
+        &selection,
+        writer,
+        metadata.key_value_metadata.clone(),
+        metadata.created_by.clone(),
+    )
+}
+
+/// Splits the row groups of `metadata` into consecutive ranges whose total compressed size is
+/// at most `max_size` bytes, e.g. to rebalance a file into smaller files with
+/// [`extract_row_groups`]. A row group larger than `max_size` is in a range of its own.
+pub fn split_row_groups(metadata: &FileMetaData, max_size: usize) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut size = 0;
+    for (i, row_group) in metadata.row_groups.iter().enumerate() {
+        let row_group_size = row_group.compressed_size();
+        if i > start && size + row_group_size > max_size {
+            ranges.push(start..i);
+            start = i;
+            size = 0;
+        }
+        size += row_group_size;
+    }
+    if start < metadata.row_groups.len() {
+        ranges.push(start..metadata.row_groups.len());
+    }
+    ranges
+}
@@ -1,9 +1,13 @@
+use std::cmp::Ordering;
 use std::sync::Arc;
 
+use parquet_format_safe::Statistics as ParquetStatistics;
+
 use crate::error::{Error, Result};
-use crate::schema::types::PhysicalType;
+use crate::metadata::{get_sort_order, SortOrder};
+use crate::schema::types::{PhysicalType, PrimitiveLogicalType, PrimitiveType};
 use crate::statistics::*;
-use crate::types::NativeType;
+use crate::types::{is_float16_nan, ord_float16, NativeType};
 
 #[inline]
 fn reduce_single<T, F: Fn(T, T) -> T>(lhs: Option<T>, rhs: Option<T>, op: F) -> Option<T> {
@@ -16,15 +20,28 @@ fn reduce_single<T, F: Fn(T, T) -> T>(lhs: Option<T>, rhs: Option<T>, op: F) ->
 }
 
 #[inline]
-fn reduce_vec8(lhs: Option<Vec<u8>>, rhs: &Option<Vec<u8>>, max: bool) -> Option<Vec<u8>> {
+fn reduce_vec8(
+    lhs: Option<Vec<u8>>,
+    rhs: &Option<Vec<u8>>,
+    max: bool,
+    sort_order: SortOrder,
+) -> Option<Vec<u8>> {
     match (lhs, rhs) {
         (None, None) => None,
         (Some(x), None) => Some(x),
         (None, Some(x)) => Some(x.clone()),
-        (Some(x), Some(y)) => Some(ord_binary(x, y.clone(), max)),
+        (Some(x), Some(y)) => Some(ord_binary(x, y.clone(), max, sort_order)),
     }
 }
 
+fn sort_order(primitive_type: &PrimitiveType) -> SortOrder {
+    get_sort_order(
+        &primitive_type.logical_type,
+        &primitive_type.converted_type,
+        &primitive_type.physical_type,
+    )
+}
+
 pub fn reduce(stats: &[&Option<Arc<dyn Statistics>>]) -> Result<Option<Arc<dyn Statistics>>> {
     if stats.is_empty() {
         return Ok(None);
@@ -51,12 +68,41 @@ pub fn reduce(stats: &[&Option<Arc<dyn Statistics>>]) -> Result<Option<Arc<dyn S
             Some(Arc::new(reduce_boolean(stats)))
         }
         PhysicalType::Int32 => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<i32, _>(stats)))
+            let stats = stats
+                .iter()
+                .map(|x| {
+                    x.as_any()
+                        .downcast_ref::<PrimitiveStatistics<i32>>()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            // e.g. UINT_32 is compared as unsigned
+            Some(Arc::new(match sort_order(&stats[0].primitive_type) {
+                SortOrder::Unsigned => {
+                    reduce_primitive_by(stats.into_iter(), |x, y| *x as u32 > *y as u32)
+                }
+                _ => reduce_primitive(stats.into_iter()),
+            }))
         }
         PhysicalType::Int64 => {
+            let stats = stats
+                .iter()
+                .map(|x| {
+                    x.as_any()
+                        .downcast_ref::<PrimitiveStatistics<i64>>()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            Some(Arc::new(match sort_order(&stats[0].primitive_type) {
+                SortOrder::Unsigned => {
+                    reduce_primitive_by(stats.into_iter(), |x, y| *x as u64 > *y as u64)
+                }
+                _ => reduce_primitive(stats.into_iter()),
+            }))
+        }
+        PhysicalType::Int96 => {
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<i64, _>(stats)))
+            Some(Arc::new(reduce_primitive::<[u32; 3], _>(stats)))
         }
         PhysicalType::Float => {
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
@@ -74,55 +120,95 @@ pub fn reduce(stats: &[&Option<Arc<dyn Statistics>>]) -> Result<Option<Arc<dyn S
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
             Some(Arc::new(reduce_fix_len_binary(stats)))
         }
-        _ => todo!(),
     })
 }
 
 fn reduce_binary<'a, I: Iterator<Item = &'a BinaryStatistics>>(mut stats: I) -> BinaryStatistics {
     let initial = stats.next().unwrap().clone();
+    let sort_order = sort_order(&initial.primitive_type);
     stats.fold(initial, |mut acc, new| {
-        acc.min_value = reduce_vec8(acc.min_value, &new.min_value, false);
-        acc.max_value = reduce_vec8(acc.max_value, &new.max_value, true);
+        acc.min_value = reduce_vec8(acc.min_value, &new.min_value, false, sort_order);
+        acc.max_value = reduce_vec8(acc.max_value, &new.max_value, true, sort_order);
         acc.null_count = reduce_single(acc.null_count, new.null_count, |x, y| x + y);
         acc.distinct_count = None;
         acc
     })
 }
 
+// SPEC: FLOAT16 are compared as signed floats, NaN are not valid min/max values,
+// the min of zeros is -0 and their max is +0.
+#[inline]
+fn reduce_float16(lhs: Option<Vec<u8>>, rhs: &Option<Vec<u8>>, max: bool) -> Option<Vec<u8>> {
+    let value = |x: Option<&[u8]>| {
+        x.and_then(|x| x.try_into().ok())
+            .map(u16::from_le_bytes)
+            .filter(|x| !is_float16_nan(*x))
+    };
+    reduce_single(value(lhs.as_deref()), value(rhs.as_deref()), |x, y| {
+        let ordering = ord_float16(x, y).then_with(|| (y & 0x8000).cmp(&(x & 0x8000)));
+        if (ordering == Ordering::Greater) == max {
+            x
+        } else {
+            y
+        }
+    })
+    .map(|x| x.to_le_bytes().to_vec())
+}
+
 fn reduce_fix_len_binary<'a, I: Iterator<Item = &'a FixedLenStatistics>>(
     mut stats: I,
 ) -> FixedLenStatistics {
     let initial = stats.next().unwrap().clone();
+    let is_float16 = initial.primitive_type.logical_type == Some(PrimitiveLogicalType::Float16);
+    let sort_order = sort_order(&initial.primitive_type);
+    let reduce = |lhs, rhs: &Option<Vec<u8>>, max| {
+        if is_float16 {
+            reduce_float16(lhs, rhs, max)
+        } else {
+            reduce_vec8(lhs, rhs, max, sort_order)
+        }
+    };
     stats.fold(initial, |mut acc, new| {
-        acc.min_value = reduce_vec8(acc.min_value, &new.min_value, false);
-        acc.max_value = reduce_vec8(acc.max_value, &new.max_value, true);
+        acc.min_value = reduce(acc.min_value, &new.min_value, false);
+        acc.max_value = reduce(acc.max_value, &new.max_value, true);
         acc.null_count = reduce_single(acc.null_count, new.null_count, |x, y| x + y);
         acc.distinct_count = None;
         acc
     })
 }
 
-fn ord_binary(a: Vec<u8>, b: Vec<u8>, max: bool) -> Vec<u8> {
-    for (v1, v2) in a.iter().zip(b.iter()) {
-        match v1.cmp(v2) {
-            std::cmp::Ordering::Greater => {
-                if max {
-                    return a;
-                } else {
-                    return b;
-                }
-            }
-            std::cmp::Ordering::Less => {
-                if max {
-                    return b;
-                } else {
-                    return a;
-                }
-            }
-            _ => {}
+/// The max (or min) of `a` and `b` in `sort_order`: signed values (e.g. decimals) are compared
+/// as big-endian two's complement integers and all other values byte-wise.
+fn ord_binary(a: Vec<u8>, b: Vec<u8>, max: bool, sort_order: SortOrder) -> Vec<u8> {
+    let ordering = match sort_order {
+        SortOrder::Signed => ord_signed_bytes(&a, &b),
+        _ => a.cmp(&b),
+    };
+    if (ordering == Ordering::Less) == max {
+        b
+    } else {
+        a
+    }
+}
+
+/// Compares two big-endian two's complement integers of any length
+fn ord_signed_bytes(a: &[u8], b: &[u8]) -> Ordering {
+    let is_negative = |x: &[u8]| matches!(x.first(), Some(x) if x & 0x80 != 0);
+    match (is_negative(a), is_negative(b)) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (is_negative, _) => {
+            // sign-extend the shortest value
+            let length = a.len().max(b.len());
+            let padding = if is_negative { u8::MAX } else { 0 };
+            let extend = |x: &[u8]| {
+                let mut extended = vec![padding; length - x.len()];
+                extended.extend_from_slice(x);
+                extended
+            };
+            extend(a).cmp(&extend(b))
         }
     }
-    a
 }
 
 fn reduce_boolean<'a, I: Iterator<Item = &'a BooleanStatistics>>(
@@ -150,20 +236,33 @@ fn reduce_primitive<
     'a,
     T: NativeType + std::cmp::PartialOrd,
     I: Iterator<Item = &'a PrimitiveStatistics<T>>,
+>(
+    stats: I,
+) -> PrimitiveStatistics<T> {
+    reduce_primitive_by(stats, |x, y| x > y)
+}
+
+/// Reduces `stats` whose values are ordered by `gt`, i.e. whether a value is greater than other
+fn reduce_primitive_by<
+    'a,
+    T: NativeType,
+    I: Iterator<Item = &'a PrimitiveStatistics<T>>,
+    F: Fn(&T, &T) -> bool,
 >(
     mut stats: I,
+    gt: F,
 ) -> PrimitiveStatistics<T> {
     let initial = stats.next().unwrap().clone();
     stats.fold(initial, |mut acc, new| {
         acc.min_value = reduce_single(
             acc.min_value,
             new.min_value,
-            |x, y| if x > y { y } else { x },
+            |x, y| if gt(&x, &y) { y } else { x },
         );
         acc.max_value = reduce_single(
             acc.max_value,
             new.max_value,
-            |x, y| if x > y { x } else { y },
+            |x, y| if gt(&x, &y) { x } else { y },
         );
         acc.null_count = reduce_single(acc.null_count, new.null_count, |x, y| x + y);
         acc.distinct_count = None;
@@ -171,9 +270,100 @@ fn reduce_primitive<
     })
 }
 
+/// Truncates `value` to at most `length` bytes such that the result is smaller or equal to it.
+/// UTF-8 values are truncated at a character boundary.
+fn truncate_min(mut value: Vec<u8>, length: usize) -> Vec<u8> {
+    if value.len() <= length {
+        return value;
+    }
+    let end = match std::str::from_utf8(&value) {
+        Ok(string) => (0..=length)
+            .rev()
+            .find(|end| string.is_char_boundary(*end))
+            .unwrap_or(0),
+        Err(_) => length,
+    };
+    value.truncate(end);
+    value
+}
+
+/// Truncates `value` to at most `length` bytes and increments it such that the result is
+/// larger than it, or returns `None` when no such value exists (e.g. all bytes are `0xFF`).
+/// UTF-8 values are truncated at a character boundary and their last character incremented.
+fn truncate_max(value: &[u8], length: usize) -> Option<Vec<u8>> {
+    if value.len() <= length {
+        return Some(value.to_vec());
+    }
+    if let Ok(string) = std::str::from_utf8(value) {
+        let mut prefix =
+            String::from_utf8(truncate_min(string.as_bytes().to_vec(), length)).ok()?;
+        while let Some(last) = prefix.pop() {
+            // code points are ordered as their UTF-8 bytes
+            let next = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32);
+            if let Some(next) = next {
+                if prefix.len() + next.len_utf8() <= length {
+                    prefix.push(next);
+                    return Some(prefix.into_bytes());
+                }
+            }
+        }
+        return None;
+    }
+    let mut prefix = value[..length].to_vec();
+    while let Some(last) = prefix.pop() {
+        if last < u8::MAX {
+            prefix.push(last + 1);
+            return Some(prefix);
+        }
+    }
+    None
+}
+
+/// Whether the min and max of the statistics of a column of `primitive_type` can be truncated
+fn is_truncatable(primitive_type: &PrimitiveType) -> bool {
+    // FLOAT16 and decimals are not ordered lexicographically
+    sort_order(primitive_type) == SortOrder::Unsigned
+        && primitive_type.logical_type != Some(PrimitiveLogicalType::Float16)
+}
+
+/// Serializes `statistics`, truncating the min and max of binary columns to `length` bytes
+/// and declaring whether they are exact (see [`super::WriteOptions::statistics_truncate_length`]).
+pub(super) fn serialize_truncated(
+    statistics: &dyn Statistics,
+    length: Option<usize>,
+) -> ParquetStatistics {
+    let mut serialized = serialize_statistics(statistics);
+    let primitive_type = match statistics.physical_type() {
+        PhysicalType::ByteArray => statistics
+            .as_any()
+            .downcast_ref::<BinaryStatistics>()
+            .map(|statistics| &statistics.primitive_type),
+        PhysicalType::FixedLenByteArray(_) => statistics
+            .as_any()
+            .downcast_ref::<FixedLenStatistics>()
+            .map(|statistics| &statistics.primitive_type),
+        _ => None,
+    };
+    if let (Some(length), Some(primitive_type)) = (length, primitive_type) {
+        if is_truncatable(primitive_type) {
+            if let Some(value) = serialized.min_value.take() {
+                let truncated = truncate_min(value.clone(), length);
+                serialized.is_min_value_exact = Some(truncated == value);
+                serialized.min_value = Some(truncated);
+            }
+            if let Some(value) = serialized.max_value.take() {
+                let truncated = truncate_max(&value, length).unwrap_or_else(|| value.clone());
+                serialized.is_max_value_exact = Some(truncated == value);
+                serialized.max_value = Some(truncated);
+            }
+        }
+    }
+    serialized
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::schema::types::PrimitiveType;
+    use crate::schema::types::{IntegerType, PrimitiveType};
 
     use super::*;
 
@@ -321,4 +511,133 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn unsigned() -> Result<()> {
+        let mut primitive_type =
+            PrimitiveType::from_physical("bla".to_string(), PhysicalType::Int32);
+        primitive_type.logical_type = Some(PrimitiveLogicalType::Integer(IntegerType::UInt32));
+        let stats = |min: i32, max: i32| PrimitiveStatistics {
+            primitive_type: primitive_type.clone(),
+            null_count: Some(0),
+            distinct_count: None,
+            min_value: Some(min),
+            max_value: Some(max),
+        };
+        // -1 is u32::MAX
+        let iter = [stats(1, 2), stats(-2, -1)]
+            .into_iter()
+            .map(|x| Some(Arc::new(x) as Arc<dyn Statistics>))
+            .collect::<Vec<_>>();
+        let a = reduce(&iter.iter().collect::<Vec<_>>())?.unwrap();
+        assert_eq!(
+            a.as_any().downcast_ref::<PrimitiveStatistics<i32>>(),
+            Some(&stats(1, -1))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ord_bytes() {
+        // a prefix is smaller
+        let max = ord_binary(b"ab".to_vec(), b"abc".to_vec(), true, SortOrder::Unsigned);
+        assert_eq!(max, b"abc");
+        let min = ord_binary(b"abc".to_vec(), b"ab".to_vec(), false, SortOrder::Unsigned);
+        assert_eq!(min, b"ab");
+        // bytes are unsigned
+        let max = ord_binary(vec![0x80], vec![0x7F], true, SortOrder::Unsigned);
+        assert_eq!(max, vec![0x80]);
+
+        // decimals are signed and of any length: -256 < -1 < 1 < 256
+        let values = [vec![0xFF, 0x00], vec![0xFF], vec![0x01], vec![0x01, 0x00]];
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate() {
+                assert_eq!(ord_signed_bytes(a, b), i.cmp(&j));
+            }
+        }
+        let min = ord_binary(vec![0x01], vec![0xFF, 0x00], false, SortOrder::Signed);
+        assert_eq!(min, vec![0xFF, 0x00]);
+    }
+
+    #[test]
+    fn float16() -> Result<()> {
+        let mut primitive_type =
+            PrimitiveType::from_physical("bla".to_string(), PhysicalType::FixedLenByteArray(2));
+        primitive_type.logical_type = Some(PrimitiveLogicalType::Float16);
+        // 0x3c00 = 1.0, 0xc000 = -2.0, 0x7e00 = NaN, 0x0000 = +0, 0x8000 = -0
+        let stats = |min: u16, max: u16| FixedLenStatistics {
+            primitive_type: primitive_type.clone(),
+            null_count: Some(0),
+            distinct_count: None,
+            min_value: Some(min.to_le_bytes().to_vec()),
+            max_value: Some(max.to_le_bytes().to_vec()),
+        };
+        let iter = [
+            stats(0x0000, 0x3c00),
+            stats(0x7e00, 0x7e00),
+            stats(0x8000, 0x8000),
+        ];
+        let a = reduce_fix_len_binary(iter.iter());
+        assert_eq!(a, stats(0x8000, 0x3c00));
+
+        let iter = [stats(0x3c00, 0x3c00), stats(0xc000, 0x0000)];
+        let a = reduce_fix_len_binary(iter.iter());
+        // byte-wise, 0xc000 (-2.0) would be the max
+        assert_eq!(a, stats(0xc000, 0x3c00));
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncate() {
+        assert_eq!(truncate_min(b"abcdef".to_vec(), 3), b"abc");
+        assert_eq!(truncate_min(b"ab".to_vec(), 3), b"ab");
+        assert_eq!(truncate_max(b"abcdef", 3), Some(b"abd".to_vec()));
+        assert_eq!(truncate_max(b"ab", 3), Some(b"ab".to_vec()));
+
+        // non UTF-8 bytes are truncated byte-wise and carried over 0xFF
+        assert_eq!(truncate_min(vec![1, 255, 255, 2], 3), vec![1, 255, 255]);
+        assert_eq!(truncate_max(&[1, 255, 255, 2], 3), Some(vec![2]));
+        assert_eq!(truncate_max(&[255, 255, 255, 255], 3), None);
+
+        // "é" has 2 bytes: UTF-8 values are truncated at character boundaries
+        assert_eq!(truncate_min("aéb".as_bytes().to_vec(), 2), b"a");
+        assert_eq!(truncate_max("aéb".as_bytes(), 2), Some(b"b".to_vec()));
+        assert_eq!(
+            truncate_max("aéb".as_bytes(), 3),
+            Some("aê".as_bytes().to_vec())
+        );
+        // the surrogates are not characters
+        let value = "\u{d7ff}\u{d7ff}";
+        assert_eq!(
+            truncate_max(value.as_bytes(), 3),
+            Some("\u{e000}".as_bytes().to_vec())
+        );
+
+        // decimals are not ordered lexicographically
+        let mut primitive_type =
+            PrimitiveType::from_physical("bla".to_string(), PhysicalType::FixedLenByteArray(6));
+        let statistics = |primitive_type: &PrimitiveType| FixedLenStatistics {
+            primitive_type: primitive_type.clone(),
+            null_count: Some(0),
+            distinct_count: None,
+            min_value: Some(vec![0, 1, 2, 3, 4, 5]),
+            max_value: Some(vec![0, 1, 2, 3, 4, 6]),
+        };
+        let truncated = serialize_truncated(&statistics(&primitive_type), Some(4));
+        assert_eq!(truncated.min_value, Some(vec![0, 1, 2, 3]));
+        assert_eq!(truncated.max_value, Some(vec![0, 1, 2, 4]));
+        assert_eq!(truncated.is_min_value_exact, Some(false));
+        assert_eq!(truncated.is_max_value_exact, Some(false));
+
+        let truncated = serialize_truncated(&statistics(&primitive_type), Some(6));
+        assert_eq!(truncated.is_min_value_exact, Some(true));
+        assert_eq!(truncated.is_max_value_exact, Some(true));
+
+        primitive_type.logical_type = Some(PrimitiveLogicalType::Decimal(12, 2));
+        let truncated = serialize_truncated(&statistics(&primitive_type), Some(4));
+        assert_eq!(truncated.min_value, Some(vec![0, 1, 2, 3, 4, 5]));
+        assert_eq!(truncated.max_value, Some(vec![0, 1, 2, 3, 4, 6]));
+        assert_eq!(truncated.is_min_value_exact, None);
+    }
 }
@@ -4,16 +4,22 @@ use futures::{AsyncWrite, AsyncWriteExt};
 
 use parquet_format_safe::{thrift::protocol::TCompactOutputStreamProtocol, FileMetaData, RowGroup};
 
+use crate::write::bloom_filter::{set_bloom_filter, write_bloom_filter_async};
+use crate::write::encryption::{column_encryptor, FileEncryptor};
 use crate::write::indexes::{write_column_index_async, write_offset_index_async};
 use crate::write::page::PageWriteSpec;
 use crate::write::State;
 use crate::{
     error::{Error, Result},
-    metadata::{KeyValue, SchemaDescriptor},
+    metadata::{KeyValue, SchemaDescriptor, SortingColumn},
     FOOTER_SIZE, PARQUET_MAGIC,
 };
 
-use super::{row_group::write_row_group_async, RowGroupIter, WriteOptions};
+use super::file::column_orders;
+use super::row_group::{
+    check_sorted, check_sorting_columns, set_sorting_columns, write_row_group_async,
+};
+use super::{RowGroupIter, WriteOptions};
 
 async fn start_file<W: AsyncWrite + Unpin>(writer: &mut W) -> Result<u64> {
     writer.write_all(&PARQUET_MAGIC).await?;
@@ -53,6 +59,13 @@ pub struct FileStreamer<W: AsyncWrite + Unpin + Send> {
     offset: u64,
     row_groups: Vec<RowGroup>,
     page_specs: Vec<Vec<Vec<PageWriteSpec>>>,
+    // the bitsets of the bloom filters of each column of each row group
+    bloom_filters: Vec<Vec<Option<Vec<u8>>>>,
+    // the sorting columns of the row groups that are written
+    sorting_columns: Option<Vec<SortingColumn>>,
+    // whether the pages of the row groups are verified to be sorted as declared
+    verify_sorting_columns: bool,
+    encryptor: Option<FileEncryptor>,
     /// Used to store the current state for writing the file
     state: State,
 }
@@ -86,10 +99,33 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
             offset: 0,
             row_groups: vec![],
             page_specs: vec![],
+            bloom_filters: vec![],
+            sorting_columns: None,
+            verify_sorting_columns: false,
+            encryptor: None,
             state: State::Initialised,
         }
     }
 
+    /// Encrypts the file according to `properties`
+    /// (see [`crate::write::FileWriter::with_encryption`]).
+    /// # Errors
+    /// Errors iff data has already been written to the file or `properties` are invalid
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn with_encryption(
+        mut self,
+        properties: crate::encryption::FileEncryptionProperties,
+    ) -> Result<Self> {
+        if self.offset != 0 {
+            return Err(Error::InvalidParameter(
+                "Encryption must be set before writing to the file".to_string(),
+            ));
+        }
+        self.encryptor = Some(FileEncryptor::try_new(properties, &self.schema)?);
+        Ok(self)
+    }
+
     /// Writes the header of the file.
     ///
     /// This is automatically called by [`Self::write`] if not called following [`Self::new`].
@@ -114,28 +150,78 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
         Error: From<E>,
         E: std::error::Error,
     {
+        self.state.check_not_errored()?;
         if self.offset == 0 {
             self.start().await?;
         }
 
         let ordinal = self.row_groups.len();
-        let (group, specs, size) = write_row_group_async(
+        let (mut group, specs, size) = write_row_group_async(
             &mut self.writer,
             self.offset,
             self.schema.columns(),
             row_group,
             ordinal,
+            &self.options,
+            self.encryptor.as_ref(),
         )
         .await?;
         self.offset += size;
+        if let Some(sorting_columns) = &self.sorting_columns {
+            // the pages are already written: the file can no longer be completed without them
+            if self.verify_sorting_columns {
+                if let Err(error) = check_sorted(&specs, self.schema.columns(), sorting_columns) {
+                    self.state = State::Errored;
+                    return Err(error);
+                }
+            }
+            set_sorting_columns(&mut group, sorting_columns);
+        }
         self.row_groups.push(group);
         self.page_specs.push(specs);
+        self.bloom_filters
+            .push(vec![None; self.schema.columns().len()]);
+        Ok(())
+    }
+
+    /// Declares the columns by which the rows of the row groups written after this call are
+    /// sorted (see [`crate::write::FileWriter::set_sorting_columns`]).
+    /// # Errors
+    /// Errors iff a sorting column is not a column of the schema
+    pub fn set_sorting_columns(
+        &mut self,
+        sorting_columns: Option<Vec<SortingColumn>>,
+        verify: bool,
+    ) -> Result<()> {
+        if let Some(sorting_columns) = &sorting_columns {
+            check_sorting_columns(self.schema.columns(), sorting_columns)?;
+        }
+        self.sorting_columns = sorting_columns;
+        self.verify_sorting_columns = verify;
         Ok(())
     }
 
+    /// Sets the bloom filter of the column `column` of the row group `row_group`, which must
+    /// have already been written. The filter is written before the footer and its offset
+    /// is recorded in the column's metadata.
+    ///
+    /// The `bitset` is usually initialized with [`crate::bloom_filter::optimal_num_bytes`]
+    /// and populated with [`crate::bloom_filter::insert`].
+    /// # Errors
+    /// Errors iff the row group or column does not exist or `bitset` is not a valid split block bitset.
+    pub fn set_bloom_filter(
+        &mut self,
+        row_group: usize,
+        column: usize,
+        bitset: Vec<u8>,
+    ) -> Result<()> {
+        set_bloom_filter(&mut self.bloom_filters, row_group, column, bitset)
+    }
+
     /// Writes the footer of the parquet file. Returns the total size of the file and the
     /// underlying writer.
     pub async fn end(&mut self, key_value_metadata: Option<Vec<KeyValue>>) -> Result<u64> {
+        self.state.check_not_errored()?;
         if self.offset == 0 {
             self.start().await?;
         }
@@ -148,13 +234,58 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
         // compute file stats
         let num_rows = self.row_groups.iter().map(|group| group.num_rows).sum();
 
+        // write bloom filters
+        for (row_group, (group, bitsets)) in self
+            .row_groups
+            .iter_mut()
+            .zip(self.bloom_filters.iter())
+            .enumerate()
+        {
+            let columns = group.columns.iter_mut().zip(bitsets.iter());
+            for (index, ((column, bitset), descriptor)) in
+                columns.zip(self.schema.columns()).enumerate()
+            {
+                if let Some(bitset) = bitset {
+                    let encryptor =
+                        column_encryptor(self.encryptor.as_ref(), row_group, index, descriptor)?;
+                    let metadata = column
+                        .meta_data
+                        .as_mut()
+                        .ok_or_else(|| Error::oos("Column chunks must contain metadata"))?;
+                    let length =
+                        write_bloom_filter_async(&mut self.writer, bitset, encryptor.as_ref())
+                            .await?;
+                    metadata.bloom_filter_offset = Some(self.offset as i64);
+                    metadata.bloom_filter_length = Some(length.try_into()?);
+                    self.offset += length;
+                }
+            }
+        }
+
         if self.options.write_statistics {
             // write column indexes (require page statistics)
-            for (group, pages) in self.row_groups.iter_mut().zip(self.page_specs.iter()) {
-                for (column, pages) in group.columns.iter_mut().zip(pages.iter()) {
+            for (row_group, (group, pages)) in self
+                .row_groups
+                .iter_mut()
+                .zip(self.page_specs.iter())
+                .enumerate()
+            {
+                let columns = group.columns.iter_mut().zip(pages.iter());
+                for (index, ((column, pages), descriptor)) in
+                    columns.zip(self.schema.columns()).enumerate()
+                {
+                    let encryptor =
+                        column_encryptor(self.encryptor.as_ref(), row_group, index, descriptor)?;
                     let offset = self.offset;
                     column.column_index_offset = Some(offset as i64);
-                    self.offset += write_column_index_async(&mut self.writer, pages).await?;
+                    self.offset += write_column_index_async(
+                        &mut self.writer,
+                        pages,
+                        &descriptor.descriptor.primitive_type,
+                        self.options.statistics_truncate_length,
+                        encryptor.as_ref(),
+                    )
+                    .await?;
                     let length = self.offset - offset;
                     column.column_index_length = Some(length as i32);
                 }
@@ -162,28 +293,49 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
         };
 
         // write offset index
-        for (group, pages) in self.row_groups.iter_mut().zip(self.page_specs.iter()) {
-            for (column, pages) in group.columns.iter_mut().zip(pages.iter()) {
+        for (row_group, (group, pages)) in self
+            .row_groups
+            .iter_mut()
+            .zip(self.page_specs.iter())
+            .enumerate()
+        {
+            let columns = group.columns.iter_mut().zip(pages.iter());
+            for (index, ((column, pages), descriptor)) in
+                columns.zip(self.schema.columns()).enumerate()
+            {
+                let encryptor =
+                    column_encryptor(self.encryptor.as_ref(), row_group, index, descriptor)?;
                 let offset = self.offset;
                 column.offset_index_offset = Some(offset as i64);
-                self.offset += write_offset_index_async(&mut self.writer, pages).await?;
+                self.offset +=
+                    write_offset_index_async(&mut self.writer, pages, encryptor.as_ref()).await?;
                 column.offset_index_length = Some((self.offset - offset) as i32);
             }
         }
 
-        let metadata = FileMetaData::new(
+        let mut metadata = FileMetaData::new(
             self.options.version.into(),
             self.schema.clone().into_thrift(),
             num_rows,
             self.row_groups.clone(),
             key_value_metadata,
             self.created_by.clone(),
-            None,
+            Some(column_orders(&self.schema)),
             None,
             None,
         );
 
-        let len = end_file(&mut self.writer, metadata).await?;
+        let len = match &self.encryptor {
+            Some(encryptor) => {
+                encryptor.encrypt_column_metadata(&mut metadata.row_groups)?;
+                let mut footer = vec![];
+                let len = encryptor.write_footer(&mut footer, &mut metadata)?;
+                self.writer.write_all(&footer).await?;
+                self.writer.flush().await?;
+                len
+            }
+            None => end_file(&mut self.writer, metadata).await?,
+        };
         Ok(self.offset + len)
     }
 
@@ -0,0 +1,187 @@
+use std::io::Cursor;
+
+use parquet2::compression::CompressionOptions;
+use parquet2::encoding::{byte_stream_split, hybrid_rle::encode_bool, Encoding};
+use parquet2::error::Result;
+use parquet2::metadata::{Descriptor, SchemaDescriptor};
+use parquet2::page::{DataPage, DataPageHeader, DataPageHeaderV1, Page};
+use parquet2::read::{decompress, get_page_iterator, read_metadata, rows, Field};
+use parquet2::write::{
+    Compressor, DynIter, DynStreamingIterator, FileWriter, Version, WriteOptions,
+};
+
+fn schema() -> Result<SchemaDescriptor> {
+    SchemaDescriptor::try_from_message(
+        "message schema {
+            OPTIONAL FLOAT a;
+            REQUIRED FLOAT b;
+            OPTIONAL DOUBLE c;
+            REQUIRED DOUBLE d;
+            OPTIONAL FIXED_LEN_BYTE_ARRAY(3) e;
+            REQUIRED FIXED_LEN_BYTE_ARRAY(3) f;
+        }",
+    )
+}
+
+/// A data page of `values` of `size` bytes, encoded with BYTE_STREAM_SPLIT
+fn page(values: &[Option<Vec<u8>>], size: usize, descriptor: &Descriptor) -> Result<DataPage> {
+    let mut buffer = vec![];
+    if descriptor.max_def_level > 0 {
+        let mut levels = vec![];
+        encode_bool(&mut levels, values.iter().map(|value| value.is_some()))?;
+        buffer.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(&levels);
+    }
+    let plain = values
+        .iter()
+        .flatten()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    byte_stream_split::encode(&plain, size, &mut buffer)?;
+
+    let header = DataPageHeaderV1 {
+        num_values: values.len() as i32,
+        encoding: Encoding::ByteStreamSplit.into(),
+        definition_level_encoding: Encoding::Rle.into(),
+        repetition_level_encoding: Encoding::Rle.into(),
+        statistics: None,
+    };
+    Ok(DataPage::new(
+        DataPageHeader::V1(header),
+        buffer,
+        descriptor.clone(),
+        Some(values.len()),
+    ))
+}
+
+fn floats(required: bool) -> Vec<Option<f32>> {
+    (0..10)
+        .map(|i| (required || i % 3 != 1).then_some(i as f32 * 1.5 - 3.0))
+        .collect()
+}
+
+fn doubles(required: bool) -> Vec<Option<f64>> {
+    (0..10)
+        .map(|i| (required || i % 3 != 1).then_some(i as f64 * 1e10 - 0.5))
+        .collect()
+}
+
+fn fixed_len(required: bool) -> Vec<Option<Vec<u8>>> {
+    (0..10u8)
+        .map(|i| (required || i % 3 != 1).then(|| vec![i, 255 - i, i * 7]))
+        .collect()
+}
+
+fn to_bytes<T: parquet2::types::NativeType>(values: &[Option<T>]) -> Vec<Option<Vec<u8>>> {
+    values
+        .iter()
+        .map(|value| value.map(|value| value.to_le_bytes().as_ref().to_vec()))
+        .collect()
+}
+
+/// The pages of the columns of [`schema`]
+fn pages(schema: &SchemaDescriptor) -> Result<Vec<DataPage>> {
+    let columns = schema.columns();
+    Ok(vec![
+        page(&to_bytes(&floats(false)), 4, &columns[0].descriptor)?,
+        page(&to_bytes(&floats(true)), 4, &columns[1].descriptor)?,
+        page(&to_bytes(&doubles(false)), 8, &columns[2].descriptor)?,
+        page(&to_bytes(&doubles(true)), 8, &columns[3].descriptor)?,
+        page(&fixed_len(false), 3, &columns[4].descriptor)?,
+        page(&fixed_len(true), 3, &columns[5].descriptor)?,
+    ])
+}
+
+/// A file with a row group of the pages of [`schema`]
+fn write() -> Result<Vec<u8>> {
+    let schema = schema()?;
+    let options = WriteOptions {
+        write_statistics: false,
+        version: Version::V1,
+        ..Default::default()
+    };
+    let columns = pages(&schema)?.into_iter().map(|page| {
+        Ok(DynStreamingIterator::new(Compressor::new_from_vec(
+            DynIter::new(std::iter::once(Ok(Page::Data(page)))),
+            CompressionOptions::Uncompressed,
+            vec![],
+        )))
+    });
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn read_pages() -> Result<()> {
+    let mut reader = Cursor::new(write()?);
+    let metadata = read_metadata(&mut reader)?;
+    let pages = metadata.row_groups[0]
+        .columns()
+        .iter()
+        .map(|column| {
+            let mut pages = get_page_iterator(column, &mut reader, None, vec![], usize::MAX)?;
+            match decompress(pages.next().unwrap()?, &mut vec![])? {
+                Page::Data(page) => Ok(page),
+                Page::Dict(_) => unreachable!(),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    assert_eq!(
+        super::primitive::page_to_vec(&pages[0], None)?,
+        floats(false)
+    );
+    assert_eq!(
+        super::primitive::page_to_vec(&pages[1], None)?,
+        floats(true)
+    );
+    assert_eq!(
+        super::primitive::page_to_vec(&pages[2], None)?,
+        doubles(false)
+    );
+    assert_eq!(
+        super::primitive::page_to_vec(&pages[3], None)?,
+        doubles(true)
+    );
+    assert_eq!(
+        super::fixed_binary::page_to_vec(&pages[4], None)?,
+        fixed_len(false)
+    );
+    assert_eq!(
+        super::fixed_binary::page_to_vec(&pages[5], None)?,
+        fixed_len(true)
+    );
+    Ok(())
+}
+
+#[test]
+fn read_rows() -> Result<()> {
+    let mut reader = Cursor::new(write()?);
+    let metadata = read_metadata(&mut reader)?;
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+
+    let field = |value: Option<Field>| value.unwrap_or(Field::Null);
+    let expected = (0..10)
+        .map(|i| {
+            vec![
+                ("a".to_string(), field(floats(false)[i].map(Field::Float))),
+                ("b".to_string(), field(floats(true)[i].map(Field::Float))),
+                ("c".to_string(), field(doubles(false)[i].map(Field::Double))),
+                ("d".to_string(), field(doubles(true)[i].map(Field::Double))),
+                (
+                    "e".to_string(),
+                    field(fixed_len(false)[i].clone().map(Field::Bytes)),
+                ),
+                (
+                    "f".to_string(),
+                    field(fixed_len(true)[i].clone().map(Field::Bytes)),
+                ),
+            ]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(result, expected);
+    Ok(())
+}
@@ -1,13 +1,37 @@
-use parquet2::{deserialize::FixedLenBinaryPageState, error::Result, page::DataPage};
+use parquet2::{
+    deserialize::{FixedLenBinaryPageState, Float16Values},
+    encoding::byte_stream_split::{self, FixedLenDecoder},
+    encoding::hybrid_rle::{encode_bool, encode_u32},
+    encoding::Encoding,
+    error::Result,
+    metadata::{Descriptor, SchemaDescriptor},
+    page::{DataPage, DataPageHeader, DataPageHeaderV1},
+    schema::types::PhysicalType,
+};
 
 use super::dictionary::FixedLenByteArrayPageDict;
 use super::utils::deserialize_optional;
 
+/// Decodes the values of `decoder`, of `size` bytes each, into owned values
+fn decode_owned(
+    mut decoder: FixedLenDecoder<'_>,
+    size: usize,
+) -> impl Iterator<Item = Vec<u8>> + '_ {
+    std::iter::from_fn(move || {
+        let mut value = vec![0; size];
+        decoder.decode_next(&mut value).then_some(value)
+    })
+}
+
 pub fn page_to_vec(
     page: &DataPage,
     dict: Option<&FixedLenByteArrayPageDict>,
 ) -> Result<Vec<Option<Vec<u8>>>> {
     assert_eq!(page.descriptor.max_rep_level, 0);
+    let size = match page.descriptor.primitive_type.physical_type {
+        PhysicalType::FixedLenByteArray(size) => size,
+        _ => unreachable!(),
+    };
 
     let state = FixedLenBinaryPageState::try_new(page, dict)?;
 
@@ -28,5 +52,99 @@ pub fn page_to_vec(
                 .map(|x| x.and_then(|x| dict.dict.value(x as usize).map(|x| x.to_vec())));
             deserialize_optional(validity, values)
         }
+        FixedLenBinaryPageState::OptionalByteStreamSplit(validity, values) => {
+            deserialize_optional(validity, decode_owned(values, size).map(Ok))
+        }
+        FixedLenBinaryPageState::RequiredByteStreamSplit(values) => {
+            Ok(decode_owned(values, size).map(Some).collect())
+        }
+    }
+}
+
+/// A data page of `values` encoded with `encoding`: its indexes in `dict`, for dictionary
+/// encodings, or its bytes in little-endian otherwise.
+fn float16_page(
+    values: &[Option<u16>],
+    dict: &[u16],
+    encoding: Encoding,
+    descriptor: &Descriptor,
+) -> Result<DataPage> {
+    let mut buffer = vec![];
+    if descriptor.max_def_level > 0 {
+        let mut levels = vec![];
+        encode_bool(&mut levels, values.iter().map(|value| value.is_some()))?;
+        buffer.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(&levels);
+    }
+    let plain = values
+        .iter()
+        .flatten()
+        .flat_map(|value| value.to_le_bytes())
+        .collect::<Vec<_>>();
+    match encoding {
+        Encoding::Plain => buffer.extend_from_slice(&plain),
+        Encoding::ByteStreamSplit => byte_stream_split::encode(&plain, 2, &mut buffer)?,
+        _ => {
+            let indexes = values
+                .iter()
+                .flatten()
+                .map(|value| dict.iter().position(|x| x == value).unwrap() as u32)
+                .collect::<Vec<_>>();
+            buffer.push(2);
+            encode_u32(&mut buffer, indexes.into_iter(), 2)?;
+        }
+    }
+
+    let header = DataPageHeaderV1 {
+        num_values: values.len() as i32,
+        encoding: encoding.into(),
+        definition_level_encoding: Encoding::Rle.into(),
+        repetition_level_encoding: Encoding::Rle.into(),
+        statistics: None,
+    };
+    Ok(DataPage::new(
+        DataPageHeader::V1(header),
+        buffer,
+        descriptor.clone(),
+        Some(values.len()),
+    ))
+}
+
+#[test]
+fn float16() -> Result<()> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema {
+            OPTIONAL FIXED_LEN_BYTE_ARRAY(2) a (FLOAT16);
+            REQUIRED FIXED_LEN_BYTE_ARRAY(2) b (FLOAT16);
+        }",
+    )?;
+    // 1.0, -2.0, 0.5 and infinity
+    let dict = [0x3c00u16, 0xc000, 0x3800, 0x7c00];
+    let dict_page = dict
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect::<Vec<_>>();
+
+    for encoding in [
+        Encoding::Plain,
+        Encoding::ByteStreamSplit,
+        Encoding::RleDictionary,
+    ] {
+        for (column, required) in schema.columns().iter().zip([false, true]) {
+            let values = (0..10)
+                .map(|i| (required || i % 3 != 1).then_some(dict[i % dict.len()]))
+                .collect::<Vec<_>>();
+            let page = float16_page(&values, &dict, encoding, &column.descriptor)?;
+
+            let (validity, decoded) = Float16Values::try_new(&page, Some(dict_page.as_slice()))?;
+            assert_eq!(validity.is_none(), required);
+            let result = if let Some(validity) = validity {
+                deserialize_optional(validity, decoded)?
+            } else {
+                decoded.map(|x| x.map(Some)).collect::<Result<Vec<_>>>()?
+            };
+            assert_eq!(result, values);
+        }
     }
+    Ok(())
 }
@@ -3,10 +3,12 @@
 /// but OTOH it has no external dependencies and is very familiar to Rust developers.
 mod binary;
 mod boolean;
+mod byte_stream_split;
 mod deserialize;
 mod dictionary;
 mod fixed_binary;
 mod indexes;
+mod predicate;
 mod primitive;
 mod primitive_nested;
 mod struct_;
@@ -265,7 +267,15 @@ pub async fn read_column_async<
         .next()
         .unwrap();
 
-    let pages = get_page_stream(column, reader, vec![], Arc::new(|_, _| true), usize::MAX).await?;
+    let pages = get_page_stream(
+        column,
+        reader,
+        vec![],
+        Arc::new(|_, _| true),
+        usize::MAX,
+        false,
+    )
+    .await?;
 
     let mut statistics = get_field_columns(metadata.row_groups[row_group].columns(), field.name())
         .map(|column_meta| column_meta.statistics().transpose())
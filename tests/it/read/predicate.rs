@@ -0,0 +1,354 @@
+use std::io::Cursor;
+
+use parquet2::error::Result;
+use parquet2::indexes::{
+    BoundaryOrder, ByteIndex, FixedLenByteIndex, Index, Interval, NativeIndex,
+};
+use parquet2::metadata::{ColumnOrder, FileMetaData, SchemaDescriptor, SortOrder};
+use parquet2::predicate::{CompareOp, Predicate};
+use parquet2::read::{
+    filter_row_groups_with_predicate, read_columns_indexes, read_metadata, read_pages_locations,
+    Field,
+};
+use parquet2::schema::types::TimeUnit;
+use parquet2::statistics::PrimitiveStatistics;
+use parquet2::write::{RowWriter, RowWriterOptions, Version, WriteOptions};
+
+// row groups of `row_options.max_rows_per_row_group` rows with ids 0..12
+fn write_file(write_statistics: bool, row_options: RowWriterOptions) -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema {
+            REQUIRED INT64 id;
+            REQUIRED INT32 u (INTEGER(32,false));
+            OPTIONAL BYTE_ARRAY name (STRING);
+            REQUIRED INT32 price (DECIMAL(9,2));
+            REQUIRED DOUBLE x;
+            REQUIRED INT96 ts;
+        }",
+    )?;
+    let options = WriteOptions {
+        write_statistics,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let mut writer = RowWriter::new(Cursor::new(vec![]), schema, options, row_options);
+    for id in 0..12i64 {
+        // the last row group has values that are negative when read as signed
+        let u = if id < 8 {
+            id as u32
+        } else {
+            u32::MAX - id as u32
+        };
+        // the second row group has no names
+        let name = if (4..8).contains(&id) {
+            Field::Null
+        } else {
+            Field::String(format!("a{:02}", id))
+        };
+        writer.write(&vec![
+            ("id".to_string(), Field::Int64(id)),
+            ("u".to_string(), Field::UInt32(u)),
+            ("name".to_string(), name),
+            (
+                "price".to_string(),
+                Field::Decimal {
+                    value: (id as i128 - 6) * 100,
+                    precision: 9,
+                    scale: 2,
+                },
+            ),
+            ("x".to_string(), Field::Double(id as f64)),
+            (
+                "ts".to_string(),
+                Field::Timestamp {
+                    value: id,
+                    unit: TimeUnit::Nanoseconds,
+                    is_adjusted_to_utc: false,
+                },
+            ),
+        ])?;
+    }
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+// 3 row groups of 4 rows with ids 0..12
+fn write(write_statistics: bool) -> Result<FileMetaData> {
+    let row_options = RowWriterOptions {
+        max_rows_per_row_group: 4,
+        ..Default::default()
+    };
+    let mut reader = Cursor::new(write_file(write_statistics, row_options)?);
+    read_metadata(&mut reader)
+}
+
+fn boundary_order(index: &dyn Index) -> BoundaryOrder {
+    let index = index.as_any();
+    if let Some(index) = index.downcast_ref::<NativeIndex<i32>>() {
+        index.boundary_order
+    } else if let Some(index) = index.downcast_ref::<NativeIndex<i64>>() {
+        index.boundary_order
+    } else if let Some(index) = index.downcast_ref::<NativeIndex<f64>>() {
+        index.boundary_order
+    } else if let Some(index) = index.downcast_ref::<NativeIndex<[u32; 3]>>() {
+        index.boundary_order
+    } else if let Some(index) = index.downcast_ref::<ByteIndex>() {
+        index.boundary_order
+    } else {
+        index
+            .downcast_ref::<FixedLenByteIndex>()
+            .unwrap()
+            .boundary_order
+    }
+}
+
+// the ordinals of the row groups selected by `predicate`
+fn select(metadata: &FileMetaData, predicate: &Predicate) -> Result<Vec<i64>> {
+    let metadata = filter_row_groups_with_predicate(metadata, predicate)?;
+    Ok(metadata
+        .row_groups
+        .iter()
+        .map(|row_group| row_group.columns()[0].statistics().unwrap().unwrap())
+        .map(|statistics| {
+            statistics
+                .as_any()
+                .downcast_ref::<parquet2::statistics::PrimitiveStatistics<i64>>()
+                .unwrap()
+                .min_value
+                .unwrap()
+                / 4
+        })
+        .collect())
+}
+
+#[test]
+fn comparisons() -> Result<()> {
+    let metadata = write(true)?;
+    let id = |op, value: i64| Predicate::compare(&["id"], op, value);
+
+    assert_eq!(select(&metadata, &id(CompareOp::Gt, 8))?, vec![2]);
+    assert_eq!(select(&metadata, &id(CompareOp::GtEq, 7))?, vec![1, 2]);
+    assert_eq!(select(&metadata, &id(CompareOp::Lt, 4))?, vec![0]);
+    assert_eq!(select(&metadata, &id(CompareOp::LtEq, 4))?, vec![0, 1]);
+    assert_eq!(select(&metadata, &id(CompareOp::Eq, 5))?, vec![1]);
+    assert_eq!(select(&metadata, &id(CompareOp::NotEq, 5))?, vec![0, 1, 2]);
+    assert_eq!(
+        select(&metadata, &id(CompareOp::Eq, 100))?,
+        Vec::<i64>::new()
+    );
+
+    let predicate = Predicate::is_in(&["id"], [1i64, 10]);
+    assert_eq!(select(&metadata, &predicate)?, vec![0, 2]);
+
+    // literals are coerced to the type of the column
+    let predicate = Predicate::compare(&["x"], CompareOp::Gt, 5i32);
+    assert_eq!(select(&metadata, &predicate)?, vec![1, 2]);
+    Ok(())
+}
+
+#[test]
+fn nulls_and_logic() -> Result<()> {
+    let metadata = write(true)?;
+    let id = |op, value: i64| Predicate::compare(&["id"], op, value);
+
+    assert_eq!(select(&metadata, &Predicate::is_null(&["name"]))?, vec![1]);
+    assert_eq!(
+        select(&metadata, &Predicate::is_not_null(&["name"]))?,
+        vec![0, 2]
+    );
+    // comparisons with null are never true
+    let predicate = Predicate::compare(&["name"], CompareOp::NotEq, "a00");
+    assert_eq!(select(&metadata, &predicate)?, vec![0, 2]);
+    assert_eq!(select(&metadata, &!predicate)?, vec![0]);
+
+    assert_eq!(select(&metadata, &!id(CompareOp::Lt, 4))?, vec![1, 2]);
+    let predicate = id(CompareOp::Lt, 2).or(id(CompareOp::Gt, 9));
+    assert_eq!(select(&metadata, &predicate)?, vec![0, 2]);
+    let predicate = id(CompareOp::GtEq, 4).and(Predicate::compare(&["name"], CompareOp::Eq, "a05"));
+    assert_eq!(select(&metadata, &predicate)?, Vec::<i64>::new());
+    Ok(())
+}
+
+#[test]
+fn sort_orders() -> Result<()> {
+    let metadata = write(true)?;
+
+    // unsigned integers
+    let predicate = Predicate::compare(&["u"], CompareOp::Gt, 1000u32);
+    assert_eq!(select(&metadata, &predicate)?, vec![2]);
+
+    // decimals are signed
+    let predicate = Predicate::compare(&["price"], CompareOp::Lt, 0i32);
+    assert_eq!(select(&metadata, &predicate)?, vec![0, 1]);
+
+    // strings are ordered lexicographically
+    let predicate = Predicate::compare(&["name"], CompareOp::GtEq, "a1");
+    assert_eq!(select(&metadata, &predicate)?, vec![2]);
+
+    // the order of INT96 is undefined
+    let predicate = Predicate::compare(&["ts"], CompareOp::Eq, 100i64);
+    assert_eq!(select(&metadata, &predicate)?, vec![0, 1, 2]);
+    Ok(())
+}
+
+#[test]
+fn column_orders() -> Result<()> {
+    // a single row group whose statistics are reduced from the statistics of its pages
+    let row_options = RowWriterOptions {
+        max_page_size: 16,
+        ..Default::default()
+    };
+    let metadata = read_metadata(&mut Cursor::new(write_file(true, row_options)?))?;
+    let sort_orders = (0..6).map(|i| metadata.column_order(i)).collect::<Vec<_>>();
+    use SortOrder::*;
+    assert_eq!(
+        sort_orders,
+        [Signed, Unsigned, Unsigned, Signed, Signed, Undefined]
+            .into_iter()
+            .map(ColumnOrder::TypeDefinedOrder)
+            .collect::<Vec<_>>()
+    );
+
+    // the pages of `u` are reduced as unsigned
+    let statistics = metadata.row_groups[0].columns()[1].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<PrimitiveStatistics<i32>>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(0));
+    assert_eq!(statistics.max_value, Some((u32::MAX - 8) as i32));
+    Ok(())
+}
+
+#[test]
+fn missing_statistics() -> Result<()> {
+    let metadata = write(false)?;
+    let predicate = Predicate::compare(&["id"], CompareOp::Eq, 100i64);
+    let metadata = filter_row_groups_with_predicate(&metadata, &predicate)?;
+    assert_eq!(metadata.row_groups.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn invalid() -> Result<()> {
+    let metadata = write(true)?;
+
+    let predicate = Predicate::compare(&["unknown"], CompareOp::Eq, 1i64);
+    assert!(filter_row_groups_with_predicate(&metadata, &predicate).is_err());
+
+    let predicate = Predicate::compare(&["name"], CompareOp::Eq, 1i64);
+    assert!(filter_row_groups_with_predicate(&metadata, &predicate).is_err());
+    Ok(())
+}
+
+#[test]
+fn pages() -> Result<()> {
+    // a single row group whose pages of `id` have 2 rows and of `name` have 3 rows or more
+    let row_options = RowWriterOptions {
+        max_page_size: 16,
+        ..Default::default()
+    };
+    let mut reader = Cursor::new(write_file(true, row_options)?);
+    let metadata = read_metadata(&mut reader)?;
+    let row_group = &metadata.row_groups[0];
+    let indexes = read_columns_indexes(&mut reader, row_group.columns())?;
+    let locations = read_pages_locations(&mut reader, row_group.columns())?;
+    let select = |predicate: &Predicate| predicate.select_rows(row_group, &indexes, &locations);
+    let id = |op, value: i64| Predicate::compare(&["id"], op, value);
+
+    let predicate = id(CompareOp::GtEq, 5).and(id(CompareOp::Lt, 9));
+    assert_eq!(select(&predicate)?, vec![Interval::new(4, 6)]);
+
+    let name = Predicate::compare(&["name"], CompareOp::Eq, "a01");
+    assert_eq!(
+        select(&predicate.clone().or(name.clone()))?,
+        vec![Interval::new(0, 3), Interval::new(4, 6)]
+    );
+    assert_eq!(select(&predicate.and(name))?, vec![]);
+
+    assert_eq!(select(&!id(CompareOp::Lt, 10))?, vec![Interval::new(10, 2)]);
+    assert_eq!(
+        select(&Predicate::is_null(&["name"]))?,
+        vec![Interval::new(3, 7)]
+    );
+
+    // the pages of each column with the selected rows
+    let pages = id(CompareOp::Gt, 10).select_pages(row_group, &indexes, &locations)?;
+    assert_eq!(pages.len(), row_group.columns().len());
+    assert_eq!(pages[0].len(), 6);
+    assert_eq!(pages[0][5].selected_rows, vec![Interval::new(0, 2)]);
+    assert!(pages[0][..5]
+        .iter()
+        .all(|page| page.selected_rows.is_empty()));
+
+    // without indexes, all rows are selected
+    let metadata = read_metadata(&mut Cursor::new(write_file(false, row_options)?))?;
+    assert_eq!(
+        id(CompareOp::Gt, 10).select_rows(&metadata.row_groups[0], &[], &[])?,
+        vec![Interval::new(0, 12)]
+    );
+    Ok(())
+}
+
+#[test]
+fn ordered_pages() -> Result<()> {
+    let row_options = RowWriterOptions {
+        max_page_size: 16,
+        ..Default::default()
+    };
+    let mut reader = Cursor::new(write_file(true, row_options)?);
+    let metadata = read_metadata(&mut reader)?;
+    let row_group = &metadata.row_groups[0];
+    let indexes = read_columns_indexes(&mut reader, row_group.columns())?;
+
+    // the null pages of `name` are ignored, `u` is ordered as unsigned and the order of `ts`
+    // is undefined
+    let orders = indexes
+        .iter()
+        .map(|index| boundary_order(index.as_ref()))
+        .collect::<Vec<_>>();
+    use BoundaryOrder::*;
+    assert_eq!(
+        orders,
+        vec![Ascending, Ascending, Ascending, Ascending, Ascending, Unordered]
+    );
+
+    // pages of 2 non-null values that decrease from 11 to 0; the third page has rows 4..8
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT64 v; }")?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let mut writer = RowWriter::new(Cursor::new(vec![]), schema, options, row_options);
+    for v in (0..12i64).rev() {
+        // the rows 4 and 5 are null and fill no bytes of a page
+        let value = if v == 6 || v == 7 {
+            Field::Null
+        } else {
+            Field::Int64(v)
+        };
+        writer.write(&vec![("v".to_string(), value)])?;
+    }
+    writer.end(None)?;
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+    let row_group = &metadata.row_groups[0];
+    let indexes = read_columns_indexes(&mut reader, row_group.columns())?;
+    let locations = read_pages_locations(&mut reader, row_group.columns())?;
+    assert_eq!(boundary_order(indexes[0].as_ref()), Descending);
+
+    let select = |predicate: &Predicate| predicate.select_rows(row_group, &indexes, &locations);
+    let v = |op, value: i64| Predicate::compare(&["v"], op, value);
+    assert_eq!(select(&v(CompareOp::Eq, 8))?, vec![Interval::new(2, 2)]);
+    assert_eq!(select(&v(CompareOp::Gt, 8))?, vec![Interval::new(0, 4)]);
+    assert_eq!(select(&v(CompareOp::LtEq, 5))?, vec![Interval::new(4, 8)]);
+    assert_eq!(select(&v(CompareOp::Lt, 0))?, vec![]);
+    assert_eq!(select(&v(CompareOp::NotEq, 3))?, vec![Interval::new(0, 12)]);
+    assert_eq!(select(&!v(CompareOp::GtEq, 2))?, vec![Interval::new(10, 2)]);
+    assert_eq!(
+        select(&Predicate::is_null(&["v"]))?,
+        vec![Interval::new(4, 4)]
+    );
+    Ok(())
+}
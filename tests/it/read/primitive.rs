@@ -107,6 +107,10 @@ pub fn page_to_vec<T: NativeType>(
                     .map(|x| x.and_then(|x| dict.dict.value(x as usize).copied()));
                 deserialize_optional(validity, values)
             }
+            NativePageState::OptionalByteStreamSplit(validity, mut values) => {
+                deserialize_optional(validity, values.by_ref().map(Ok))
+            }
+            NativePageState::RequiredByteStreamSplit(values) => Ok(values.map(Some).collect()),
         },
         PageState::Filtered(state) => match state {
             FilteredPageState::Optional(values) => values.collect(),
@@ -1,5 +1,5 @@
 use parquet2::{
-    encoding::Encoding,
+    encoding::{plain, Encoding},
     metadata::Descriptor,
     page::{DataPage, DataPageHeader, DataPageHeaderV1, Page},
     statistics::{serialize_statistics, BinaryStatistics, Statistics},
@@ -19,8 +19,7 @@ fn unzip_option(array: &[Option<Vec<u8>>]) -> Result<(Vec<u8>, Vec<u8>)> {
     let mut values = vec![];
     let iter = array.iter().map(|value| {
         if let Some(item) = value {
-            values.extend_from_slice(&(item.len() as i32).to_le_bytes());
-            values.extend_from_slice(item.as_ref());
+            plain::encode_binary_value(item, &mut values);
             true
         } else {
             false
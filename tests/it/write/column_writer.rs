@@ -0,0 +1,301 @@
+use std::io::Cursor;
+
+use parquet2::error::{Error, Result};
+use parquet2::metadata::SchemaDescriptor;
+use parquet2::read::{read_metadata, read_pages_locations, rows, Field, Row};
+use parquet2::write::{
+    ColumnValues, ColumnWriter, ColumnWriterOptions, DynIter, FileWriter, Version, WriteOptions,
+};
+
+const NUM_ROWS: usize = 30;
+
+const OPTIONS: WriteOptions = WriteOptions {
+    write_statistics: true,
+    version: Version::V2,
+    write_page_crc: false,
+    statistics_truncate_length: None,
+};
+
+fn schema() -> Result<SchemaDescriptor> {
+    SchemaDescriptor::try_from_message(
+        "message schema {
+            OPTIONAL INT64 id;
+            OPTIONAL group tags (LIST) {
+                REPEATED group list {
+                    OPTIONAL BYTE_ARRAY element (UTF8);
+                }
+            }
+        }",
+    )
+}
+
+fn row(i: usize) -> Row {
+    let id = match i % 5 {
+        0 => Field::Null,
+        _ => Field::Int64(i as i64),
+    };
+    let tags = match i % 4 {
+        0 => Field::Null,
+        1 => Field::List(vec![]),
+        n => Field::List(
+            (0..n)
+                .map(|j| match j {
+                    0 if n == 3 => Field::Null,
+                    _ => Field::String(format!("tag {} {}", i, j)),
+                })
+                .collect(),
+        ),
+    };
+    vec![("id".to_string(), id), ("tags".to_string(), tags)]
+}
+
+/// The definition levels, repetition levels and non-null values of `tags` in `rows`
+fn tags(rows: &[Row]) -> (Vec<u32>, Vec<u32>, Vec<Vec<u8>>) {
+    let mut def_levels = vec![];
+    let mut rep_levels = vec![];
+    let mut values = vec![];
+    for row in rows {
+        match &row[1].1 {
+            Field::List(items) if !items.is_empty() => {
+                for (j, item) in items.iter().enumerate() {
+                    rep_levels.push(u32::from(j > 0));
+                    match item {
+                        Field::String(value) => {
+                            def_levels.push(3);
+                            values.push(value.as_bytes().to_vec());
+                        }
+                        _ => def_levels.push(2),
+                    }
+                }
+            }
+            Field::List(_) => {
+                def_levels.push(1);
+                rep_levels.push(0);
+            }
+            _ => {
+                def_levels.push(0);
+                rep_levels.push(0);
+            }
+        }
+    }
+    (def_levels, rep_levels, values)
+}
+
+/// The definition levels and non-null values of `id` in `rows`
+fn ids(rows: &[Row]) -> (Vec<u32>, Vec<i64>) {
+    rows.iter()
+        .map(|row| match row[0].1 {
+            Field::Int64(value) => (1, Some(value)),
+            _ => (0, None),
+        })
+        .fold(
+            (vec![], vec![]),
+            |(mut levels, mut values), (level, value)| {
+                levels.push(level);
+                values.extend(value);
+                (levels, values)
+            },
+        )
+}
+
+fn writers(options: ColumnWriterOptions) -> Result<Vec<ColumnWriter>> {
+    schema()?
+        .columns()
+        .iter()
+        .map(|column| ColumnWriter::try_new(column.descriptor.clone(), OPTIONS, options))
+        .collect()
+}
+
+fn write_row_group(
+    writer: &mut FileWriter<Cursor<Vec<u8>>>,
+    columns: &mut [ColumnWriter],
+) -> Result<()> {
+    let pages = columns
+        .iter_mut()
+        .map(|column| column.finish())
+        .collect::<Vec<_>>();
+    writer.write(DynIter::new(pages.into_iter()))
+}
+
+#[test]
+fn pages() -> Result<()> {
+    let options = ColumnWriterOptions {
+        max_rows_per_page: 4,
+        ..Default::default()
+    };
+    let expected = (0..NUM_ROWS).map(row).collect::<Vec<_>>();
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema()?, OPTIONS, None);
+    let mut columns = writers(options)?;
+
+    let (levels, values) = ids(&expected);
+    columns[0].write(ColumnValues::Int64(&values), Some(&levels), None)?;
+
+    // batches of 5 entries, whose records span multiple batches
+    let (def_levels, rep_levels, values) = tags(&expected);
+    let mut start = 0;
+    for (def_levels, rep_levels) in def_levels.chunks(5).zip(rep_levels.chunks(5)) {
+        let num_values = def_levels.iter().filter(|level| **level == 3).count();
+        columns[1].write(
+            ColumnValues::ByteArray(&values[start..start + num_values]),
+            Some(def_levels),
+            Some(rep_levels),
+        )?;
+        start += num_values;
+    }
+    assert_eq!(columns[1].num_rows(), NUM_ROWS);
+    assert!(!columns[1].is_full());
+
+    write_row_group(&mut writer, &mut columns)?;
+    writer.end(None)?;
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+
+    // pages of 4 rows, regardless of the number of entries of each record
+    let locations = read_pages_locations(&mut reader, metadata.row_groups[0].columns())?;
+    for locations in locations {
+        let first_rows = locations
+            .iter()
+            .map(|location| location.first_row_index)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            first_rows,
+            (0..NUM_ROWS as i64).step_by(4).collect::<Vec<_>>()
+        );
+    }
+
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn page_size() -> Result<()> {
+    let options = ColumnWriterOptions {
+        dictionary: false,
+        max_page_size: 20,
+        ..Default::default()
+    };
+    let expected = (0..NUM_ROWS).map(row).collect::<Vec<_>>();
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema()?, OPTIONS, None);
+    let mut columns = writers(options)?;
+
+    let (levels, values) = ids(&expected);
+    columns[0].write(ColumnValues::Int64(&values), Some(&levels), None)?;
+    let (def_levels, rep_levels, values) = tags(&expected);
+    columns[1].write(
+        ColumnValues::ByteArray(&values),
+        Some(&def_levels),
+        Some(&rep_levels),
+    )?;
+
+    write_row_group(&mut writer, &mut columns)?;
+    writer.end(None)?;
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+
+    // pages of `id` are finished after 3 values of 8 bytes and pages of `tags` after each
+    // record with 2 values of 11 bytes, which are followed by a new page at the next record
+    let locations = read_pages_locations(&mut reader, metadata.row_groups[0].columns())?;
+    let first_rows = locations
+        .iter()
+        .map(|locations| {
+            locations
+                .iter()
+                .map(|location| location.first_row_index)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(first_rows[0], vec![0, 4, 8, 12, 15, 19, 23, 27]);
+    assert_eq!(
+        first_rows[1],
+        vec![0, 3, 4, 7, 8, 11, 12, 15, 16, 19, 20, 23, 24, 27, 28]
+    );
+
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn row_groups() -> Result<()> {
+    let options = ColumnWriterOptions {
+        max_rows_per_row_group: 10,
+        ..Default::default()
+    };
+    let expected = (0..NUM_ROWS).map(row).collect::<Vec<_>>();
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema()?, OPTIONS, None);
+    let mut columns = writers(options)?;
+
+    for row in expected.chunks(3) {
+        let (levels, values) = ids(row);
+        columns[0].write(ColumnValues::Int64(&values), Some(&levels), None)?;
+        let (def_levels, rep_levels, values) = tags(row);
+        columns[1].write(
+            ColumnValues::ByteArray(&values),
+            Some(&def_levels),
+            Some(&rep_levels),
+        )?;
+        if columns.iter().any(|column| column.is_full()) {
+            write_row_group(&mut writer, &mut columns)?;
+        }
+    }
+    write_row_group(&mut writer, &mut columns)?;
+    writer.end(None)?;
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+
+    let num_rows = metadata
+        .row_groups
+        .iter()
+        .map(|row_group| row_group.num_rows())
+        .collect::<Vec<_>>();
+    assert_eq!(num_rows, vec![12, 12, 6]);
+
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn invalid() -> Result<()> {
+    let mut columns = writers(ColumnWriterOptions::default())?;
+    let is_invalid = |result: Result<()>| matches!(result, Err(Error::InvalidParameter(_)));
+
+    // wrong physical type
+    assert!(is_invalid(columns[0].write(
+        ColumnValues::Int32(&[1]),
+        Some(&[1]),
+        None
+    )));
+    // missing levels
+    assert!(is_invalid(columns[0].write(
+        ColumnValues::Int64(&[1]),
+        None,
+        None
+    )));
+    assert!(is_invalid(columns[1].write(
+        ColumnValues::ByteArray(&[]),
+        Some(&[0]),
+        None
+    )));
+    // levels above the maximum
+    assert!(is_invalid(columns[0].write(
+        ColumnValues::Int64(&[]),
+        Some(&[2]),
+        None
+    )));
+    // more values than non-null entries
+    assert!(is_invalid(columns[0].write(
+        ColumnValues::Int64(&[1, 2]),
+        Some(&[1, 0]),
+        None
+    )));
+    // the first entry continues a record
+    assert!(is_invalid(columns[1].write(
+        ColumnValues::ByteArray(&[]),
+        Some(&[1]),
+        Some(&[1])
+    )));
+    assert_eq!(columns[1].num_rows(), 0);
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::io::Cursor;
+
+use parquet2::compression::CompressionOptions;
+use parquet2::error::{Error, Result};
+use parquet2::indexes::select_pages;
+use parquet2::indexes::Interval;
+use parquet2::metadata::SchemaDescriptor;
+use parquet2::read::{get_page_iterator, read_metadata, read_pages_locations, IndexedPageReader};
+use parquet2::schema::types::{ParquetType, PhysicalType};
+use parquet2::write::{
+    Compressor, DynIter, DynStreamingIterator, FileWriter, Version, WriteOptions,
+};
+
+use super::primitive::array_to_page_v1;
+
+fn write_file(write_page_crc: bool) -> Result<Vec<u8>> {
+    let page1 = vec![Some(0), Some(1), None, Some(3)];
+    let page2 = vec![Some(10), Some(11)];
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc,
+        ..Default::default()
+    };
+
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col".to_string(),
+            PhysicalType::Int32,
+        )],
+    );
+
+    let pages = vec![
+        array_to_page_v1::<i32>(&page1, &options, &schema.columns()[0].descriptor),
+        array_to_page_v1::<i32>(&page2, &options, &schema.columns()[0].descriptor),
+    ];
+
+    let pages = DynStreamingIterator::new(Compressor::new(
+        DynIter::new(pages.into_iter()),
+        CompressionOptions::Snappy,
+        vec![],
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+
+    Ok(writer.into_inner().into_inner())
+}
+
+/// flips a bit of the last byte of the last page of the first column
+fn corrupt(data: &mut [u8]) -> Result<()> {
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let (start, length) = metadata.row_groups[0].columns()[0].byte_range();
+    data[(start + length - 1) as usize] ^= 1;
+    Ok(())
+}
+
+fn read_pages(data: Vec<u8>, verify_crc: bool) -> Result<usize> {
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    let pages = get_page_iterator(column, reader, None, vec![], usize::MAX)?;
+    let pages = pages.with_crc_check(verify_crc);
+    pages.collect::<Result<Vec<_>>>().map(|pages| pages.len())
+}
+
+#[test]
+fn page_reader() -> Result<()> {
+    let data = write_file(true)?;
+    assert_eq!(read_pages(data.clone(), true)?, 2);
+
+    let mut corrupted = data;
+    corrupt(&mut corrupted)?;
+    assert!(matches!(
+        read_pages(corrupted.clone(), true),
+        Err(Error::InvalidChecksum { .. })
+    ));
+    // verification is opt-in
+    assert_eq!(read_pages(corrupted, false)?, 2);
+    Ok(())
+}
+
+#[test]
+fn page_reader_without_crc() -> Result<()> {
+    // pages without checksum are not verified
+    let mut data = write_file(false)?;
+    corrupt(&mut data)?;
+    assert_eq!(read_pages(data, true)?, 2);
+    Ok(())
+}
+
+#[test]
+fn indexed_page_reader() -> Result<()> {
+    let mut data = write_file(true)?;
+    corrupt(&mut data)?;
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let columns = metadata.row_groups[0].columns();
+    let locations = read_pages_locations(&mut reader, columns)?;
+
+    let read = |reader: Cursor<Vec<u8>>, intervals: &[Interval]| {
+        let pages = select_pages(intervals, &locations[0], metadata.row_groups[0].num_rows())?;
+        IndexedPageReader::new(reader, &columns[0], pages, vec![], vec![])
+            .with_crc_check(true)
+            .collect::<Result<Vec<_>>>()
+    };
+
+    // the first page is valid
+    let pages = read(reader.clone(), &[Interval::new(0, 2)])?;
+    assert_eq!(pages.len(), 1);
+
+    // the second page is corrupted
+    let result = read(reader, &[Interval::new(4, 2)]);
+    assert!(matches!(result, Err(Error::InvalidChecksum { .. })));
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn page_stream() -> Result<()> {
+    use std::sync::Arc;
+
+    use futures::StreamExt;
+    use parquet2::read::get_page_stream;
+
+    let mut data = write_file(true)?;
+    corrupt(&mut data)?;
+
+    let mut reader = futures::io::Cursor::new(data);
+    let metadata = parquet2::read::read_metadata_async(&mut reader).await?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    for verify_crc in [false, true] {
+        let pages = get_page_stream(
+            column,
+            &mut reader,
+            vec![],
+            Arc::new(|_, _| true),
+            usize::MAX,
+            verify_crc,
+        )
+        .await?;
+        let pages = pages.collect::<Vec<_>>().await;
+        assert_eq!(pages.len(), 2);
+        assert!(pages[0].is_ok());
+        assert_eq!(pages[1].is_ok(), !verify_crc);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn indexed_page_stream() -> Result<()> {
+    use futures::StreamExt;
+    use parquet2::read::{get_indexed_page_stream, read_pages_locations_async};
+
+    let mut data = write_file(true)?;
+    corrupt(&mut data)?;
+
+    let mut reader = futures::io::Cursor::new(data);
+    let metadata = parquet2::read::read_metadata_async(&mut reader).await?;
+    let columns = metadata.row_groups[0].columns();
+    let locations = read_pages_locations_async(&mut reader, columns).await?;
+    let num_rows = metadata.row_groups[0].num_rows();
+
+    for verify_crc in [false, true] {
+        let pages = select_pages(&[Interval::new(0, num_rows)], &locations[0], num_rows)?;
+        let pages =
+            get_indexed_page_stream(&columns[0], &mut reader, pages, vec![], verify_crc).await?;
+        let pages = pages.collect::<Vec<_>>().await;
+        assert_eq!(pages.len(), 2);
+        assert!(pages[0].is_ok());
+        assert_eq!(pages[1].is_ok(), !verify_crc);
+    }
+    Ok(())
+}
@@ -0,0 +1,496 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use parquet2::bloom_filter::{hash_native, insert, is_in_set, read_with_decryption};
+use parquet2::compression::CompressionOptions;
+use parquet2::encryption::{
+    Algorithm, ColumnKey, FileDecryptionProperties, FileEncryptionProperties, KeyRetriever,
+};
+use parquet2::error::{Error, Result};
+use parquet2::indexes::{select_pages, Interval, NativeIndex};
+use parquet2::metadata::SchemaDescriptor;
+use parquet2::read::{
+    get_page_iterator, read_columns_indexes, read_columns_indexes_with_decryption, read_metadata,
+    read_metadata_with_decryption, read_pages_locations, read_pages_locations_with_decryption,
+    BasicDecompressor, IndexedPageReader,
+};
+use parquet2::schema::types::{ParquetType, PhysicalType};
+use parquet2::write::{
+    Compressor, DynIter, DynStreamingIterator, FileWriter, RowGroupIter, Version, WriteOptions,
+    DEFAULT_MAX_DICT_SIZE,
+};
+
+use super::primitive::{array_to_dict_pages_v1, array_to_page_v1};
+use crate::get_path;
+use crate::read::collect;
+use crate::Array;
+
+const FOOTER_KEY: [u8; 16] = [1; 16];
+const COLUMN_KEY: [u8; 32] = [2; 32];
+
+fn column_a() -> Vec<Option<i32>> {
+    vec![Some(1), None, Some(2), Some(1)]
+}
+
+fn column_b() -> (Vec<Option<i32>>, Vec<Option<i32>>) {
+    (vec![Some(10), Some(11), None], vec![Some(12)])
+}
+
+fn column_c() -> Vec<Option<i32>> {
+    vec![None, Some(20)]
+}
+
+fn options(write_page_crc: bool) -> WriteOptions {
+    WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc,
+        ..Default::default()
+    }
+}
+
+fn schema() -> SchemaDescriptor {
+    SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![
+            ParquetType::from_physical("a".to_string(), PhysicalType::Int32),
+            ParquetType::from_physical("b".to_string(), PhysicalType::Int32),
+            ParquetType::from_physical("c".to_string(), PhysicalType::Int32),
+        ],
+    )
+}
+
+/// A row group with three columns: "a", with a dictionary page and a data page, "b", with two
+/// data pages, and "c", with one data page.
+fn row_group(
+    schema: &SchemaDescriptor,
+    options: &WriteOptions,
+) -> Result<RowGroupIter<'static, Error>> {
+    let a = array_to_dict_pages_v1(
+        &column_a(),
+        options,
+        &schema.columns()[0].descriptor,
+        DEFAULT_MAX_DICT_SIZE,
+    )?;
+    let (b1, b2) = column_b();
+    let b = vec![
+        array_to_page_v1(&b1, options, &schema.columns()[1].descriptor)?,
+        array_to_page_v1(&b2, options, &schema.columns()[1].descriptor)?,
+    ];
+    let c = vec![array_to_page_v1(
+        &column_c(),
+        options,
+        &schema.columns()[2].descriptor,
+    )?];
+
+    let columns = [a, b, c].into_iter().map(|pages| {
+        Ok(DynStreamingIterator::new(Compressor::new_from_vec(
+            DynIter::new(pages.into_iter().map(Ok)),
+            CompressionOptions::Snappy,
+            vec![],
+        )))
+    });
+    Ok(DynIter::new(columns))
+}
+
+/// The bloom filter of the values of "b"
+fn bloom_filter() -> Vec<u8> {
+    let (b1, b2) = column_b();
+    let mut bitset = vec![0; 32];
+    b1.into_iter()
+        .chain(b2)
+        .flatten()
+        .for_each(|value| insert(&mut bitset, hash_native(value)));
+    bitset
+}
+
+/// Writes a file with the columns of [`row_group`] and the bloom filter of "b".
+fn write_file(properties: FileEncryptionProperties, write_page_crc: bool) -> Result<Vec<u8>> {
+    let schema = schema();
+    let options = options(write_page_crc);
+    let row_group = row_group(&schema, &options)?;
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None).with_encryption(properties)?;
+
+    writer.write(row_group)?;
+    writer.set_bloom_filter(0, 1, bloom_filter())?;
+    writer.end(None)?;
+
+    Ok(writer.into_inner().into_inner())
+}
+
+/// Reads the pages of every column of the file
+fn read_columns(data: &[u8], properties: &FileDecryptionProperties) -> Result<Vec<Vec<Array>>> {
+    let mut reader = Cursor::new(data);
+    let (metadata, decryptor) = read_metadata_with_decryption(&mut reader, properties)?;
+
+    metadata.row_groups[0]
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let mut pages = get_page_iterator(column, &mut reader, None, vec![], usize::MAX)?
+                .with_crc_check(true);
+            if let Some(decryptor) = &decryptor {
+                if let Some(decryptor) = decryptor.column_decryptor(0, i, column)? {
+                    pages = pages.with_decryptor(decryptor);
+                }
+            }
+            collect(
+                BasicDecompressor::new(pages, vec![]),
+                column.physical_type(),
+            )
+        })
+        .collect()
+}
+
+fn expected() -> Vec<Vec<Array>> {
+    let (b1, b2) = column_b();
+    vec![
+        vec![Array::Int32(column_a())],
+        vec![Array::Int32(b1), Array::Int32(b2)],
+        vec![Array::Int32(column_c())],
+    ]
+}
+
+struct Retriever(HashMap<Vec<u8>, Vec<u8>>);
+
+impl KeyRetriever for Retriever {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+        self.0
+            .get(key_metadata)
+            .cloned()
+            .ok_or_else(|| Error::InvalidParameter("unknown key".to_string()))
+    }
+}
+
+#[test]
+fn encrypted_footer() -> Result<()> {
+    let data = write_file(FileEncryptionProperties::new(FOOTER_KEY.to_vec()), false)?;
+    assert_eq!(&data[data.len() - 4..], b"PARE");
+
+    let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    assert_eq!(read_columns(&data, &properties)?, expected());
+
+    // the metadata of the columns is part of the (encrypted) footer
+    let (metadata, _) = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+    assert!(metadata.row_groups[0].columns()[1].statistics().is_some());
+
+    assert!(matches!(
+        read_metadata(&mut Cursor::new(&data)),
+        Err(Error::InvalidParameter(_))
+    ));
+
+    let properties = FileDecryptionProperties::new(vec![3; 16]);
+    assert!(read_columns(&data, &properties).is_err());
+    Ok(())
+}
+
+#[test]
+fn ctr() -> Result<()> {
+    let mut properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+    properties.algorithm = Algorithm::AesGcmCtrV1;
+    let data = write_file(properties, true)?;
+
+    let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    assert_eq!(read_columns(&data, &properties)?, expected());
+    Ok(())
+}
+
+#[test]
+fn plaintext_footer_and_column_keys() -> Result<()> {
+    let mut properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+    properties.plaintext_footer = true;
+    properties.footer_key_metadata = Some(b"footer".to_vec());
+    properties.column_keys.insert(
+        "a".to_string(),
+        ColumnKey {
+            key: FOOTER_KEY.to_vec(),
+            key_metadata: Some(b"a".to_vec()),
+        },
+    );
+    properties.column_keys.insert(
+        "b".to_string(),
+        ColumnKey {
+            key: COLUMN_KEY.to_vec(),
+            key_metadata: Some(b"b".to_vec()),
+        },
+    );
+    let data = write_file(properties, true)?;
+    assert_eq!(&data[data.len() - 4..], b"PAR1");
+
+    let keys = HashMap::from([
+        (b"footer".to_vec(), FOOTER_KEY.to_vec()),
+        (b"a".to_vec(), FOOTER_KEY.to_vec()),
+        (b"b".to_vec(), COLUMN_KEY.to_vec()),
+    ]);
+    let properties = FileDecryptionProperties {
+        key_retriever: Some(Arc::new(Retriever(keys))),
+        ..Default::default()
+    };
+    assert_eq!(read_columns(&data, &properties)?, expected());
+
+    let (metadata, _) = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+    let columns = metadata.row_groups[0].columns();
+    assert!(columns[1].statistics().is_some());
+    assert_indexes_and_bloom_filter(&data, &properties)?;
+
+    // the plaintext footer can be read without keys...
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let columns = metadata.row_groups[0].columns();
+    assert!(metadata.encryption_algorithm.is_some());
+    // ... without the statistics of encrypted columns
+    assert!(columns[1].statistics().is_none());
+    assert!(columns[2].statistics().is_some());
+
+    // ... and so do plaintext columns
+    let properties = FileDecryptionProperties::default();
+    let (metadata, decryptor) =
+        read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+    let decryptor = decryptor.unwrap();
+    let columns = metadata.row_groups[0].columns();
+    assert!(decryptor.column_decryptor(0, 1, &columns[1]).is_err());
+    assert!(decryptor.column_decryptor(0, 2, &columns[2])?.is_none());
+    Ok(())
+}
+
+/// Asserts that the column indexes, offset indexes and bloom filter of the file are encrypted
+/// and can be read with decryption, including via [`IndexedPageReader`].
+fn assert_indexes_and_bloom_filter(
+    data: &[u8],
+    properties: &FileDecryptionProperties,
+) -> Result<()> {
+    let mut reader = Cursor::new(data);
+    let (metadata, decryptor) = read_metadata_with_decryption(&mut reader, properties)?;
+    let decryptor = decryptor.unwrap();
+    let columns = metadata.row_groups[0].columns();
+    let decryptors = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| decryptor.column_decryptor(0, i, column))
+        .collect::<Result<Vec<_>>>()?;
+
+    assert!(matches!(
+        read_columns_indexes(&mut reader, columns),
+        Err(Error::FeatureNotSupported(_))
+    ));
+    assert!(matches!(
+        read_pages_locations(&mut reader, columns),
+        Err(Error::FeatureNotSupported(_))
+    ));
+
+    let indexes = read_columns_indexes_with_decryption(&mut reader, columns, &decryptors)?;
+    let index = indexes[1]
+        .as_any()
+        .downcast_ref::<NativeIndex<i32>>()
+        .unwrap();
+    let bounds = index
+        .indexes
+        .iter()
+        .map(|page| (page.min, page.max, page.null_count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bounds,
+        vec![(Some(10), Some(11), Some(1)), (Some(12), Some(12), Some(0))]
+    );
+
+    let locations = read_pages_locations_with_decryption(&mut reader, columns, &decryptors)?;
+    assert_eq!(
+        locations.iter().map(|x| x.len()).collect::<Vec<_>>(),
+        vec![1, 2, 1]
+    );
+
+    let mut bitset = vec![];
+    let column_decryptor = decryptors[1].as_ref().unwrap();
+    read_with_decryption(&columns[1], &mut reader, &mut bitset, column_decryptor)?;
+    assert_eq!(bitset, bloom_filter());
+    assert!(is_in_set(&bitset, hash_native(12i32)));
+
+    // only the second page of "b" is read, with the ordinal of a second page
+    let pages = select_pages(&[Interval::new(3, 1)], &locations[1], 4)?;
+    let decryptor = decryptor.column_decryptor(0, 1, &columns[1])?.unwrap();
+    let pages = IndexedPageReader::new(&mut reader, &columns[1], pages, vec![], vec![])
+        .with_crc_check(true)
+        .with_decryptor(decryptor);
+    let (_, b2) = column_b();
+    assert_eq!(
+        collect(BasicDecompressor::new(pages, vec![]), PhysicalType::Int32)?,
+        vec![Array::Int32(b2)]
+    );
+    Ok(())
+}
+
+#[test]
+fn indexes_and_bloom_filter() -> Result<()> {
+    let data = write_file(FileEncryptionProperties::new(FOOTER_KEY.to_vec()), true)?;
+    let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    assert_indexes_and_bloom_filter(&data, &properties)
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn file_streamer() -> Result<()> {
+    use parquet2::write::FileStreamer;
+
+    let schema = schema();
+    let options = options(true);
+    let row_group = row_group(&schema, &options)?;
+
+    let writer = futures::io::Cursor::new(vec![]);
+    let properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+    let mut writer =
+        FileStreamer::new(writer, schema, options, None).with_encryption(properties)?;
+
+    writer.write(row_group).await?;
+    writer.set_bloom_filter(0, 1, bloom_filter())?;
+    writer.end(None).await?;
+    let data = writer.into_inner().into_inner();
+    assert_eq!(&data[data.len() - 4..], b"PARE");
+
+    let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    assert_eq!(read_columns(&data, &properties)?, expected());
+    assert_indexes_and_bloom_filter(&data, &properties)
+}
+
+#[test]
+fn tampered_plaintext_footer() -> Result<()> {
+    let mut properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+    properties.plaintext_footer = true;
+    let mut data = write_file(properties, false)?;
+
+    // change the last byte of the tag of the signature
+    let len = data.len();
+    data[len - 9] ^= 1;
+
+    let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    assert!(read_metadata_with_decryption(&mut Cursor::new(&data), &properties).is_err());
+    Ok(())
+}
+
+#[test]
+fn aad_prefix() -> Result<()> {
+    let mut properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+    properties.aad_prefix = Some(b"file.parquet".to_vec());
+    properties.store_aad_prefix = false;
+    let data = write_file(properties, false)?;
+
+    let mut properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    assert!(read_columns(&data, &properties).is_err());
+
+    properties.aad_prefix = Some(b"other.parquet".to_vec());
+    assert!(read_columns(&data, &properties).is_err());
+
+    properties.aad_prefix = Some(b"file.parquet".to_vec());
+    assert_eq!(read_columns(&data, &properties)?, expected());
+    Ok(())
+}
+
+#[test]
+fn invalid_properties() {
+    let properties = FileEncryptionProperties::new(vec![1; 10]);
+    assert!(write_file(properties, false).is_err());
+
+    let mut properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+    properties.column_keys.insert(
+        "d".to_string(),
+        ColumnKey {
+            key: COLUMN_KEY.to_vec(),
+            key_metadata: None,
+        },
+    );
+    assert!(write_file(properties, false).is_err());
+}
+
+/// Concatenates the values of `arrays`, the pages of a column
+fn concat(arrays: Vec<Array>) -> Array {
+    arrays
+        .into_iter()
+        .reduce(|lhs, rhs| match (lhs, rhs) {
+            (Array::Boolean(mut lhs), Array::Boolean(rhs)) => {
+                lhs.extend(rhs);
+                Array::Boolean(lhs)
+            }
+            (Array::Int32(mut lhs), Array::Int32(rhs)) => {
+                lhs.extend(rhs);
+                Array::Int32(lhs)
+            }
+            (Array::Float(mut lhs), Array::Float(rhs)) => {
+                lhs.extend(rhs);
+                Array::Float(lhs)
+            }
+            (Array::Double(mut lhs), Array::Double(rhs)) => {
+                lhs.extend(rhs);
+                Array::Double(lhs)
+            }
+            _ => unreachable!(),
+        })
+        .unwrap()
+}
+
+/// Reads a file of parquet-testing encrypted by parquet-mr, whose footer is encrypted with the
+/// key "kf" and whose columns `double_field` and `float_field` with the keys "kc1" and "kc2".
+fn read_parquet_mr(file: &str) -> Result<()> {
+    let data = std::fs::read(get_path().join(file))?;
+
+    let keys = HashMap::from([
+        (b"kf".to_vec(), b"0123456789012345".to_vec()),
+        (b"kc1".to_vec(), b"1234567890123450".to_vec()),
+        (b"kc2".to_vec(), b"1234567890123451".to_vec()),
+    ]);
+    let properties = FileDecryptionProperties {
+        key_retriever: Some(Arc::new(Retriever(keys))),
+        ..Default::default()
+    };
+
+    let mut reader = Cursor::new(data);
+    let (metadata, decryptor) = read_metadata_with_decryption(&mut reader, &properties)?;
+    let decryptor = decryptor.unwrap();
+
+    let names = [
+        "boolean_field",
+        "int32_field",
+        "float_field",
+        "double_field",
+    ];
+    let mut columns = names.iter().map(|_| vec![]).collect::<Vec<_>>();
+    for (row_group, group) in metadata.row_groups.iter().enumerate() {
+        for (i, column) in group.columns().iter().enumerate() {
+            let name = &column.descriptor().path_in_schema[0];
+            let index = match names.iter().position(|x| x == name) {
+                Some(index) => index,
+                None => continue,
+            };
+            let mut pages = get_page_iterator(column, &mut reader, None, vec![], usize::MAX)?;
+            if let Some(decryptor) = decryptor.column_decryptor(row_group, i, column)? {
+                pages = pages.with_decryptor(decryptor);
+            }
+            columns[index].extend(collect(
+                BasicDecompressor::new(pages, vec![]),
+                column.physical_type(),
+            )?);
+        }
+    }
+    let columns = columns.into_iter().map(concat).collect::<Vec<_>>();
+
+    let rows = 0..metadata.num_rows;
+    let expected = vec![
+        Array::Boolean(rows.clone().map(|i| Some(i % 2 == 0)).collect()),
+        Array::Int32(rows.clone().map(|i| Some(i as i32)).collect()),
+        Array::Float(rows.clone().map(|i| Some(i as f32 * 1.1f32)).collect()),
+        Array::Double(rows.map(|i| Some(i as f64 * 1.1111111)).collect()),
+    ];
+    assert_eq!(columns, expected);
+    Ok(())
+}
+
+#[test]
+fn parquet_mr_gcm() -> Result<()> {
+    read_parquet_mr("encrypt_columns_and_footer.parquet.encrypted")
+}
+
+#[test]
+fn parquet_mr_gcm_ctr() -> Result<()> {
+    read_parquet_mr("encrypt_columns_and_footer_ctr.parquet.encrypted")
+}
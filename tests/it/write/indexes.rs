@@ -3,15 +3,21 @@ use std::io::Cursor;
 use parquet2::compression::CompressionOptions;
 use parquet2::error::Result;
 use parquet2::indexes::{
-    select_pages, BoundaryOrder, Index, Interval, NativeIndex, PageIndex, PageLocation,
+    select_pages, BoundaryOrder, ByteIndex, Index, Interval, NativeIndex, PageIndex, PageLocation,
 };
 use parquet2::metadata::SchemaDescriptor;
+use parquet2::page::CompressedPage;
+use parquet2::predicate::{CompareOp, Predicate};
 use parquet2::read::{
-    read_columns_indexes, read_metadata, read_pages_locations, BasicDecompressor, IndexedPageReader,
+    filter_row_groups_with_predicate, get_page_iterator, read_columns_indexes, read_metadata,
+    read_pages_locations, BasicDecompressor, Field, IndexedPageReader,
 };
 use parquet2::schema::types::{ParquetType, PhysicalType, PrimitiveType};
-use parquet2::write::WriteOptions;
-use parquet2::write::{Compressor, DynIter, DynStreamingIterator, FileWriter, Version};
+use parquet2::statistics::{BinaryStatistics, FixedLenStatistics};
+use parquet2::write::{
+    Compressor, DynIter, DynStreamingIterator, FileWriter, RowWriter, RowWriterOptions, Version,
+    WriteOptions,
+};
 
 use crate::read::collect;
 use crate::Array;
@@ -25,6 +31,7 @@ fn write_file() -> Result<Vec<u8>> {
     let options = WriteOptions {
         write_statistics: true,
         version: Version::V1,
+        ..Default::default()
     };
 
     let schema = SchemaDescriptor::new(
@@ -120,7 +127,7 @@ fn read_indexes_and_locations() -> Result<()> {
                 null_count: Some(0),
             },
         ],
-        boundary_order: BoundaryOrder::Unordered,
+        boundary_order: BoundaryOrder::Ascending,
     }) as Box<dyn Index>];
 
     let indexes = read_columns_indexes(&mut reader, columns)?;
@@ -131,3 +138,182 @@ fn read_indexes_and_locations() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn read_indexes_and_locations_async() -> Result<()> {
+    use parquet2::read::{read_columns_indexes_async, read_pages_locations_async};
+
+    let data = write_file()?;
+    let mut reader = Cursor::new(data.clone());
+    let metadata = read_metadata(&mut reader)?;
+    let columns = &metadata.row_groups[0].columns();
+
+    let expected_indexes = read_columns_indexes(&mut reader, columns)?;
+    let expected_pages = read_pages_locations(&mut reader, columns)?;
+
+    let mut reader = futures::io::Cursor::new(data);
+    let indexes = read_columns_indexes_async(&mut reader, columns).await?;
+    assert_eq!(&indexes, &expected_indexes);
+
+    let pages = read_pages_locations_async(&mut reader, columns).await?;
+    assert_eq!(pages, expected_pages);
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn read_indexed_page_async() -> Result<()> {
+    use futures::TryStreamExt;
+    use parquet2::page::CompressedPage;
+    use parquet2::read::{get_indexed_page_stream, read_pages_locations_async};
+
+    let data = write_file()?;
+    let mut reader = futures::io::Cursor::new(data);
+
+    let metadata = parquet2::read::read_metadata_async(&mut reader).await?;
+
+    let column = 0;
+    let columns = &metadata.row_groups[0].columns();
+
+    // selected the rows
+    let intervals = &[Interval::new(2, 2)];
+
+    let pages = read_pages_locations_async(&mut reader, columns).await?;
+
+    let pages = select_pages(intervals, &pages[column], metadata.row_groups[0].num_rows())?;
+
+    let pages =
+        get_indexed_page_stream(&columns[column], &mut reader, pages, vec![], false).await?;
+    let pages = pages.try_collect::<Vec<_>>().await?;
+    assert_eq!(pages.len(), 1);
+    match &pages[0] {
+        CompressedPage::Data(page) => assert!(page.selected_rows().is_some()),
+        _ => panic!("expected a data page"),
+    }
+
+    let pages = BasicDecompressor::new(pages.into_iter().map(Ok), vec![]);
+
+    let arrays = collect(pages, columns[column].physical_type())?;
+
+    // the second item and length 2
+    assert_eq!(arrays, vec![Array::Int32(vec![None, Some(3)])]);
+
+    Ok(())
+}
+
+#[test]
+fn truncated_statistics() -> Result<()> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema {
+            REQUIRED BYTE_ARRAY name (STRING);
+            REQUIRED FIXED_LEN_BYTE_ARRAY(6) key;
+        }",
+    )?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        statistics_truncate_length: Some(4),
+        ..Default::default()
+    };
+    let row_options = RowWriterOptions {
+        max_page_size: 1,
+        ..Default::default()
+    };
+    let mut writer = RowWriter::new(Cursor::new(vec![]), schema, options, row_options);
+    for (name, key) in [
+        ("alpha", [0, 1, 2, 3, 4, 5]),
+        ("bravo", [1, 255, 255, 255, 255, 0]),
+    ] {
+        writer.write(&vec![
+            ("name".to_string(), Field::String(name.to_string())),
+            ("key".to_string(), Field::Bytes(key.to_vec())),
+        ])?;
+    }
+    writer.end(None)?;
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+    let columns = metadata.row_groups[0].columns();
+
+    // the min is truncated down and the max truncated up
+    let statistics = columns[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<BinaryStatistics>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(b"alph".to_vec()));
+    assert_eq!(statistics.max_value, Some(b"braw".to_vec()));
+
+    let statistics = columns[1].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<FixedLenStatistics>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(vec![0, 1, 2, 3]));
+    assert_eq!(statistics.max_value, Some(vec![2]));
+
+    // truncated bounds are declared inexact
+    let statistics = columns[0].metadata().statistics.as_ref().unwrap();
+    assert_eq!(statistics.is_min_value_exact, Some(false));
+    assert_eq!(statistics.is_max_value_exact, Some(false));
+
+    let expected = vec![
+        (b"alph".to_vec(), b"alpi".to_vec()),
+        (b"brav".to_vec(), b"braw".to_vec()),
+    ];
+
+    let indexes = read_columns_indexes(&mut reader, columns)?;
+    let index = indexes[0].as_any().downcast_ref::<ByteIndex>().unwrap();
+    let bounds = index
+        .indexes
+        .iter()
+        .map(|page| (page.min.clone().unwrap(), page.max.clone().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(bounds, expected);
+
+    // the statistics of the page headers are truncated as well
+    let pages = get_page_iterator(&columns[0], &mut reader, None, vec![], usize::MAX)?;
+    let bounds = pages
+        .filter_map(|page| match page {
+            Ok(CompressedPage::Data(page)) => Some(page.statistics().unwrap().map(|statistics| {
+                let statistics = statistics
+                    .as_any()
+                    .downcast_ref::<BinaryStatistics>()
+                    .unwrap();
+                (
+                    statistics.min_value.clone().unwrap(),
+                    statistics.max_value.clone().unwrap(),
+                )
+            })),
+            Ok(CompressedPage::Dict(_)) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(bounds, expected);
+
+    // the truncated bounds still select the row groups of the values
+    let predicate = Predicate::compare(&["name"], CompareOp::Eq, "bravo");
+    assert_eq!(
+        filter_row_groups_with_predicate(&metadata, &predicate)?
+            .row_groups
+            .len(),
+        1
+    );
+    // "bravo" is below the upper bound, "braw"
+    let predicate = Predicate::compare(&["name"], CompareOp::Gt, "bravo");
+    assert_eq!(
+        filter_row_groups_with_predicate(&metadata, &predicate)?
+            .row_groups
+            .len(),
+        1
+    );
+    let predicate = Predicate::compare(&["name"], CompareOp::GtEq, "braw0");
+    assert_eq!(
+        filter_row_groups_with_predicate(&metadata, &predicate)?
+            .row_groups
+            .len(),
+        0
+    );
+    Ok(())
+}
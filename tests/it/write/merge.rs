@@ -0,0 +1,200 @@
+use std::io::Cursor;
+
+use parquet2::error::Result;
+use parquet2::metadata::{KeyValue, SchemaDescriptor};
+use parquet2::read::{read_columns_indexes, read_metadata, read_pages_locations, rows, Field, Row};
+use parquet2::write::{
+    extract_row_groups, merge, split_row_groups, RowWriter, RowWriterOptions, Version, WriteOptions,
+};
+
+fn row(i: i64) -> Row {
+    let name = if i % 3 == 1 {
+        Field::Null
+    } else {
+        Field::String(format!("name {}", i))
+    };
+    vec![
+        ("id".to_string(), Field::Int64(i)),
+        ("name".to_string(), name),
+    ]
+}
+
+// a file with the rows `ids` in row groups of 3 rows
+fn write(ids: std::ops::Range<i64>, key_value_metadata: Vec<KeyValue>) -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema {
+            REQUIRED INT64 id;
+            OPTIONAL BYTE_ARRAY name (STRING);
+        }",
+    )?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let row_options = RowWriterOptions {
+        max_rows_per_row_group: 3,
+        max_page_size: 16,
+        ..Default::default()
+    };
+    let mut writer = RowWriter::new(Cursor::new(vec![]), schema, options, row_options);
+    ids.map(row).try_for_each(|row| writer.write(&row))?;
+    writer.end(Some(key_value_metadata))?;
+    Ok(writer.into_inner().into_inner())
+}
+
+fn key_value(key: &str, value: &str) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: Some(value.to_string()),
+    }
+}
+
+#[test]
+fn merge_files() -> Result<()> {
+    let mut readers = vec![
+        Cursor::new(write(0..5, vec![key_value("a", "0"), key_value("b", "0")])?),
+        Cursor::new(write(5..9, vec![key_value("b", "1"), key_value("c", "1")])?),
+    ];
+    let mut writer = Cursor::new(vec![]);
+    let size = merge(&mut readers, &mut writer, None)?;
+    let data = writer.into_inner();
+    assert_eq!(size, data.len() as u64);
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    assert_eq!(metadata.num_rows, 9);
+    assert_eq!(metadata.row_groups.len(), 4);
+    assert!(metadata.column_orders.is_some());
+    assert_eq!(
+        metadata.key_value_metadata,
+        Some(vec![
+            key_value("a", "0"),
+            key_value("b", "0"),
+            key_value("c", "1")
+        ])
+    );
+
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(result, (0..9).map(row).collect::<Vec<_>>());
+
+    // the indexes are copied and point to the copied pages
+    for row_group in &metadata.row_groups {
+        let columns = row_group.columns();
+        let indexes = read_columns_indexes(&mut reader, columns)?;
+        assert_eq!(indexes.len(), columns.len());
+        let locations = read_pages_locations(&mut reader, columns)?;
+        for (column, locations) in columns.iter().zip(locations) {
+            assert_eq!(locations[0].offset, column.data_page_offset());
+            let (start, length) = column.byte_range();
+            let last = locations.last().unwrap();
+            assert!(last.offset as u64 + last.compressed_page_size as u64 <= start + length);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn extract_and_split() -> Result<()> {
+    let mut reader = Cursor::new(write(0..8, vec![key_value("a", "0")])?);
+    let metadata = read_metadata(&mut reader)?;
+    assert_eq!(metadata.row_groups.len(), 3);
+
+    let sizes = metadata
+        .row_groups
+        .iter()
+        .map(|row_group| row_group.compressed_size())
+        .collect::<Vec<_>>();
+    assert_eq!(split_row_groups(&metadata, usize::MAX), vec![0..3]);
+    assert_eq!(split_row_groups(&metadata, 0), vec![0..1, 1..2, 2..3]);
+    assert_eq!(
+        split_row_groups(&metadata, sizes[0] + sizes[1]),
+        vec![0..2, 2..3]
+    );
+
+    let mut writer = Cursor::new(vec![]);
+    let size = extract_row_groups(&mut reader, &metadata, &[2, 0], &mut writer)?;
+    let data = writer.into_inner();
+    assert_eq!(size, data.len() as u64);
+
+    let mut reader = Cursor::new(data);
+    let extracted = read_metadata(&mut reader)?;
+    assert_eq!(extracted.num_rows, 5);
+    assert_eq!(extracted.key_value_metadata, metadata.key_value_metadata);
+    let result = rows(&mut reader, &extracted, None)?.collect::<Result<Vec<_>>>()?;
+    let expected = (6..8).chain(0..3).map(row).collect::<Vec<_>>();
+    assert_eq!(result, expected);
+
+    // the statistics and indexes are carried over
+    let row_group = &extracted.row_groups[0];
+    let original = &metadata.row_groups[2];
+    assert!(row_group.columns()[0].metadata().statistics.is_some());
+    assert_eq!(
+        row_group.columns()[0].metadata().statistics,
+        original.columns()[0].metadata().statistics
+    );
+    let locations = read_pages_locations(&mut reader, row_group.columns())?;
+    assert_eq!(
+        locations[0][0].offset,
+        row_group.columns()[0].data_page_offset()
+    );
+    assert_eq!(
+        read_columns_indexes(&mut reader, row_group.columns())?.len(),
+        2
+    );
+
+    let mut writer = Cursor::new(vec![]);
+    assert!(extract_row_groups(&mut reader, &metadata, &[3], &mut writer).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "bloom_filter")]
+#[test]
+fn merge_bloom_filters() -> Result<()> {
+    use parquet2::bloom_filter;
+
+    let (a, bitset_a) = super::write_bloom_filter(&[Some(0), Some(1)])?;
+    let (b, bitset_b) = super::write_bloom_filter(&[Some(2), None])?;
+    let mut writer = Cursor::new(vec![]);
+    merge(&mut [Cursor::new(a), Cursor::new(b)], &mut writer, None)?;
+
+    let mut reader = Cursor::new(writer.into_inner());
+    let metadata = read_metadata(&mut reader)?;
+    let mut bitset = vec![];
+    for (row_group, expected) in metadata.row_groups.iter().zip([bitset_a, bitset_b]) {
+        let column = &row_group.columns()[0];
+        bloom_filter::read(column, &mut reader, &mut bitset)?;
+        assert_eq!(bitset, expected);
+        // the header and bitset were read up to the declared length
+        let end = column.metadata().bloom_filter_offset.unwrap()
+            + column.metadata().bloom_filter_length.unwrap() as i64;
+        assert_eq!(reader.position(), end as u64);
+    }
+    Ok(())
+}
+
+#[test]
+fn invalid() -> Result<()> {
+    let mut writer = Cursor::new(vec![]);
+    assert!(merge::<Cursor<Vec<u8>>, _>(&mut [], &mut writer, None).is_err());
+
+    let other = SchemaDescriptor::try_from_message("message schema { REQUIRED INT64 id; }")?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        ..Default::default()
+    };
+    let mut other = RowWriter::new(
+        Cursor::new(vec![]),
+        other,
+        options,
+        RowWriterOptions::default(),
+    );
+    other.end(None)?;
+    let mut readers = vec![
+        Cursor::new(write(0..5, vec![])?),
+        Cursor::new(other.into_inner().into_inner()),
+    ];
+    assert!(merge(&mut readers, &mut writer, None).is_err());
+    Ok(())
+}
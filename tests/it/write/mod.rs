@@ -1,6 +1,13 @@
 mod binary;
+mod column_writer;
+mod crc;
+#[cfg(feature = "encryption")]
+mod encryption;
 mod indexes;
+mod merge;
+mod nested;
 mod primitive;
+mod rows;
 mod sidecar;
 
 use std::io::{Cursor, Read, Seek};
@@ -8,18 +15,20 @@ use std::sync::Arc;
 
 use parquet2::compression::{BrotliLevel, CompressionOptions};
 use parquet2::error::Result;
-use parquet2::metadata::SchemaDescriptor;
-use parquet2::read::read_metadata;
+use parquet2::metadata::{SchemaDescriptor, SortingColumn};
+use parquet2::read::{read_metadata, rows, Field};
 use parquet2::schema::types::{ParquetType, PhysicalType};
 use parquet2::statistics::Statistics;
 #[cfg(feature = "async")]
 use parquet2::write::FileStreamer;
-use parquet2::write::{Compressor, DynIter, DynStreamingIterator, FileWriter, Version};
+use parquet2::write::{
+    encode_column_chunk, Compressor, DynIter, DynStreamingIterator, FileWriter, Version,
+};
 use parquet2::{metadata::Descriptor, page::Page, write::WriteOptions};
 
 use super::Array;
 use super::{alltypes_plain, alltypes_statistics};
-use primitive::array_to_page_v1;
+use primitive::{array_to_dict_pages_v1, array_to_page_v1};
 
 pub fn array_to_page(
     array: &Array,
@@ -59,6 +68,7 @@ fn test_column(column: &str, compression: CompressionOptions) -> Result<()> {
     let options = WriteOptions {
         write_statistics: true,
         version: Version::V1,
+        ..Default::default()
     };
 
     // prepare schema
@@ -192,6 +202,7 @@ fn basic() -> Result<()> {
     let options = WriteOptions {
         write_statistics: false,
         version: Version::V1,
+        ..Default::default()
     };
 
     let schema = SchemaDescriptor::new(
@@ -234,6 +245,531 @@ fn basic() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn encoded_column_chunks() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: true,
+        ..Default::default()
+    };
+
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![
+            ParquetType::from_physical("a".to_string(), PhysicalType::Int32),
+            ParquetType::from_physical("b".to_string(), PhysicalType::Int32),
+        ],
+    );
+    let row_groups = vec![
+        vec![vec![Some(0), None, Some(2)], vec![Some(3), Some(4), None]],
+        vec![vec![Some(5)], vec![None]],
+    ];
+
+    let pages = |array: &[Option<i32>], column: usize| {
+        DynStreamingIterator::new(Compressor::new_from_vec(
+            DynIter::new(std::iter::once(array_to_page_v1(
+                array,
+                &options,
+                &schema.columns()[column].descriptor,
+            ))),
+            CompressionOptions::Uncompressed,
+            vec![],
+        ))
+    };
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    for arrays in &row_groups {
+        let columns = arrays
+            .iter()
+            .enumerate()
+            .map(|(column, array)| Ok(pages(array, column)));
+        writer.write(DynIter::new(columns))?;
+    }
+    writer.end(None)?;
+    let expected = writer.into_inner().into_inner();
+
+    // the column chunks are encoded in parallel and written in order
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    for arrays in &row_groups {
+        let columns = std::thread::scope(|scope| {
+            let handles = arrays
+                .iter()
+                .enumerate()
+                .map(|(column, array)| {
+                    let pages = &pages;
+                    let descriptor = &schema.columns()[column];
+                    scope.spawn(move || {
+                        encode_column_chunk(descriptor, pages(array, column), &options)
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Result<Vec<_>>>()
+        })?;
+        assert_eq!(columns[0].page_specs().len(), 1);
+        writer.write_encoded(columns)?;
+    }
+    writer.end(None)?;
+    let data = writer.into_inner().into_inner();
+    assert_eq!(data, expected);
+
+    let metadata = read_metadata(&mut Cursor::new(data))?;
+    assert_eq!(metadata.row_groups.len(), 2);
+    assert_eq!(metadata.num_rows, 4);
+
+    // the column chunks must match the schema
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    let encode = |column: usize| {
+        encode_column_chunk(&schema.columns()[column], pages(&[], column), &options)
+    };
+    assert!(writer.write_encoded(vec![encode(0)?]).is_err());
+    assert!(writer.write_encoded(vec![encode(1)?, encode(0)?]).is_err());
+    Ok(())
+}
+
+#[test]
+fn unsorted_row_groups() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        ..Default::default()
+    };
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "a".to_string(),
+            PhysicalType::Int32,
+        )],
+    );
+    let sorting_columns = Some(vec![SortingColumn::new(0, false, false)]);
+
+    // a column chunk whose pages hold each of `arrays`
+    let pages = |arrays: &[&[Option<i32>]]| {
+        let pages = arrays
+            .iter()
+            .map(|array| array_to_page_v1(array, &options, &schema.columns()[0].descriptor))
+            .collect::<Vec<_>>();
+        DynStreamingIterator::new(Compressor::new_from_vec(
+            DynIter::new(pages.into_iter()),
+            CompressionOptions::Uncompressed,
+            vec![],
+        ))
+    };
+    let sorted: &[&[Option<i32>]] = &[&[Some(0), Some(1)], &[Some(2)]];
+    let unsorted: &[&[Option<i32>]] = &[&[Some(2)], &[Some(0), Some(1)]];
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    writer.set_sorting_columns(sorting_columns.clone(), true)?;
+    writer.write_encoded(vec![encode_column_chunk(
+        &schema.columns()[0],
+        pages(sorted),
+        &options,
+    )?])?;
+    writer.end(None)?;
+    let expected = writer.into_inner().into_inner();
+
+    // encoded column chunks are rejected before they are written
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    writer.set_sorting_columns(sorting_columns.clone(), true)?;
+    let encode = |arrays| encode_column_chunk(&schema.columns()[0], pages(arrays), &options);
+    assert!(writer.write_encoded(vec![encode(unsorted)?]).is_err());
+    writer.write_encoded(vec![encode(sorted)?])?;
+    writer.end(None)?;
+    assert_eq!(writer.into_inner().into_inner(), expected);
+
+    // row groups are rejected after they are written, which errors the writer
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    writer.set_sorting_columns(sorting_columns, true)?;
+    let row_group = |arrays| DynIter::new(std::iter::once(Ok(pages(arrays))));
+    assert!(writer.write(row_group(unsorted)).is_err());
+    assert!(writer.write(row_group(sorted)).is_err());
+    assert!(writer.end(None).is_err());
+    Ok(())
+}
+
+// a file with a column of `array` and its bloom filter, and the bitset of the filter
+#[cfg(feature = "bloom_filter")]
+fn write_bloom_filter(array: &[Option<i32>]) -> Result<(Vec<u8>, Vec<u8>)> {
+    use parquet2::bloom_filter;
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        ..Default::default()
+    };
+
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col".to_string(),
+            PhysicalType::Int32,
+        )],
+    );
+
+    let pages = DynStreamingIterator::new(Compressor::new_from_vec(
+        DynIter::new(std::iter::once(array_to_page_v1(
+            array,
+            &options,
+            &schema.columns()[0].descriptor,
+        ))),
+        CompressionOptions::Uncompressed,
+        vec![],
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    let mut bitset = vec![0; bloom_filter::optimal_num_bytes(array.len(), 0.01)];
+    array
+        .iter()
+        .flatten()
+        .for_each(|x| bloom_filter::insert(&mut bitset, bloom_filter::hash_native(*x)));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+
+    writer.write(DynIter::new(columns))?;
+    assert!(writer.set_bloom_filter(0, 1, bitset.clone()).is_err());
+    assert!(writer.set_bloom_filter(0, 0, vec![0; 10]).is_err());
+    writer.set_bloom_filter(0, 0, bitset.clone())?;
+    writer.end(None)?;
+
+    Ok((writer.into_inner().into_inner(), bitset))
+}
+
+#[cfg(feature = "bloom_filter")]
+#[test]
+fn bloom_filter() -> Result<()> {
+    use parquet2::bloom_filter;
+    use parquet2::predicate::{CompareOp, Predicate};
+
+    let array = vec![Some(0), Some(1), None, Some(3)];
+    let (data, bitset) = write_bloom_filter(&array)?;
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    let offset = column.metadata().bloom_filter_offset.unwrap();
+    let length = column.metadata().bloom_filter_length.unwrap();
+    // the header and bitset are followed by the column index
+    assert_eq!(
+        column.column_chunk().column_index_offset,
+        Some(offset + length as i64)
+    );
+
+    let mut read_bitset = vec![];
+    bloom_filter::read(column, &mut reader, &mut read_bitset)?;
+    assert_eq!(read_bitset, bitset);
+    assert!(bloom_filter::is_in_set(
+        &read_bitset,
+        bloom_filter::hash_native(3i32)
+    ));
+
+    // literals are hashed in the representation of the column
+    assert!(bloom_filter::may_contain(
+        column,
+        &read_bitset,
+        &[3i64.into()]
+    )?);
+    assert!(!bloom_filter::may_contain(
+        column,
+        &read_bitset,
+        &[2i32.into(), i64::MAX.into()]
+    )?);
+    assert!(bloom_filter::may_contain(column, &[], &[2i32.into()])?);
+    assert!(bloom_filter::may_contain(column, &read_bitset, &["a".into()]).is_err());
+
+    // 2 is within the statistics but not in the bloom filter
+    let row_group = &metadata.row_groups[0];
+    let predicate = Predicate::compare(&["col"], CompareOp::Eq, 2i32);
+    assert!(predicate.may_match(row_group)?);
+    let bitsets = vec![read_bitset.clone()];
+    assert!(!predicate.may_match_with_bloom_filters(row_group, &bitsets)?);
+    assert!((!predicate).may_match_with_bloom_filters(row_group, &bitsets)?);
+    let predicate = Predicate::is_in(&["col"], [2i32, 3]);
+    assert!(predicate.may_match_with_bloom_filters(row_group, &bitsets)?);
+    assert!(predicate
+        .may_match_with_bloom_filters(row_group, &[])
+        .is_err());
+
+    // the rest of the file is still readable
+    let (result, _) = read_column(&mut reader)?;
+    assert_eq!(result, Array::Int32(array));
+    Ok(())
+}
+
+#[cfg(all(feature = "bloom_filter", feature = "async"))]
+#[tokio::test]
+async fn bloom_filter_async() -> Result<()> {
+    use parquet2::bloom_filter;
+    use parquet2::read::read_metadata_async;
+
+    let (data, bitset) = write_bloom_filter(&[Some(0), Some(1), None, Some(3)])?;
+    let mut reader = futures::io::Cursor::new(data);
+
+    let metadata = read_metadata_async(&mut reader).await?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    let mut read_bitset = vec![];
+    bloom_filter::read_async(column, &mut reader, &mut read_bitset).await?;
+    assert_eq!(read_bitset, bitset);
+    assert!(bloom_filter::may_contain(
+        column,
+        &read_bitset,
+        &[1i32.into()]
+    )?);
+    assert!(!bloom_filter::may_contain(
+        column,
+        &read_bitset,
+        &[2i32.into()]
+    )?);
+    Ok(())
+}
+
+fn test_dictionary(max_dict_size: usize) -> Result<()> {
+    let array = vec![Some(1), None, Some(2), Some(1), None, Some(3), Some(2)];
+
+    let options = WriteOptions {
+        write_statistics: false,
+        version: Version::V1,
+        ..Default::default()
+    };
+
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col".to_string(),
+            PhysicalType::Int32,
+        )],
+    );
+
+    let pages = array_to_dict_pages_v1(
+        &array,
+        &options,
+        &schema.columns()[0].descriptor,
+        max_dict_size,
+    )?;
+    let pages = DynStreamingIterator::new(Compressor::new_from_vec(
+        DynIter::new(pages.into_iter().map(Ok)),
+        CompressionOptions::Uncompressed,
+        vec![],
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+
+    let data = writer.into_inner().into_inner();
+    let mut reader = Cursor::new(data);
+
+    let (result, _) = read_column(&mut reader)?;
+    assert_eq!(result, Array::Int32(array.clone()));
+
+    let metadata = read_metadata(&mut reader)?;
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+    let expected = array
+        .iter()
+        .map(|x| {
+            vec![(
+                "col".to_string(),
+                x.map(Field::Int32).unwrap_or(Field::Null),
+            )]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn dictionary() -> Result<()> {
+    test_dictionary(parquet2::write::DEFAULT_MAX_DICT_SIZE)
+}
+
+#[test]
+fn dictionary_fallback() -> Result<()> {
+    test_dictionary(4)
+}
+
+#[test]
+fn float16() -> Result<()> {
+    use parquet2::deserialize::Float16Values;
+    use parquet2::encoding::{hybrid_rle::encode_bool, Encoding};
+    use parquet2::page::{DataPage, DataPageHeader, DataPageHeaderV1};
+    use parquet2::read::{get_page_iterator, BasicDecompressor};
+    use parquet2::schema::types::PrimitiveLogicalType;
+    use parquet2::schema::Repetition;
+    use parquet2::statistics::{serialize_statistics, FixedLenStatistics};
+    use parquet2::types::ord_float16;
+    use parquet2::FallibleStreamingIterator;
+
+    // a PLAIN-encoded page of optional FLOAT16 values, given by their bits
+    fn array_to_page(array: &[Option<u16>], descriptor: &Descriptor) -> Result<Page> {
+        // the definition levels are prefixed by their length
+        let mut buffer = vec![];
+        encode_bool(&mut buffer, array.iter().map(|x| x.is_some()))?;
+        let mut buffer = [(buffer.len() as u32).to_le_bytes().to_vec(), buffer].concat();
+        array
+            .iter()
+            .flatten()
+            .for_each(|x| buffer.extend_from_slice(&x.to_le_bytes()));
+
+        let values = || array.iter().flatten().copied();
+        let statistics = FixedLenStatistics {
+            primitive_type: descriptor.primitive_type.clone(),
+            null_count: Some((array.len() - values().count()) as i64),
+            distinct_count: None,
+            max_value: values()
+                .max_by(|x, y| ord_float16(*x, *y))
+                .map(|x| x.to_le_bytes().to_vec()),
+            min_value: values()
+                .min_by(|x, y| ord_float16(*x, *y))
+                .map(|x| x.to_le_bytes().to_vec()),
+        };
+
+        let header = DataPageHeaderV1 {
+            num_values: array.len() as i32,
+            encoding: Encoding::Plain.into(),
+            definition_level_encoding: Encoding::Rle.into(),
+            repetition_level_encoding: Encoding::Rle.into(),
+            statistics: Some(serialize_statistics(&statistics)),
+        };
+        Ok(Page::Data(DataPage::new(
+            DataPageHeader::V1(header),
+            buffer,
+            descriptor.clone(),
+            Some(array.len()),
+        )))
+    }
+
+    // 1.0, -2.0, 0.5
+    let pages = [
+        vec![Some(0x3c00u16), Some(0xc000)],
+        vec![Some(0x3800), None],
+    ];
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        ..Default::default()
+    };
+
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::try_from_primitive(
+            "col".to_string(),
+            PhysicalType::FixedLenByteArray(2),
+            Repetition::Optional,
+            None,
+            Some(PrimitiveLogicalType::Float16),
+            None,
+        )?],
+    );
+
+    let descriptor = &schema.columns()[0].descriptor;
+    let pages = pages
+        .iter()
+        .map(|array| array_to_page(array, descriptor))
+        .collect::<Vec<_>>();
+    let pages = DynStreamingIterator::new(Compressor::new_from_vec(
+        DynIter::new(pages.into_iter()),
+        CompressionOptions::Uncompressed,
+        vec![],
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+
+    let data = writer.into_inner().into_inner();
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    // compared as floats: byte-wise, -2.0 would be the max
+    let statistics = column.statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<FixedLenStatistics>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(0xc000u16.to_le_bytes().to_vec()));
+    assert_eq!(statistics.max_value, Some(0x3c00u16.to_le_bytes().to_vec()));
+
+    let pages = get_page_iterator(column, &mut reader, None, vec![], usize::MAX)?;
+    let mut pages = BasicDecompressor::new(pages, vec![]);
+    let mut values = vec![];
+    while let Some(Page::Data(page)) = pages.next()? {
+        let (_, page_values) = Float16Values::try_new(page, None::<&[u8]>)?;
+        values.extend(page_values.collect::<Result<Vec<_>>>()?);
+    }
+    assert_eq!(values, [0x3c00, 0xc000, 0x3800]);
+    Ok(())
+}
+
+#[test]
+fn float16_rows() -> Result<()> {
+    use parquet2::read::{rows, Field};
+    use parquet2::schema::types::PrimitiveLogicalType;
+    use parquet2::statistics::FixedLenStatistics;
+    use parquet2::write::{RowWriter, RowWriterOptions};
+
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema {
+            OPTIONAL FIXED_LEN_BYTE_ARRAY(2) col (FLOAT16);
+        }",
+    )?;
+    let expected = [Some(1.0), Some(-2.0), Some(0.5), None]
+        .into_iter()
+        .map(|x| vec![("col".to_string(), x.map_or(Field::Null, Field::Float))])
+        .collect::<Vec<_>>();
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        ..Default::default()
+    };
+    let mut writer = RowWriter::new(
+        Cursor::new(vec![]),
+        schema,
+        options,
+        RowWriterOptions::default(),
+    );
+    for row in &expected {
+        writer.write(row)?;
+    }
+    writer.end(None)?;
+
+    let data = writer.into_inner().into_inner();
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    assert_eq!(
+        column.descriptor().descriptor.primitive_type.logical_type,
+        Some(PrimitiveLogicalType::Float16)
+    );
+
+    // compared as floats: byte-wise, -2.0 (0xc000) would be the max
+    let statistics = column.statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<FixedLenStatistics>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(vec![0x00, 0xc0]));
+    assert_eq!(statistics.max_value, Some(vec![0x00, 0x3c]));
+
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(result, expected);
+    Ok(())
+}
+
 #[cfg(feature = "async")]
 async fn test_column_async(column: &str, compression: CompressionOptions) -> Result<()> {
     let array = alltypes_plain(column);
@@ -241,6 +777,7 @@ async fn test_column_async(column: &str, compression: CompressionOptions) -> Res
     let options = WriteOptions {
         write_statistics: true,
         version: Version::V1,
+        ..Default::default()
     };
 
     // prepare schema
@@ -0,0 +1,304 @@
+use std::io::Cursor;
+
+use parquet2::encoding::hybrid_rle::encode_u32;
+use parquet2::encoding::Encoding;
+use parquet2::error::Result;
+use parquet2::metadata::{Descriptor, SchemaDescriptor};
+use parquet2::nested::{read_nested, shred, Nested, ShreddedLeaf};
+use parquet2::page::{split_buffer, DataPage, DataPageHeader, DataPageHeaderV2, Page};
+use parquet2::read::{get_column_iterator, levels::get_bit_width, read_metadata, rows, Field};
+use parquet2::schema::types::TimeUnit;
+use parquet2::write::{
+    Compressor, DynIter, DynStreamingIterator, FileWriter, Version, WriteOptions,
+};
+use parquet2::{compression::CompressionOptions, types::NativeType};
+
+fn encode_levels(levels: &[u32], max_level: i16) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+    if max_level > 0 {
+        encode_u32(
+            &mut buffer,
+            levels.iter().copied(),
+            get_bit_width(max_level),
+        )?;
+    }
+    Ok(buffer)
+}
+
+fn plain<T: NativeType>(values: &[T]) -> Vec<Vec<u8>> {
+    values
+        .iter()
+        .map(|value| value.to_le_bytes().as_ref().to_vec())
+        .collect()
+}
+
+fn plain_binary(values: &[&str]) -> Vec<Vec<u8>> {
+    values
+        .iter()
+        .map(|value| {
+            let mut slot = (value.len() as u32).to_le_bytes().to_vec();
+            slot.extend_from_slice(value.as_bytes());
+            slot
+        })
+        .collect()
+}
+
+// writes a v2 page of a leaf whose values are `slots[leaf.indices]`, plain-encoded
+fn leaf_to_page(
+    leaf: &ShreddedLeaf,
+    slots: &[Vec<u8>],
+    num_rows: usize,
+    descriptor: &Descriptor,
+) -> Result<Page> {
+    let rep_levels = encode_levels(&leaf.rep_levels, descriptor.max_rep_level)?;
+    let def_levels = encode_levels(&leaf.def_levels, descriptor.max_def_level)?;
+
+    let mut buffer = rep_levels.clone();
+    buffer.extend_from_slice(&def_levels);
+    leaf.indices
+        .iter()
+        .for_each(|index| buffer.extend_from_slice(&slots[*index]));
+
+    let header = DataPageHeaderV2 {
+        num_values: leaf.def_levels.len() as i32,
+        num_nulls: (leaf.def_levels.len() - leaf.indices.len()) as i32,
+        num_rows: num_rows as i32,
+        encoding: Encoding::Plain.into(),
+        definition_levels_byte_length: def_levels.len() as i32,
+        repetition_levels_byte_length: rep_levels.len() as i32,
+        is_compressed: Some(false),
+        statistics: None,
+    };
+
+    Ok(Page::Data(DataPage::new(
+        DataPageHeader::V2(header),
+        buffer,
+        descriptor.clone(),
+        Some(num_rows),
+    )))
+}
+
+fn values(pages: &[Page]) -> Result<Vec<i32>> {
+    let mut values = vec![];
+    for page in pages {
+        if let Page::Data(page) = page {
+            let (_, _, buffer) = split_buffer(page)?;
+            values.extend(
+                buffer
+                    .chunks_exact(4)
+                    .map(|chunk| i32::from_le_bytes(chunk.try_into().unwrap())),
+            );
+        }
+    }
+    Ok(values)
+}
+
+// writes a file with a single row group of `schema` whose fields have the `nested` values, and
+// whose leaves have the plain-encoded `slots`
+fn write(schema: &SchemaDescriptor, nested: &[Nested], slots: &[Vec<Vec<u8>>]) -> Result<Vec<u8>> {
+    let options = WriteOptions {
+        write_statistics: false,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let mut leaves = vec![];
+    for (field, nested) in schema.fields().iter().zip(nested) {
+        leaves.extend(
+            shred(field, nested)?
+                .into_iter()
+                .map(|leaf| (leaf, nested.len())),
+        );
+    }
+    let columns = leaves
+        .iter()
+        .zip(slots.iter())
+        .zip(schema.columns())
+        .map(|(((leaf, num_rows), slots), column)| {
+            let page = leaf_to_page(leaf, slots, *num_rows, &column.descriptor);
+            Ok(DynStreamingIterator::new(Compressor::new_from_vec(
+                DynIter::new(std::iter::once(page)),
+                CompressionOptions::Uncompressed,
+                vec![],
+            )))
+        })
+        .collect::<Vec<_>>();
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    writer.write(DynIter::new(columns.into_iter()))?;
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn struct_with_list() -> Result<()> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema {
+            OPTIONAL group a {
+                OPTIONAL INT32 b;
+                OPTIONAL group c (LIST) {
+                    REPEATED group list {
+                        OPTIONAL INT32 element;
+                    }
+                }
+            }
+        }",
+    )?;
+    let field = &schema.fields()[0];
+
+    // [{b: 1, c: [1, null]}, null, {b: null, c: []}, {b: 4, c: null}, {b: 5, c: [3]}]
+    let nested = Nested::Struct {
+        validity: Some(vec![true, false, true, true, true]),
+        length: 5,
+        children: vec![
+            Nested::Primitive {
+                validity: Some(vec![true, false, false, true, true]),
+                length: 5,
+            },
+            Nested::List {
+                validity: Some(vec![true, false, true, false, true]),
+                offsets: vec![0, 2, 2, 2, 2, 3],
+                child: Box::new(Nested::Primitive {
+                    validity: Some(vec![true, false, true]),
+                    length: 3,
+                }),
+            },
+        ],
+    };
+    let slots = vec![plain(&[1, 0, 0, 4, 5]), plain(&[1, 0, 3])];
+
+    let mut reader = Cursor::new(write(&schema, std::slice::from_ref(&nested), &slots)?);
+
+    let metadata = read_metadata(&mut reader)?;
+    let columns = get_column_iterator(
+        &mut reader,
+        &metadata.row_groups[0],
+        "a",
+        None,
+        vec![],
+        usize::MAX,
+    );
+    let (result, pages) = read_nested(field, columns)?;
+
+    assert_eq!(result, nested);
+    assert_eq!(values(&pages[0])?, vec![1, 4, 5]);
+    assert_eq!(values(&pages[1])?, vec![1, 3]);
+    Ok(())
+}
+
+#[test]
+fn read_rows() -> Result<()> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema {
+            REQUIRED INT32 id (INTEGER(16,true));
+            OPTIONAL BYTE_ARRAY name (STRING);
+            OPTIONAL group tags (LIST) {
+                REPEATED group list {
+                    OPTIONAL INT32 element (DATE);
+                }
+            }
+            OPTIONAL group props (MAP) {
+                REPEATED group key_value {
+                    REQUIRED BYTE_ARRAY key (UTF8);
+                    OPTIONAL INT64 value (TIMESTAMP(MILLIS,true));
+                }
+            }
+        }",
+    )?;
+
+    // [
+    //   {id: 1, name: "a", tags: [1, null], props: {"x": 10}},
+    //   {id: 2, name: null, tags: null, props: {}},
+    //   {id: 3, name: "c", tags: [], props: null},
+    // ]
+    let nested = [
+        Nested::Primitive {
+            validity: None,
+            length: 3,
+        },
+        Nested::Primitive {
+            validity: Some(vec![true, false, true]),
+            length: 3,
+        },
+        Nested::List {
+            validity: Some(vec![true, false, true]),
+            offsets: vec![0, 2, 2, 2],
+            child: Box::new(Nested::Primitive {
+                validity: Some(vec![true, false]),
+                length: 2,
+            }),
+        },
+        Nested::List {
+            validity: Some(vec![true, true, false]),
+            offsets: vec![0, 1, 1, 1],
+            child: Box::new(Nested::Struct {
+                validity: None,
+                length: 1,
+                children: vec![
+                    Nested::Primitive {
+                        validity: None,
+                        length: 1,
+                    },
+                    Nested::Primitive {
+                        validity: None,
+                        length: 1,
+                    },
+                ],
+            }),
+        },
+    ];
+    let slots = vec![
+        plain(&[1, 2, 3]),
+        plain_binary(&["a", "", "c"]),
+        plain(&[1, 0]),
+        plain_binary(&["x"]),
+        plain(&[10i64]),
+    ];
+    let data = write(&schema, &nested, &slots)?;
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+
+    let timestamp = |value| Field::Timestamp {
+        value,
+        unit: TimeUnit::Milliseconds,
+        is_adjusted_to_utc: true,
+    };
+    let expected = vec![
+        vec![
+            ("id".to_string(), Field::Int16(1)),
+            ("name".to_string(), Field::String("a".to_string())),
+            (
+                "tags".to_string(),
+                Field::List(vec![Field::Date(1), Field::Null]),
+            ),
+            (
+                "props".to_string(),
+                Field::Map(vec![(Field::String("x".to_string()), timestamp(10))]),
+            ),
+        ],
+        vec![
+            ("id".to_string(), Field::Int16(2)),
+            ("name".to_string(), Field::Null),
+            ("tags".to_string(), Field::Null),
+            ("props".to_string(), Field::Map(vec![])),
+        ],
+        vec![
+            ("id".to_string(), Field::Int16(3)),
+            ("name".to_string(), Field::String("c".to_string())),
+            ("tags".to_string(), Field::List(vec![])),
+            ("props".to_string(), Field::Null),
+        ],
+    ];
+    assert_eq!(result, expected);
+
+    // projected fields are returned in the order of the projection
+    let result =
+        rows(&mut reader, &metadata, Some(&["name", "id"]))?.collect::<Result<Vec<_>>>()?;
+    let expected = expected
+        .into_iter()
+        .map(|row| vec![row[1].clone(), row[0].clone()])
+        .collect::<Vec<_>>();
+    assert_eq!(result, expected);
+    Ok(())
+}
@@ -2,9 +2,9 @@ use parquet2::{
     encoding::Encoding,
     metadata::Descriptor,
     page::{DataPage, DataPageHeader, DataPageHeaderV1, Page},
-    statistics::{serialize_statistics, PrimitiveStatistics, Statistics},
+    statistics::{serialize_statistics, ParquetStatistics, PrimitiveStatistics, Statistics},
     types::NativeType,
-    write::WriteOptions,
+    write::{DictEncoder, WriteOptions},
     {encoding::hybrid_rle::encode_bool, error::Result},
 };
 
@@ -40,16 +40,12 @@ fn unzip_option<T: NativeType>(array: &[Option<T>]) -> Result<(Vec<u8>, Vec<u8>)
     Ok((values, validity))
 }
 
-pub fn array_to_page_v1<T: NativeType>(
+fn statistics<T: NativeType>(
     array: &[Option<T>],
     options: &WriteOptions,
     descriptor: &Descriptor,
-) -> Result<Page> {
-    let (values, mut buffer) = unzip_option(array)?;
-
-    buffer.extend_from_slice(&values);
-
-    let statistics = if options.write_statistics {
+) -> Option<ParquetStatistics> {
+    if options.write_statistics {
         let statistics = &PrimitiveStatistics {
             primitive_type: descriptor.primitive_type.clone(),
             null_count: Some((array.len() - array.iter().flatten().count()) as i64),
@@ -60,7 +56,19 @@ pub fn array_to_page_v1<T: NativeType>(
         Some(serialize_statistics(statistics))
     } else {
         None
-    };
+    }
+}
+
+pub fn array_to_page_v1<T: NativeType>(
+    array: &[Option<T>],
+    options: &WriteOptions,
+    descriptor: &Descriptor,
+) -> Result<Page> {
+    let (values, mut buffer) = unzip_option(array)?;
+
+    buffer.extend_from_slice(&values);
+
+    let statistics = statistics(array, options, descriptor);
 
     let header = DataPageHeaderV1 {
         num_values: array.len() as i32,
@@ -77,3 +85,41 @@ pub fn array_to_page_v1<T: NativeType>(
         Some(array.len()),
     )))
 }
+
+/// Encodes `array` into a dictionary page followed by a data page, falling back to
+/// plain encoding when the dictionary exceeds `max_dict_size`.
+pub fn array_to_dict_pages_v1<T: NativeType>(
+    array: &[Option<T>],
+    options: &WriteOptions,
+    descriptor: &Descriptor,
+    max_dict_size: usize,
+) -> Result<Vec<Page>> {
+    let (_, mut buffer) = unzip_option(array)?;
+
+    let mut encoder = DictEncoder::try_new(T::TYPE, max_dict_size)?;
+    let values = array.iter().flatten().copied().collect::<Vec<_>>();
+    let values = encoder.encode_native(&values)?;
+    buffer.extend_from_slice(values.buffer());
+
+    let header = DataPageHeaderV1 {
+        num_values: array.len() as i32,
+        encoding: values.encoding().into(),
+        definition_level_encoding: Encoding::Rle.into(),
+        repetition_level_encoding: Encoding::Rle.into(),
+        statistics: statistics(array, options, descriptor),
+    };
+
+    let data = Page::Data(DataPage::new(
+        DataPageHeader::V1(header),
+        buffer,
+        descriptor.clone(),
+        Some(array.len()),
+    ));
+
+    Ok(encoder
+        .into_dict_page()
+        .map(Page::Dict)
+        .into_iter()
+        .chain(std::iter::once(data))
+        .collect())
+}
@@ -0,0 +1,360 @@
+use std::io::Cursor;
+
+use parquet2::compression::CompressionOptions;
+use parquet2::error::{Error, Result};
+use parquet2::metadata::{SchemaDescriptor, SortingColumn};
+use parquet2::page::CompressedPage;
+use parquet2::read::{get_page_iterator, read_metadata, rows, Field, Row};
+use parquet2::schema::types::TimeUnit;
+use parquet2::statistics::{BinaryStatistics, PrimitiveStatistics};
+use parquet2::write::{extract_row_groups, RowWriter, RowWriterOptions, Version, WriteOptions};
+
+fn schema() -> Result<SchemaDescriptor> {
+    SchemaDescriptor::try_from_message(
+        "message schema {
+            REQUIRED INT64 id;
+            OPTIONAL BYTE_ARRAY name (STRING);
+            OPTIONAL BOOLEAN flag;
+            OPTIONAL FIXED_LEN_BYTE_ARRAY(4) amount (DECIMAL(9,2));
+            OPTIONAL group tags (LIST) {
+                REPEATED group list {
+                    OPTIONAL INT32 element (DATE);
+                }
+            }
+            OPTIONAL group props (MAP) {
+                REPEATED group key_value {
+                    REQUIRED BYTE_ARRAY key (UTF8);
+                    OPTIONAL INT64 value (TIMESTAMP(MILLIS,true));
+                }
+            }
+            OPTIONAL group point {
+                REQUIRED DOUBLE x;
+                OPTIONAL DOUBLE y;
+            }
+            OPTIONAL INT96 ts;
+        }",
+    )
+}
+
+fn row(i: i64) -> Row {
+    let timestamp = |value| Field::Timestamp {
+        value,
+        unit: TimeUnit::Milliseconds,
+        is_adjusted_to_utc: true,
+    };
+    let null_or = |field| if i % 3 == 1 { Field::Null } else { field };
+    vec![
+        ("id".to_string(), Field::Int64(i)),
+        (
+            "name".to_string(),
+            null_or(Field::String(format!("name {}", i % 4))),
+        ),
+        ("flag".to_string(), null_or(Field::Bool(i % 2 == 0))),
+        (
+            "amount".to_string(),
+            null_or(Field::Decimal {
+                value: (i as i128 - 2) * 150,
+                precision: 9,
+                scale: 2,
+            }),
+        ),
+        (
+            "tags".to_string(),
+            match i % 3 {
+                0 => Field::List((0..i as i32 % 4).map(Field::Date).collect()),
+                1 => Field::Null,
+                _ => Field::List(vec![Field::Null, Field::Date(i as i32)]),
+            },
+        ),
+        (
+            "props".to_string(),
+            null_or(Field::Map(vec![
+                (Field::String("a".to_string()), timestamp(i)),
+                (Field::String("b".to_string()), Field::Null),
+            ])),
+        ),
+        (
+            "point".to_string(),
+            null_or(Field::Group(vec![
+                ("x".to_string(), Field::Double(i as f64)),
+                ("y".to_string(), Field::Null),
+            ])),
+        ),
+        (
+            "ts".to_string(),
+            null_or(Field::Timestamp {
+                value: i * 3_600_000_000_000 + i,
+                unit: TimeUnit::Nanoseconds,
+                is_adjusted_to_utc: false,
+            }),
+        ),
+    ]
+}
+
+fn round_trip(options: WriteOptions, row_options: RowWriterOptions) -> Result<()> {
+    let expected = (0..10).map(row).collect::<Vec<_>>();
+
+    let mut writer = RowWriter::new(Cursor::new(vec![]), schema()?, options, row_options);
+    for row in &expected {
+        writer.write(row)?;
+    }
+    writer.end(None)?;
+    let data = writer.into_inner().into_inner();
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    assert_eq!(metadata.num_rows, 10);
+    assert_eq!(
+        metadata.row_groups.len(),
+        10usize.div_ceil(row_options.max_rows_per_row_group)
+    );
+
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn round_trip_v1() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        ..Default::default()
+    };
+    round_trip(options, RowWriterOptions::default())?;
+
+    let mut writer = RowWriter::new(
+        Cursor::new(vec![]),
+        schema()?,
+        options,
+        RowWriterOptions::default(),
+    );
+    (0..10).try_for_each(|i| writer.write(&row(i)))?;
+    writer.end(None)?;
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+
+    let columns = metadata.row_groups[0].columns();
+    let statistics = columns[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<PrimitiveStatistics<i64>>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(0));
+    assert_eq!(statistics.max_value, Some(9));
+
+    let statistics = columns[1].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<BinaryStatistics>()
+        .unwrap();
+    assert_eq!(statistics.null_count, Some(3));
+    assert_eq!(statistics.min_value, Some(b"name 0".to_vec()));
+    assert_eq!(statistics.max_value, Some(b"name 3".to_vec()));
+    Ok(())
+}
+
+#[test]
+fn round_trip_v2_plain_snappy() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: false,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let row_options = RowWriterOptions {
+        compression: CompressionOptions::Snappy,
+        dictionary: false,
+        ..Default::default()
+    };
+    round_trip(options, row_options)
+}
+
+#[test]
+fn row_groups_and_pages() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let row_options = RowWriterOptions {
+        max_rows_per_row_group: 4,
+        max_page_size: 1,
+        ..Default::default()
+    };
+    round_trip(options, row_options)?;
+
+    let mut writer = RowWriter::new(Cursor::new(vec![]), schema()?, options, row_options);
+    (0..3).try_for_each(|i| writer.write(&row(i)))?;
+    assert_eq!(writer.num_buffered_rows(), 3);
+    writer.end(None)?;
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+
+    // every row is in its own page, after the dictionary page
+    let column = &metadata.row_groups[0].columns()[0];
+    let pages = get_page_iterator(column, &mut reader, None, vec![], usize::MAX)?
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(pages.len(), 4);
+    assert!(matches!(pages[0], CompressedPage::Dict(_)));
+    Ok(())
+}
+
+#[test]
+fn invalid_rows() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let mut writer = RowWriter::new(
+        Cursor::new(vec![]),
+        schema()?,
+        options,
+        RowWriterOptions::default(),
+    );
+
+    let mut invalid = row(0);
+    invalid[0].1 = Field::Null;
+    assert!(matches!(
+        writer.write(&invalid),
+        Err(Error::InvalidParameter(_))
+    ));
+
+    let mut invalid = row(0);
+    invalid[6].1 = Field::Group(vec![("x".to_string(), Field::String("a".to_string()))]);
+    assert!(writer.write(&invalid).is_err());
+
+    let mut invalid = row(0);
+    invalid.swap(1, 2);
+    assert!(writer.write(&invalid).is_err());
+
+    // a decimal that does not fit in 4 bytes
+    let mut invalid = row(0);
+    invalid[3].1 = Field::Decimal {
+        value: i64::MAX as i128,
+        precision: 9,
+        scale: 2,
+    };
+    assert!(writer.write(&invalid).is_err());
+
+    // invalid rows are not buffered
+    assert_eq!(writer.num_buffered_rows(), 0);
+    writer.write(&row(0))?;
+    writer.end(None)?;
+
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+    let result = rows(&mut reader, &metadata, None)?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(result, vec![row(0)]);
+    Ok(())
+}
+
+// rows sorted by `tenant`, with nulls last, and then by `ts`
+fn write_sorted(
+    tenants: &[Option<i32>],
+    sorting_columns: Option<Vec<SortingColumn>>,
+    verify: bool,
+) -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema {
+            OPTIONAL INT32 tenant;
+            REQUIRED INT64 ts;
+        }",
+    )?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let row_options = RowWriterOptions {
+        max_rows_per_row_group: 6,
+        max_page_size: 8,
+        ..Default::default()
+    };
+    let mut writer = RowWriter::new(Cursor::new(vec![]), schema, options, row_options);
+    writer.set_sorting_columns(sorting_columns, verify)?;
+    for (ts, tenant) in tenants.iter().enumerate() {
+        let tenant = tenant.map(Field::Int32).unwrap_or(Field::Null);
+        writer.write(&vec![
+            ("tenant".to_string(), tenant),
+            ("ts".to_string(), Field::Int64(ts as i64)),
+        ])?;
+    }
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn sorting_columns() -> Result<()> {
+    let sorting_columns = vec![
+        SortingColumn::new(0, false, false),
+        SortingColumn::new(1, false, false),
+    ];
+    let tenants = [0, 0, 0, 1, 1, 2, 2, 2, 3]
+        .into_iter()
+        .map(Some)
+        .chain([None, None])
+        .collect::<Vec<_>>();
+    let data = write_sorted(&tenants, Some(sorting_columns.clone()), true)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    assert_eq!(metadata.row_groups.len(), 2);
+    for row_group in &metadata.row_groups {
+        assert_eq!(
+            row_group.sorting_columns(),
+            Some(sorting_columns.as_slice())
+        );
+    }
+
+    // extracted row groups keep their sorting columns
+    let mut writer = Cursor::new(vec![]);
+    extract_row_groups(&mut reader, &metadata, &[1], &mut writer)?;
+    let metadata = read_metadata(&mut Cursor::new(writer.into_inner()))?;
+    assert_eq!(
+        metadata.row_groups[0].sorting_columns(),
+        Some(sorting_columns.as_slice())
+    );
+
+    // descending with nulls first
+    let sorting_columns = vec![SortingColumn::new(0, true, true)];
+    let tenants = [None, Some(3), Some(2), Some(2), Some(0)];
+    let data = write_sorted(&tenants, Some(sorting_columns.clone()), true)?;
+    let metadata = read_metadata(&mut Cursor::new(data))?;
+    assert_eq!(
+        metadata.row_groups[0].sorting_columns(),
+        Some(sorting_columns.as_slice())
+    );
+
+    let data = write_sorted(&tenants, None, true)?;
+    let metadata = read_metadata(&mut Cursor::new(data))?;
+    assert_eq!(metadata.row_groups[0].sorting_columns(), None);
+
+    assert!(matches!(
+        write_sorted(
+            &tenants,
+            Some(vec![SortingColumn::new(2, false, false)]),
+            false
+        ),
+        Err(Error::InvalidParameter(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn unsorted_pages() -> Result<()> {
+    let tenants = [Some(0), Some(1), Some(0)];
+    let sorting_columns = vec![SortingColumn::new(0, false, false)];
+    assert!(matches!(
+        write_sorted(&tenants, Some(sorting_columns.clone()), true),
+        Err(Error::InvalidParameter(_))
+    ));
+
+    // the declaration is only verified on demand
+    let data = write_sorted(&tenants, Some(sorting_columns.clone()), false)?;
+    let metadata = read_metadata(&mut Cursor::new(data))?;
+    assert_eq!(
+        metadata.row_groups[0].sorting_columns(),
+        Some(sorting_columns.as_slice())
+    );
+    Ok(())
+}